@@ -0,0 +1,32 @@
+// #[task] attribute of rust-task-1, use it through rust_task_1::task
+mod task_attributes;
+
+mod task_expansion;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, ItemFn};
+
+use task_attributes::TaskAttributes;
+
+/*
+    Declares a registered task from an async fn, like Celery's @app.task:
+
+    #[task(name = "send_email", priority = High, retries = 3, timeout = "10s", rate_limit = "10/m")]
+    async fn send_email(args: EmailArgs) -> Result<(), String> { ... }
+
+    - send_email.register(&mut registry) => registry entry with the options (TaskConfig)
+    - send_email.delay(&mut proxy, args) => typed submit through the Proxy, args are serialized
+    - send_email::call(args).await => the fn itself
+    Every option is optional, the default name is the name of the fn.
+*/
+#[proc_macro_attribute]
+pub fn task(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut attributes = TaskAttributes::default();
+    let parser = syn::meta::parser(|meta| attributes.parse(meta));
+    parse_macro_input!(attr with parser);
+
+    let task_fn = parse_macro_input!(item as ItemFn);
+    task_expansion::expand(attributes, task_fn)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+use syn::{meta::ParseNestedMeta, Ident, LitInt, LitStr};
+
+const PRIORITIES: [&str; 4] = ["None", "Low", "Medium", "High"];
+
+// Options of #[task(...)], checked at compile time so a typo is a compile error ("10/m" by a const in the expansion)
+#[derive(Default)]
+pub struct TaskAttributes {
+    pub name: Option<LitStr>,
+    pub priority: Option<Ident>,
+    pub retries: Option<u32>,
+    pub timeout: Option<Duration>,
+    pub rate_limit: Option<LitStr>, // "10/m", parsed by TaskRateLimit of rust-task-1
+}
+
+impl TaskAttributes {
+    pub fn parse(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("name") {
+            self.name = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("priority") {
+            let priority: Ident = meta.value()?.parse()?;
+            if !PRIORITIES.iter().any(|known| priority == known) {
+                return Err(syn::Error::new(priority.span(), format!("priority must be one of {}", PRIORITIES.join(", "))));
+            }
+            self.priority = Some(priority);
+        } else if meta.path.is_ident("retries") {
+            let retries: LitInt = meta.value()?.parse()?;
+            self.retries = Some(retries.base10_parse()?);
+        } else if meta.path.is_ident("timeout") {
+            let timeout: LitStr = meta.value()?.parse()?;
+            let parsed = humantime::parse_duration(&timeout.value())
+                .map_err(|error| syn::Error::new(timeout.span(), format!("invalid timeout: {}", error)))?;
+            self.timeout = Some(parsed);
+        } else if meta.path.is_ident("rate_limit") {
+            self.rate_limit = Some(meta.value()?.parse()?);
+        } else {
+            return Err(meta.error("unknown task option, expected name, priority, retries, timeout or rate_limit"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse::Parser;
+
+    fn parse(tokens: proc_macro2::TokenStream) -> syn::Result<TaskAttributes> {
+        let mut attributes = TaskAttributes::default();
+        syn::meta::parser(|meta| attributes.parse(meta)).parse2(tokens)?;
+        Ok(attributes)
+    }
+
+    #[test]
+    fn test_every_option_should_be_parsed() {
+        let attributes = parse(quote::quote!(name = "send_email", priority = High, retries = 3, timeout = "1m 30s", rate_limit = "10/m")).unwrap();
+
+        assert_eq!(attributes.name.unwrap().value(), "send_email");
+        assert_eq!(attributes.priority.unwrap(), "High");
+        assert_eq!(attributes.retries, Some(3));
+        assert_eq!(attributes.timeout, Some(Duration::from_secs(90)));
+        assert_eq!(attributes.rate_limit.unwrap().value(), "10/m");
+    }
+
+    #[test]
+    fn test_invalid_options_should_be_compile_errors() {
+        assert!(parse(quote::quote!(priority = Urgent)).is_err());
+        assert!(parse(quote::quote!(timeout = "soon")).is_err());
+        assert!(parse(quote::quote!(rate_limit = 10)).is_err());
+        assert!(parse(quote::quote!(queue = "emails")).is_err());
+        assert!(parse(quote::quote!()).unwrap().name.is_none());
+    }
+}
@@ -0,0 +1,114 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::{FnArg, Ident, ItemFn, LitStr, Pat, Type};
+
+use crate::task_attributes::TaskAttributes;
+
+/*
+    async fn send_email(args: EmailArgs) becomes a unit struct `send_email` (like in celery-rs):
+    - the fn body moves to send_email::call()
+    - the args of the registered task are the fn args => one arg is sent as it is, more args as a tuple
+*/
+pub fn expand(attributes: TaskAttributes, task_fn: ItemFn) -> syn::Result<TokenStream> {
+    let signature = &task_fn.sig;
+    if signature.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(signature.fn_token, "#[task] can only be used on an async fn"));
+    }
+    if !signature.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&signature.generics, "#[task] fn can't be generic, its args are deserialized from JSON"));
+    }
+
+    let (arg_names, arg_types) = task_args(&task_fn)?;
+    let args_type = match arg_types.as_slice() {
+        [] => quote!(()),
+        [arg_type] => quote!(#arg_type),
+        arg_types => quote!((#(#arg_types),*)),
+    };
+    let args_value = match arg_names.as_slice() {
+        [arg_name] => quote!(#arg_name),
+        arg_names => quote!((#(#arg_names),*)),
+    };
+
+    let task_ident = &signature.ident;
+    let name = attributes.name.unwrap_or_else(|| LitStr::new(&task_ident.to_string(), task_ident.span()));
+    let config = task_config(&attributes.priority, attributes.retries, attributes.timeout, attributes.rate_limit);
+
+    let vis = &task_fn.vis;
+    let fn_attrs = &task_fn.attrs;
+    let doc_attrs = task_fn.attrs.iter().filter(|attr| attr.path().is_ident("doc"));
+    let call_signature = syn::Signature { ident: format_ident!("call"), ..signature.clone() };
+    let body = &task_fn.block;
+
+    Ok(quote! {
+        #(#doc_attrs)*
+        #[allow(non_camel_case_types)]
+        #[derive(Debug, Clone, Copy)]
+        #vis struct #task_ident;
+
+        impl #task_ident {
+            pub const NAME: &'static str = #name;
+
+            #(#fn_attrs)*
+            pub #call_signature #body
+
+            pub fn config() -> ::rust_task_1::registry::task_config::TaskConfig {
+                #config
+            }
+
+            pub fn register(self, registry: &mut ::rust_task_1::registry::task_registry::TaskRegistry) -> &mut ::rust_task_1::registry::task_registry::TaskRegistry {
+                registry.register_with_config(Self::NAME, Self::config(), |#args_value: #args_type| Self::call(#(#arg_names),*))
+            }
+
+            // Err(UnknownTaskName) when the task is not registered in the registry of the proxy
+            pub fn delay(self, proxy: &mut ::rust_task_1::core::proxy::Proxy, #(#arg_names: #arg_types),*)
+                -> ::std::result::Result<::rust_task_1::core::task_handle::TaskHandle<(), ::std::string::String>, ::rust_task_1::error_handler::error_handler::ExecutorError>
+            {
+                proxy.send_task(Self::NAME, #args_value)
+            }
+        }
+    })
+}
+
+// Names of the fn args (argN for patterns), `self` is not allowed
+fn task_args(task_fn: &ItemFn) -> syn::Result<(Vec<Ident>, Vec<Type>)> {
+    let mut names = Vec::new();
+    let mut types = Vec::new();
+
+    for (index, input) in task_fn.sig.inputs.iter().enumerate() {
+        let FnArg::Typed(arg) = input else {
+            return Err(syn::Error::new_spanned(input, "#[task] fn can't take self"));
+        };
+        let name = match arg.pat.as_ref() {
+            Pat::Ident(pat) if pat.subpat.is_none() => pat.ident.clone(),
+            _ => format_ident!("arg{}", index),
+        };
+        names.push(name);
+        types.push(arg.ty.as_ref().clone());
+    }
+    Ok((names, types))
+}
+
+fn task_config(priority: &Option<Ident>, retries: Option<u32>, timeout: Option<std::time::Duration>, rate_limit: Option<LitStr>) -> TokenStream {
+    let priority = priority.clone().unwrap_or_else(|| format_ident!("None"));
+    let mut config = quote!(::rust_task_1::registry::task_config::TaskConfig::new(::rust_task_1::priority::priority::Priority::#priority));
+
+    if let Some(retries) = retries {
+        config = quote!(#config.with_retries(#retries));
+    }
+    if let Some(timeout) = timeout {
+        let nanos = timeout.as_nanos() as u64;
+        config = quote!(#config.with_timeout(::std::time::Duration::from_nanos(#nanos)));
+    }
+    if let Some(rate_limit) = rate_limit {
+        // Checked by the parser of TaskRateLimit in a const => an invalid literal fails the build, pointing at the attribute
+        let rate_limit_type = quote!(::rust_task_1::rate_limiting::task_rate_limit::TaskRateLimit);
+        let check = quote_spanned! {rate_limit.span()=>
+            const _: () = ::std::assert!(#rate_limit_type::parse_rate_limit(#rate_limit).is_some(), "rate_limit must be like \"10/s\", \"10/m\" or \"10/h\"");
+        };
+        config = quote!(#config.with_rate_limit({
+            #check
+            #rate_limit.parse::<#rate_limit_type>().unwrap()
+        }));
+    }
+    config
+}
@@ -0,0 +1,155 @@
+use std::{convert::Infallible, time::Instant};
+
+use crate::core::{executor::AsyncExecutor, job::Job, submit_mode::SubmitMode, task_handle::{task_handle, TaskHandle}};
+use crate::error_handler::error_handler::{fail_gracefully, ExecutorError, TaskError};
+use crate::priority::priority::Priority;
+use crate::rate_limiting::slot_rate_limiter::SlotRateLimiter;
+
+use super::types::{ChainContinuation, ChainRunner};
+
+/*
+    Celery-like chain => steps are executed one after another, the output of each step is the input of the next one
+        chain(fetch_user()).then(|user| send_email(user)).then(|sent| log_result(sent))
+    - Every step is a separate task => goes through the rate limiter and the worker and is counted in the metrics
+    - The next step is submitted only when the previous one is finished
+    - When a step fails (timeout, panic, cancel) the remaining steps are not executed and the handle gets the error
+*/
+pub struct Chain<T> {
+    run: ChainRunner<T>,
+}
+
+pub fn chain<F, T>(first: F) -> Chain<T>
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    Chain {
+        run: Box::new(move |executor, priority, continuation| {
+            submit_step(&executor, priority, first, SubmitMode::Blocking)?.on_complete(continuation);
+            Ok(())
+        }),
+    }
+}
+
+impl<T: Send + 'static> Chain<T> {
+    pub fn then<S, Fut, U>(self, step: S) -> Chain<U>
+    where
+        S: FnOnce(T) -> Fut + Send + 'static,
+        Fut: Future<Output = U> + Send + 'static,
+        U: Send + 'static,
+    {
+        let previous = self.run;
+
+        Chain {
+            run: Box::new(move |executor, priority, continuation: ChainContinuation<U>| {
+                let next_executor = executor.clone();
+                let next_priority = priority.clone();
+
+                previous(executor, priority, Box::new(move |result| match result {
+                    // Called on a worker => the step must not wait for the rate limit or free space in the queue
+                    Ok(output) => match submit_follow_up(&next_executor, next_priority, step(output)) {
+                        Ok(handle) => handle.on_complete(continuation),
+                        Err(error) => {
+                            fail_gracefully(error, "Chain step was not sent to the workers");
+                            continuation(Err(TaskError::Cancelled));
+                        }
+                    },
+                    Err(reason) => continuation(Err(reason)), // Remaining steps are skipped
+                }))
+            }),
+        }
+    }
+
+    pub fn apply(self, executor: &AsyncExecutor, priority: Priority) -> Result<TaskHandle<T>, ExecutorError> {
+        let (completer, handle) = task_handle::<T, Infallible>();
+
+        (self.run)(executor.clone(), priority, Box::new(move |result| completer.complete_with_result(result)))?;
+        Ok(handle)
+    }
+}
+
+// One step of the canvas is a normal task => rate limited and delayed to the executor
+pub(crate) fn submit_step<F, T>(executor: &AsyncExecutor, priority: Priority, fut: F, mode: SubmitMode) -> Result<TaskHandle<T>, ExecutorError>
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    let (job, handle) = Job::with_handle(fut);
+    SlotRateLimiter::submit_rate_limited(executor, priority, job, mode)?;
+    Ok(handle)
+}
+
+// Next chain step or chord callback, submitted from a worker => handed to the DelayedScheduler as due now,
+// its thread waits for the rate limit and the queue space instead of the worker
+pub(crate) fn submit_follow_up<F, T>(executor: &AsyncExecutor, priority: Priority, fut: F) -> Result<TaskHandle<T>, ExecutorError>
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    let (job, handle) = Job::with_handle(fut);
+    executor.delay_at(Instant::now(), priority, job)?;
+    Ok(handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
+    use std::time::Duration;
+
+    use super::*;
+    use crate::core::proxy::Proxy;
+
+    #[test]
+    fn test_chain_should_pipe_outputs() {
+        let mut proxy = Proxy::new().unwrap();
+
+        let handle = proxy.chain(
+            chain(async { 1 })
+                .then(|number| async move { number + 1 })
+                .then(|number| async move { format!("result: {}", number) }),
+            Priority::None,
+        ).unwrap();
+
+        assert_eq!(handle.join(), Ok(String::from("result: 2")));
+        proxy.await_completion().unwrap();
+        assert_eq!(proxy.get_metrics().get_tasks_count(), 3);
+    }
+
+    #[test]
+    fn test_chain_should_stop_after_failed_step() {
+        let mut proxy = Proxy::new().unwrap();
+        let last_step_called = Arc::new(AtomicBool::new(false));
+        let last_step_called_clone = last_step_called.clone();
+
+        let handle = proxy.chain(
+            chain(async { 1 })
+                .then(|_: i32| async move { panic!("step failed") })
+                .then(move |_: ()| async move { last_step_called_clone.store(true, Ordering::SeqCst) }),
+            Priority::None,
+        ).unwrap();
+
+        assert_eq!(handle.join(), Err(TaskError::Panicked(String::from("step failed"))));
+        proxy.await_completion().unwrap();
+        assert!(!last_step_called.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_chain_steps_should_wait_for_the_rate_limit() {
+        let config = crate::executor_config::ExecutorConfigBuilder::new().with_rate_limit_per_sec(2).build().unwrap();
+        let mut proxy = Proxy::with_config(config).unwrap();
+        let first_start = std::time::Instant::now();
+
+        // Every step reports how long after the first submission it started
+        let handle = proxy.chain(
+            chain(async move { vec![first_start.elapsed()] })
+                .then(move |mut starts: Vec<Duration>| async move { starts.push(first_start.elapsed()); starts })
+                .then(move |mut starts: Vec<Duration>| async move { starts.push(first_start.elapsed()); starts }),
+            Priority::None,
+        ).unwrap();
+
+        let starts = handle.join().unwrap();
+        proxy.await_completion().unwrap();
+        assert!(starts[1] < Duration::from_millis(900));
+        assert!(starts[2] >= Duration::from_millis(900)); // Third step needs the slot of the first one
+    }
+}
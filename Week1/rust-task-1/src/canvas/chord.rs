@@ -0,0 +1,74 @@
+use std::convert::Infallible;
+
+use crate::core::{executor::AsyncExecutor, task_handle::{task_handle, TaskHandle, TaskResult}};
+use crate::error_handler::error_handler::{fail_gracefully, ExecutorError, TaskError};
+use crate::priority::priority::Priority;
+
+use super::{chain::submit_follow_up, group::Group, types::{ChordCallback, GroupTask}};
+
+/*
+    Celery-like chord => group of tasks and a callback that is executed after all of them are finished
+    - The callback gets the results of the group (in order) and is executed as a normal task
+*/
+pub struct Chord<T, U> {
+    header: Group<T>,
+    callback: ChordCallback<T, U>,
+}
+
+pub fn chord<T, C, Fut, U>(header: Group<T>, callback: C) -> Chord<T, U>
+where
+    T: Send + 'static,
+    C: FnOnce(Vec<TaskResult<T>>) -> Fut + Send + 'static,
+    Fut: Future<Output = U> + Send + 'static,
+    U: Send + 'static,
+{
+    Chord {
+        header,
+        callback: Box::new(move |results| Box::pin(callback(results)) as GroupTask<U>),
+    }
+}
+
+impl<T: Send + 'static, U: Send + 'static> Chord<T, U> {
+    pub fn apply(self, executor: &AsyncExecutor, priority: Priority) -> Result<TaskHandle<U>, ExecutorError> {
+        let (completer, handle) = task_handle::<U, Infallible>();
+
+        let callback = self.callback;
+        let callback_executor = executor.clone();
+        let callback_priority = priority.clone();
+
+        self.header.apply(executor, priority)?.on_complete(move |results| match results {
+            Ok(results) => match submit_follow_up(&callback_executor, callback_priority, callback(results)) {
+                Ok(callback_handle) => callback_handle.on_complete(move |result| completer.complete_with_result(result)),
+                Err(error) => {
+                    fail_gracefully(error, "Chord callback was not sent to the workers");
+                    completer.complete_with_error(TaskError::Cancelled);
+                }
+            },
+            Err(reason) => completer.complete_with_error(reason),
+        });
+        Ok(handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{canvas::group::group, core::proxy::Proxy};
+
+    #[test]
+    fn test_chord_callback_should_receive_group_results() {
+        let mut proxy = Proxy::new().unwrap();
+
+        let header = group((1..=4).map(|number| async move { number * 10 }));
+        let handle = proxy.chord(
+            chord(header, |results: Vec<TaskResult<u32>>| async move {
+                results.into_iter().map(|result| result.unwrap_or(0)).sum::<u32>()
+            }),
+            Priority::High,
+        ).unwrap();
+
+        assert_eq!(handle.join(), Ok(100));
+        proxy.await_completion().unwrap();
+        assert_eq!(proxy.get_metrics().get_tasks_count(), 5); // 4 group tasks + callback
+    }
+}
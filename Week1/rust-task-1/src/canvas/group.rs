@@ -0,0 +1,122 @@
+use std::{convert::Infallible, sync::{Arc, Mutex}};
+
+use crate::core::{executor::AsyncExecutor, submit_mode::SubmitMode, task_handle::{task_handle, TaskCompleter, TaskHandle, TaskResult}};
+use crate::error_handler::error_handler::ExecutorError;
+use crate::priority::priority::Priority;
+
+use super::{chain::submit_step, types::GroupTask};
+
+/*
+    Celery-like group => all tasks are executed in parallel
+    - The handle returns the results in the same order as the tasks were added
+    - Failed task does not stop the others, its result is Err(TaskError)
+*/
+pub struct Group<T> {
+    tasks: Vec<GroupTask<T>>,
+}
+
+pub fn group<I, F, T>(tasks: I) -> Group<T>
+where
+    I: IntoIterator<Item = F>,
+    F: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    Group {
+        tasks: tasks.into_iter().map(|task| Box::pin(task) as GroupTask<T>).collect(),
+    }
+}
+
+struct GroupResults<T> {
+    results: Vec<Option<TaskResult<T>>>,
+    remaining: usize,
+}
+
+// Collects the results of the group tasks, completes the group handle when the last one is finished
+struct GroupCollector<T> {
+    results: Mutex<GroupResults<T>>,
+    completer: TaskCompleter<Vec<TaskResult<T>>>,
+}
+
+impl<T: Send + 'static> GroupCollector<T> {
+    fn collect(&self, idx: usize, result: TaskResult<T>) {
+        let mut group_results = self.results.lock().unwrap();
+        group_results.results[idx] = Some(result);
+        group_results.remaining -= 1;
+
+        if group_results.remaining == 0 {
+            let results = group_results.results.drain(..).map(|result| result.expect("Every group task has a result")).collect();
+            drop(group_results);
+
+            self.completer.complete(results);
+        }
+    }
+}
+
+impl<T: Send + 'static> Group<T> {
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    // Err when a task of the group is not accepted, the tasks sent before it still run
+    pub fn apply(self, executor: &AsyncExecutor, priority: Priority) -> Result<TaskHandle<Vec<TaskResult<T>>>, ExecutorError> {
+        let (completer, handle) = task_handle::<Vec<TaskResult<T>>, Infallible>();
+
+        let total_tasks = self.tasks.len();
+        if total_tasks == 0 {
+            completer.complete(Vec::new());
+            return Ok(handle);
+        }
+
+        let collector = Arc::new(GroupCollector {
+            results: Mutex::new(GroupResults { results: (0..total_tasks).map(|_| None).collect(), remaining: total_tasks }),
+            completer,
+        });
+
+        for (idx, task) in self.tasks.into_iter().enumerate() {
+            let collector = collector.clone();
+            submit_step(executor, priority.clone(), task, SubmitMode::Blocking)?.on_complete(move |result| collector.collect(idx, result));
+        }
+        Ok(handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use futures_timer::Delay;
+
+    use super::*;
+    use crate::core::proxy::Proxy;
+
+    #[test]
+    fn test_group_should_return_results_in_order() {
+        let mut proxy = Proxy::new().unwrap();
+        let start = Instant::now();
+
+        let tasks = (1..=3).map(|number| async move {
+            Delay::new(Duration::from_millis(400 - number * 100)).await;
+            number
+        });
+        let handle = proxy.group(group(tasks), Priority::None).unwrap();
+
+        assert_eq!(handle.join(), Ok(vec![Ok(1), Ok(2), Ok(3)]));
+        assert!(start.elapsed() < Duration::from_millis(900)); // Tasks run in parallel
+
+        proxy.await_completion().unwrap();
+        assert_eq!(proxy.get_metrics().get_tasks_count(), 3);
+    }
+
+    #[test]
+    fn test_empty_group_should_finish_immediately() {
+        let mut proxy = Proxy::new().unwrap();
+
+        let handle = proxy.group(group(Vec::<GroupTask<u32>>::new()), Priority::None).unwrap();
+
+        assert_eq!(handle.join(), Ok(vec![]));
+    }
+}
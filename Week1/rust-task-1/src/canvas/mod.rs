@@ -0,0 +1,4 @@
+pub mod chain;
+pub mod group;
+pub mod chord;
+pub mod types;
\ No newline at end of file
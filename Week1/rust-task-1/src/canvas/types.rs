@@ -0,0 +1,13 @@
+use std::pin::Pin;
+
+use crate::{core::{executor::AsyncExecutor, task_handle::TaskResult}, error_handler::error_handler::ExecutorError, priority::priority::Priority};
+
+// Called with the result of the last step of the chain
+pub type ChainContinuation<T> = Box<dyn FnOnce(TaskResult<T>) + Send + 'static>;
+
+// Submits the steps of the chain one after another and calls the continuation at the end, Err when the first step is not accepted
+pub type ChainRunner<T> = Box<dyn FnOnce(AsyncExecutor, Priority, ChainContinuation<T>) -> Result<(), ExecutorError> + Send + 'static>;
+
+pub type GroupTask<T> = Pin<Box<dyn Future<Output = T> + Send + 'static>>;
+
+pub type ChordCallback<T, U> = Box<dyn FnOnce(Vec<TaskResult<T>>) -> GroupTask<U> + Send + 'static>;
@@ -1,4 +1,5 @@
 pub mod base_channel;
 pub mod worker_channel;
+pub mod priority_channel;
 pub mod shutdown_channel;
 pub mod types;
\ No newline at end of file
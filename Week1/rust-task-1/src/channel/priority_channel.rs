@@ -0,0 +1,360 @@
+use std::{sync::{atomic::{AtomicBool, Ordering}, Arc, Condvar, Mutex, Weak}, time::{Duration, Instant}};
+
+use crossbeam::channel::{self, Receiver, RecvError, RecvTimeoutError, Select, SendError, SendTimeoutError, Sender, TryRecvError, TrySendError};
+
+use crate::{core::job::Job, priority::priority::Priority};
+
+/*
+    Worker channel with priorities.
+    - One crossbeam channel per Priority level => FIFO order inside each level
+    - Receivers always check the levels from High to None, so a High job never waits behind None jobs
+    - Channel is disconnected when all senders are dropped (every sender clone holds all levels)
+    - Capacity is shared by all levels => when `capacity` jobs are queued, senders wait (backpressure)
+    - close() disconnects the senders while receivers are still alive (the executor keeps a receiver till the end of the process),
+      receivers get the jobs that are already queued and then Disconnected
+*/
+const PRIORITY_LEVELS: usize = Priority::High.to_value() as usize + 1;
+
+// How often a waiting sender checks if the receivers are still alive
+const DISCONNECT_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug)]
+struct QueueCapacity {
+    capacity: usize,
+    queued: Mutex<usize>, // Jobs sent and not yet received
+    space_freed: Condvar,
+    closed: AtomicBool, // Set by PriorityReceiver::close(), nobody takes the jobs anymore
+}
+
+impl QueueCapacity {
+    fn release(&self) {
+        let mut queued = self.queued.lock().unwrap();
+        *queued = queued.saturating_sub(1);
+        self.space_freed.notify_one();
+    }
+}
+
+enum Reservation {
+    Reserved,
+    Full,
+    Disconnected,
+}
+
+#[derive(Debug, Clone)]
+pub struct PrioritySender {
+    senders: Vec<Sender<Job>>, // Index is Priority::to_value()
+    capacity: Arc<QueueCapacity>,
+    receivers_alive: Weak<()>, // Can't be upgraded when all receivers are dropped
+}
+
+#[derive(Debug, Clone)]
+pub struct PriorityReceiver {
+    receivers: Vec<Receiver<Job>>,
+    capacity: Arc<QueueCapacity>,
+    _alive: Arc<()>,
+}
+
+pub fn priority_channel(capacity: usize) -> (PrioritySender, PriorityReceiver) {
+    let (senders, receivers) = (0..PRIORITY_LEVELS).map(|_| channel::unbounded::<Job>()).unzip();
+    let capacity = Arc::new(QueueCapacity { capacity, queued: Mutex::new(0), space_freed: Condvar::new(), closed: AtomicBool::new(false) });
+    let alive = Arc::new(());
+
+    (
+        PrioritySender { senders, capacity: capacity.clone(), receivers_alive: Arc::downgrade(&alive) },
+        PriorityReceiver { receivers, capacity, _alive: alive },
+    )
+}
+
+// Same errors as crossbeam, they give the job back to the caller => the Err variant is as big as the Job
+#[allow(clippy::result_large_err)]
+impl PrioritySender {
+    // Job is queued on the level of job.priority, waits while the queue is full
+    pub fn send(&self, job: Job) -> Result<(), SendError<Job>> {
+        match self.reserve(None) {
+            Reservation::Reserved => self.push(job),
+            _ => Err(SendError(job)),
+        }
+    }
+
+    // Returns the job back when the queue is full
+    pub fn try_send(&self, job: Job) -> Result<(), TrySendError<Job>> {
+        match self.reserve(Some(Instant::now())) {
+            Reservation::Reserved => self.push(job).map_err(|SendError(job)| TrySendError::Disconnected(job)),
+            Reservation::Full => Err(TrySendError::Full(job)),
+            Reservation::Disconnected => Err(TrySendError::Disconnected(job)),
+        }
+    }
+
+    pub fn send_timeout(&self, job: Job, timeout: Duration) -> Result<(), SendTimeoutError<Job>> {
+        match self.reserve(Some(Instant::now() + timeout)) {
+            Reservation::Reserved => self.push(job).map_err(|SendError(job)| SendTimeoutError::Disconnected(job)),
+            Reservation::Full => Err(SendTimeoutError::Timeout(job)),
+            Reservation::Disconnected => Err(SendTimeoutError::Disconnected(job)),
+        }
+    }
+
+    // Ignores the capacity => for follow-up jobs sent from the workers, waiting there could block the worker that frees the space
+    pub fn force_send(&self, job: Job) -> Result<(), SendError<Job>> {
+        if self.capacity.closed.load(Ordering::Acquire) {
+            return Err(SendError(job));
+        }
+        *self.capacity.queued.lock().unwrap() += 1;
+        self.push(job)
+    }
+
+    // Takes one place in the queue, waits for free space till the deadline (None => no deadline)
+    fn reserve(&self, deadline: Option<Instant>) -> Reservation {
+        let mut queued = self.capacity.queued.lock().unwrap();
+
+        loop {
+            if self.receivers_alive.strong_count() == 0 || self.capacity.closed.load(Ordering::Acquire) {
+                return Reservation::Disconnected;
+            }
+            if *queued < self.capacity.capacity {
+                *queued += 1;
+                return Reservation::Reserved;
+            }
+
+            let mut wait = DISCONNECT_CHECK_INTERVAL;
+            if let Some(deadline) = deadline {
+                let now = Instant::now();
+                if now >= deadline {
+                    return Reservation::Full;
+                }
+                wait = wait.min(deadline - now);
+            }
+            queued = self.capacity.space_freed.wait_timeout(queued, wait).unwrap().0;
+        }
+    }
+
+    fn push(&self, mut job: Job) -> Result<(), SendError<Job>> {
+        let level = job.priority.to_value() as usize;
+        job.queued_at = Some(Instant::now());
+
+        self.senders[level].send(job).inspect_err(|_| self.capacity.release())
+    }
+}
+
+impl PriorityReceiver {
+    // Highest priority job that is waiting
+    pub fn try_recv(&self) -> Result<Job, TryRecvError> {
+        let mut disconnected_levels = 0;
+
+        for receiver in self.receivers.iter().rev() {
+            match receiver.try_recv() {
+                Ok(job) => {
+                    self.capacity.release(); // Space for a waiting sender
+                    return Ok(job);
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => disconnected_levels += 1,
+            }
+        }
+
+        // Closed => no more jobs come even when some senders are alive, the sent ones are counted in `queued` till received
+        let closed_and_empty = self.capacity.closed.load(Ordering::Acquire) && *self.capacity.queued.lock().unwrap() == 0;
+        if disconnected_levels == self.receivers.len() || closed_and_empty {
+            Err(TryRecvError::Disconnected)
+        } else {
+            Err(TryRecvError::Empty)
+        }
+    }
+
+    pub fn recv(&self) -> Result<Job, RecvError> {
+        loop {
+            match self.try_recv() {
+                Ok(job) => return Ok(job),
+                Err(TryRecvError::Disconnected) => return Err(RecvError),
+                Err(TryRecvError::Empty) => {
+                    let mut select = Select::new();
+                    self.register(&mut select);
+                    select.ready(); // Another worker can take the job first, then it is checked again
+                }
+            }
+        }
+    }
+
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<Job, RecvTimeoutError> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            match self.try_recv() {
+                Ok(job) => return Ok(job),
+                Err(TryRecvError::Disconnected) => return Err(RecvTimeoutError::Disconnected),
+                Err(TryRecvError::Empty) => {
+                    let mut select = Select::new();
+                    self.register(&mut select);
+
+                    if select.ready_deadline(deadline).is_err() {
+                        return Err(RecvTimeoutError::Timeout);
+                    }
+                }
+            }
+        }
+    }
+
+    // Adds all levels to the select, so a worker can wait for this channel together with other channels
+    pub fn register<'a>(&'a self, select: &mut Select<'a>) {
+        for receiver in &self.receivers {
+            select.recv(receiver);
+        }
+    }
+
+    // Senders get Disconnected from now on, also the ones that wait for free space
+    pub fn close(&self) {
+        let _queued = self.capacity.queued.lock().unwrap(); // Waiting senders check the flag under this lock
+        self.capacity.closed.store(true, Ordering::Release);
+        self.capacity.space_freed.notify_all();
+    }
+
+    pub fn len(&self) -> usize {
+        self.receivers.iter().map(|receiver| receiver.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.receivers.iter().all(|receiver| receiver.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job_with_priority(priority: Priority) -> Job {
+        Job::new(Box::pin(async {})).with_priority(priority)
+    }
+
+    #[test]
+    fn test_try_recv_should_return_highest_priority_first() {
+        let (tx, rx) = priority_channel(16);
+
+        for priority in [Priority::None, Priority::Low, Priority::High, Priority::Medium] {
+            tx.send(job_with_priority(priority)).unwrap();
+        }
+        assert_eq!(rx.len(), 4);
+
+        let order: Vec<Priority> = (0..4).map(|_| rx.try_recv().unwrap().priority).collect();
+
+        assert_eq!(order, vec![Priority::High, Priority::Medium, Priority::Low, Priority::None]);
+        assert!(rx.is_empty());
+    }
+
+    #[test]
+    fn test_same_priority_should_keep_fifo_order() {
+        let (tx, rx) = priority_channel(16);
+        let (first, second) = (Job::new(Box::pin(async {})), Job::new(Box::pin(async {})));
+
+        tx.send(first.with_priority(Priority::Low)).unwrap();
+        tx.send(job_with_priority(Priority::High)).unwrap();
+        tx.send(second.with_priority(Priority::Low).with_failure_callback(Box::new(|_| {}))).unwrap();
+
+        assert_eq!(rx.recv().unwrap().priority, Priority::High);
+        assert!(rx.recv().unwrap().on_failure.is_none()); // First Low job
+        assert!(rx.recv().unwrap().on_failure.is_some()); // Second Low job
+    }
+
+    #[test]
+    fn test_recv_should_fail_when_all_senders_are_dropped() {
+        let (tx, rx) = priority_channel(16);
+
+        tx.send(job_with_priority(Priority::Medium)).unwrap();
+        drop(tx);
+
+        assert!(rx.recv().is_ok()); // Queued jobs are still received
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Disconnected)));
+        assert!(matches!(rx.recv_timeout(Duration::from_millis(10)), Err(RecvTimeoutError::Disconnected)));
+    }
+
+    #[test]
+    fn test_recv_timeout_should_wait_for_job() {
+        let (tx, rx) = priority_channel(16);
+
+        assert!(matches!(rx.recv_timeout(Duration::from_millis(20)), Err(RecvTimeoutError::Timeout)));
+
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            tx.send(job_with_priority(Priority::None)).unwrap();
+        });
+
+        assert!(rx.recv_timeout(Duration::from_secs(1)).is_ok());
+    }
+
+    #[test]
+    fn test_try_send_should_return_job_when_queue_is_full() {
+        let (tx, rx) = priority_channel(2);
+
+        tx.send(job_with_priority(Priority::None)).unwrap();
+        tx.try_send(job_with_priority(Priority::None)).unwrap();
+        assert!(matches!(tx.try_send(job_with_priority(Priority::High)), Err(TrySendError::Full(_))));
+
+        rx.try_recv().unwrap(); // Frees one place
+        assert!(tx.try_send(job_with_priority(Priority::High)).is_ok());
+    }
+
+    #[test]
+    fn test_send_timeout_should_wait_for_free_space() {
+        let (tx, rx) = priority_channel(1);
+        tx.send(job_with_priority(Priority::None)).unwrap();
+
+        assert!(matches!(tx.send_timeout(job_with_priority(Priority::None), Duration::from_millis(20)), Err(SendTimeoutError::Timeout(_))));
+
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            rx.recv().unwrap();
+            std::thread::sleep(Duration::from_millis(100)); // Receiver is alive till the job is sent
+        });
+
+        assert!(tx.send_timeout(job_with_priority(Priority::None), Duration::from_secs(1)).is_ok());
+    }
+
+    #[test]
+    fn test_force_send_should_ignore_capacity() {
+        let (tx, rx) = priority_channel(1);
+
+        tx.send(job_with_priority(Priority::None)).unwrap();
+        tx.force_send(job_with_priority(Priority::None)).unwrap();
+        assert_eq!(rx.len(), 2);
+
+        rx.recv().unwrap();
+        assert!(matches!(tx.try_send(job_with_priority(Priority::None)), Err(TrySendError::Full(_)))); // Still one over capacity
+    }
+
+    #[test]
+    fn test_blocked_send_should_fail_when_receivers_are_dropped() {
+        let (tx, rx) = priority_channel(1);
+        tx.send(job_with_priority(Priority::None)).unwrap();
+        drop(rx);
+
+        assert!(tx.send(job_with_priority(Priority::None)).is_err());
+    }
+
+    #[test]
+    fn test_blocked_send_should_fail_when_channel_is_closed() {
+        let (tx, rx) = priority_channel(1);
+        tx.send(job_with_priority(Priority::None)).unwrap();
+
+        let closing_rx = rx.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            closing_rx.close();
+        });
+
+        let start = Instant::now();
+        assert!(tx.send(job_with_priority(Priority::None)).is_err()); // Receiver is still alive
+        assert!(start.elapsed() < DISCONNECT_CHECK_INTERVAL);
+        assert!(tx.force_send(job_with_priority(Priority::None)).is_err());
+        assert_eq!(rx.len(), 1);
+    }
+
+    #[test]
+    fn test_closed_channel_should_give_queued_jobs_then_disconnect() {
+        let (tx, rx) = priority_channel(16);
+        tx.send(job_with_priority(Priority::Low)).unwrap();
+        rx.close();
+
+        // Sender is still alive, the queued job is received and then the channel is disconnected
+        assert!(rx.try_recv().is_ok());
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Disconnected)));
+        drop(tx);
+    }
+}
@@ -2,34 +2,27 @@ use super::base_channel::{BaseChannel, Channel};
 
 use crossbeam::channel;
 use super::types::{ShutdownSender, ShutdownReceiver};
-use crate::error_handler::error_handler::{fail, ExecutorError};
+use crate::error_handler::error_handler::ExecutorError;
 
 pub struct ShutdownChannelBuilder;
 
 impl ShutdownChannelBuilder {
-    pub fn create_channel() -> (ShutdownSender, ShutdownReceiver) {
+    pub fn create_channel() -> Result<(ShutdownSender, ShutdownReceiver), ExecutorError> {
         let channel = ShutdownChannelBuilder::initialize_channel();
 
-        let (tx, rx) = ShutdownChannelBuilder::validate_channel(channel);
-
-        (tx, rx)
+        ShutdownChannelBuilder::validate_channel(channel)
     }
 
-    pub fn validate_channel(channel: Channel) -> (ShutdownSender, ShutdownReceiver) {
+    pub fn validate_channel(channel: Channel) -> Result<(ShutdownSender, ShutdownReceiver), ExecutorError> {
         match channel {
-            Channel::ShutdownChannel(tx, rx) => {
-                let sender = tx.unwrap();
-                let receiver = rx.unwrap();
-
-                sender.send(()).unwrap();
+            Channel::ShutdownChannel(Some(sender), Some(receiver)) => {
+                sender.send(()).map_err(|_| ExecutorError::ChannelConnectionIsNotEstablished)?;
 
-                let _ = receiver.recv().unwrap();      
+                receiver.recv().map_err(|_| ExecutorError::ChannelConnectionIsNotEstablished)?;
 
-                return (Some(sender), Some(receiver))     
-            }
-            _ => {
-                fail(ExecutorError::ChannelConnectionIsNotEstablished, "The connection to channel is not established!".to_string());
+                Ok((Some(sender), Some(receiver)))
             }
+            _ => Err(ExecutorError::ChannelConnectionIsNotEstablished),
         }
     }
 }
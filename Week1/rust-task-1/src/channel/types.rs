@@ -1,9 +1,10 @@
 use crossbeam::channel::{Receiver, Sender};
-use std::pin::Pin;
 
-// Used for workers channel
-pub type SenderType = Sender<Pin<Box<dyn Future<Output = ()> + Send>>>;
-pub type ReceiverType = Receiver<Pin<Box<dyn Future<Output = ()> + Send>>>;
+use super::priority_channel::{PriorityReceiver, PrioritySender};
+
+// Used for workers channel => jobs are received by priority
+pub type SenderType = PrioritySender;
+pub type ReceiverType = PriorityReceiver;
 
 // Used for shutdown channel
 pub type ShutdownSender = Option<Sender<()>>;
@@ -1,41 +1,44 @@
 use super::base_channel::{BaseChannel, Channel};
-use crossbeam::channel;
+use super::priority_channel::priority_channel;
 use super::types::{SenderType, ReceiverType};
-use crate::error_handler::error_handler::{fail, ExecutorError};
-use crate::core::types::Task;
+use crate::error_handler::error_handler::ExecutorError;
+use crate::core::job::Job;
 
 pub struct WorkerChannelBuilder;
 
 impl WorkerChannelBuilder {
-    pub fn create_channel() -> (SenderType, ReceiverType) {
-        let channel = WorkerChannelBuilder::initialize_channel();
+    pub fn create_channel(capacity: usize) -> Result<(SenderType, ReceiverType), ExecutorError> {
+        let channel = WorkerChannelBuilder::initialize_bounded_channel(capacity);
 
-        let (tx, rx) = WorkerChannelBuilder::validate_channel(channel);
-
-        (tx, rx)
+        WorkerChannelBuilder::validate_channel(channel)
     }
 
-    pub fn validate_channel(channel: Channel) -> (SenderType, ReceiverType) {
-        let task = Box::pin(Channel::validation_future_function());
+    pub fn validate_channel(channel: Channel) -> Result<(SenderType, ReceiverType), ExecutorError> {
+        let task = Job::new(Box::pin(Channel::validation_future_function()));
 
         match channel {
             Channel::WorkerChannel(tx, rx) => {
-                tx.send(task).unwrap();
+                tx.send(task).map_err(|_| ExecutorError::ChannelConnectionIsNotEstablished)?;
 
-                let _ = rx.recv().unwrap();      
+                drop(rx.recv().map_err(|_| ExecutorError::ChannelConnectionIsNotEstablished)?); // Validation future is never polled
 
-                return (tx, rx)     
-            }
-            _ => {
-                fail(ExecutorError::ChannelConnectionIsNotEstablished, "The connection to channel is not established!".to_string());
+                Ok((tx, rx))
             }
+            _ => Err(ExecutorError::ChannelConnectionIsNotEstablished),
         }
     }
 }
 
+impl WorkerChannelBuilder {
+    // Senders wait (or fail with try_send) when `capacity` jobs are waiting
+    pub fn initialize_bounded_channel(capacity: usize) -> Channel {
+        let (tx, rx) = priority_channel(capacity);
+        Channel::WorkerChannel(tx, rx)
+    }
+}
+
 impl BaseChannel for WorkerChannelBuilder {
     fn initialize_channel() -> Channel {
-        let (tx, rx) = channel::unbounded::<Task>();
-        Channel::WorkerChannel(tx, rx)
+        WorkerChannelBuilder::initialize_bounded_channel(usize::MAX)
     }
 }
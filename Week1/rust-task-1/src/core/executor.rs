@@ -1,12 +1,24 @@
-use std::{sync::{atomic::{AtomicBool, Ordering}, Arc, Mutex}, time::Instant};
-
-use crate::{executor_config::ExecutorConfig, performance_monitoring::metrics::MetricsReport, worker::{base_worker::BaseWorker, future_executor_worker::FutureExecutorBuilder}};
-use crate::error_handler::error_handler::{fail, ExecutorError};
+use std::{sync::{atomic::{AtomicBool, Ordering}, Arc, Mutex, OnceLock}, time::Instant};
+
+use crate::{executor_config::ExecutorConfig, performance_monitoring::{metrics::MetricsReport, scaling_history::ScalingHistory}, worker::worker_pool::WorkerPool};
+use crate::error_handler::error_handler::{fail, fail_gracefully, ExecutorError};
+use crate::priority::priority::Priority;
+use crate::result_backend::{stored_result::StoredResult, types::ResultBackendArc};
+use crate::lifecycle::{task_id::TaskId, task_state::TaskState, task_tracker::TaskTracker, types::TaskTrackerArc};
+use crate::scheduler::{delayed_scheduler::DelayedScheduler, types::DelayedSchedulerArc};
+use crate::middleware::{middleware_chain::MiddlewareChain, types::{MiddlewareArc, MiddlewareChainArc}};
+use crate::routing::named_queues::DEFAULT_QUEUE;
+use crate::telemetry::task_span::TaskSpan;
+use crate::rate_limiting::slot_rate_limiter::SlotRateLimiter;
 
 use crate::channel::{worker_channel::WorkerChannelBuilder, shutdown_channel::ShutdownChannelBuilder};
 use crate::channel::types::{ShutdownReceiver, ReceiverType};
 
-use super::{executor_types::{ConfigParamsArc, ShutdownSenderArc, WorkerSenderOpt}, types::{MetricsData, ProxyExecutor, StopFlag, Task, WorkerHandles}};
+use crossbeam::channel::{SendTimeoutError, TrySendError};
+
+use super::submit_mode::SubmitMode;
+use super::shutdown::{ShutdownMode, ShutdownReport, ShutdownState};
+use super::{executor_types::{ConfigParamsArc, ShutdownSenderArc, ShutdownStateArc, WorkerPoolArc, WorkerSenderOpt}, job::Job, types::{MetricsData, ProxyExecutor, ScalingHistoryData, StopFlag, Task, WorkerHandles}};
 /*
 NOTE:
 With my approach:
@@ -27,11 +39,31 @@ pub struct AsyncExecutor {
     sender: WorkerSenderOpt, // Since i want to use one channel and i need to safe my sender address to be able to send from many scopes 
     shutdown_ack_tx: ShutdownSenderArc, // clone into each worker
     shutdown_ack_rx: ShutdownReceiver, // Receiver for N shutdown signals
+    scheduler: DelayedSchedulerArc, // Holds delayed jobs (task_at, task_after, retry backoff) till they are due
+    receiver: Option<ReceiverType>, // Used only by shutdown() to drop the queued tasks and to close the channel
+    shutdown_state: ShutdownStateArc, // Shutdown mode + counters for the ShutdownReport, shared with the workers
+    pool: Option<WorkerPoolArc>, // Starts and stops the workers between min_workers and max_workers
+    pub tracker: TaskTrackerArc, // TaskState of every task, shared by all queues of the Proxy
+    pub middleware: MiddlewareChainArc, // Hooks around every task of this executor, shared with the workers
+    pub queue: Arc<str>, // Name of the queue in the Proxy, set on every job that reaches this executor
+    pub scaling_history: ScalingHistoryData, // Last changes of the pool size, MetricsReport has only the counters
+    pub rate_limiter: SlotRateLimiter, // rate_limit_per_sec of this queue, the slots are shared by all clones
 }
 
 impl AsyncExecutor {
-    pub fn new() -> ProxyExecutor {
-        let config =   Arc::new(ExecutorConfig::default());
+    pub fn new() -> Result<ProxyExecutor, ExecutorError> {
+        AsyncExecutor::with_config(ExecutorConfig::default())
+    }
+
+    // Config is validated by ExecutorConfigBuilder::build()
+    pub fn with_config(config: ExecutorConfig) -> Result<ProxyExecutor, ExecutorError> {
+        AsyncExecutor::with_tracker(config, Arc::new(TaskTracker::new()))
+    }
+
+    // Same as with_config(), queues of one Proxy share the tracker so the state of any id can be found
+    pub fn with_tracker(config: ExecutorConfig, tracker: TaskTrackerArc) -> Result<ProxyExecutor, ExecutorError> {
+        let rate_limiter = SlotRateLimiter::new(config.rate_limit_per_sec);
+        let config = Arc::new(config);
 
         let mut executor_instance = AsyncExecutor { 
             stop_flag: Arc::new(AtomicBool::new(false)),
@@ -41,131 +73,347 @@ impl AsyncExecutor {
             sender: None, // No channel when initialized
             shutdown_ack_rx: None,
             shutdown_ack_tx: Arc::new(None),
+            scheduler: Arc::new(OnceLock::new()), // Thread is started with the first delayed job
+            receiver: None,
+            shutdown_state: Arc::new(ShutdownState::new()),
+            pool: None,
+            tracker,
+            middleware: Arc::new(MiddlewareChain::new()),
+            queue: Arc::from(DEFAULT_QUEUE),
+            scaling_history: Arc::new(Mutex::new(ScalingHistory::new())),
+            rate_limiter,
 
             // With Arc i pass the Rust ownership rules and allow this to be shared accross my threads without dropping too early
             // While Mutex ensures that only one thread access and mutate data at a time
         };
 
         // Create the shutdown channel
-        let (shd_tx, shd_rx) = ShutdownChannelBuilder::create_channel();
+        let (shd_tx, shd_rx) = ShutdownChannelBuilder::create_channel()?;
         executor_instance.shutdown_ack_tx = Arc::new(shd_tx);
         executor_instance.shutdown_ack_rx = shd_rx;
 
 
         // Here i set the channel for my workers threads. So i provide only the workers with the receiver of this channel
-        let (w_tx, w_rx) = WorkerChannelBuilder::create_channel(); // Create the channel for communication -> .delay() sends to workers tasks
+        let (w_tx, w_rx) = WorkerChannelBuilder::create_channel(executor_instance.config.get_queue_capacity())?; // Create the channel for communication -> .delay() sends to workers tasks
         executor_instance.sender = Some(w_tx); // Set the sender
-        executor_instance.spawn_workers(w_rx); // Pass to workers so the can receive via this channel tasks sended from delay() 
+        executor_instance.receiver = Some(w_rx.clone());
+        executor_instance.spawn_workers(w_rx)?; // Pass to workers so the can receive via this channel tasks sended from delay() 
+        // On error the already spawned workers stop, because executor_instance (the sender) is dropped
  
-        Arc::new(Mutex::new(executor_instance))
+        Ok(Arc::new(Mutex::new(executor_instance)))
     }
 
-    pub fn force_shutdown(&mut self) {
-        println!("SHUTDOWN STARTED");
-        let timeout = self.config.get_shutdown_timeout(); // TODO: Implement the timeout functionality for shutdown
-        let deadline = Instant::now() + timeout;
+    // Finishes queued and running tasks, same as shutdown(ShutdownMode::Drain)
+    pub fn force_shutdown(&mut self) -> Result<ShutdownReport, ExecutorError> {
+        self.shutdown(ShutdownMode::Drain)
+    }
 
-        let mut shutdowns = 0;
-        let total_threads = self.config.get_total_workers();
-        
-        while shutdowns < total_threads {
-            let now = Instant::now();
+    /*
+        Stops the workers by the mode and waits for their acks till shutdown_timeout.
+        When the timeout is reached it does not panic, the tasks that are still queued or running are reported as abandoned.
+        Err(ShutDownError) when the executor is already shut down.
+    */
+    pub fn shutdown(&mut self, mode: ShutdownMode) -> Result<ShutdownReport, ExecutorError> {
+        if self.shutdown_state.get_mode().is_some() {
+            return Err(ExecutorError::ShutDownError);
+        }
 
-            if now >= deadline {
-                // SHUTDOWN TIMED OUT
-                fail(ExecutorError::ShutDownError, "Shutdown timed out, please check the passed functions".to_string())
-            }
+        self.shutdown_state.begin(mode);
+        if let Some(pool) = self.pool.as_ref() {
+            pool.close(); // Autoscaler does not start workers while they are stopping
+            pool.resume(); // Drain has to finish the queued tasks, the other modes drop them anyway
+        }
+        self.sender = None;
+        // Executor clones (scheduler, beat, chains, the user) can't keep the channel open => workers stop when it is empty
+        self.close_queue();
+        let scheduled = self.cancel_scheduled();
 
-            let remaining = deadline - now;
-            match self.shutdown_ack_rx.as_ref().unwrap().recv_timeout(remaining) {
-                Ok(_) => {
-                    shutdowns += 1;
-                }
-                Err(_) => {
-                    fail(ExecutorError::ShutDownError, "Shutdown timed out, please check the passed functions".to_string())
+        if mode != ShutdownMode::Drain {
+            self.cancel_queued();
+        }
+
+        let deadline = Instant::now() + self.config.get_shutdown_timeout();
+        let live_workers = || self.pool.as_ref().map_or(0, |pool| pool.get_live_workers());
+
+        // Pool size changes (idle workers can stop too) => every ack only wakes this loop, live workers are checked again
+        if let Some(shutdown_ack_rx) = self.shutdown_ack_rx.as_ref() {
+            while live_workers() > 0 {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+
+                if shutdown_ack_rx.recv_timeout(remaining).is_err() {
+                    break;
                 }
             }
         }
-        println!("ALL Threads are closed sucessfully. Number of closed threads: {}", shutdowns);
-        self.stop_flag.store(true, Ordering::Relaxed);
-        drop(self.sender.clone()); // Drop the sender -> Destory the channel 
+        let timed_out = live_workers() > 0;
+
+        if mode != ShutdownMode::Drain {
+            self.cancel_queued(); // Taken by nobody when the workers stopped first
+        }
+
+        let queued = self.receiver.as_ref().map_or(0, |receiver| receiver.len());
+        let abandoned = scheduled + if timed_out { queued + self.shutdown_state.get_in_flight() } else { queued };
+
+        self.stop_flag.store(true, Ordering::Relaxed); // Workers that did not ack stop at their next loop
+
+        if timed_out {
+            fail_gracefully(ExecutorError::ShutDownError, "Shutdown timed out, please check the passed functions");
+        } else {
+            self.join_workers()?;
+        }
+
+        Ok(ShutdownReport {
+            mode,
+            completed: self.shutdown_state.get_completed(),
+            cancelled: self.shutdown_state.get_cancelled(),
+            abandoned,
+            timed_out,
+        })
+    }
+
+    // Queued tasks are dropped => their handles get TaskError::Cancelled
+    fn cancel_queued(&self) {
+        let Some(receiver) = self.receiver.as_ref() else { return };
+
+        let mut cancelled = 0;
+        while let Ok(job) = receiver.try_recv() {
+            self.drop_unstarted(job);
+            cancelled += 1;
+        }
+        self.shutdown_state.tasks_cancelled(cancelled);
+    }
+
+    // Jobs waiting for their countdown, eta or retry backoff never run => handles get Cancelled, they are reported as abandoned
+    fn cancel_scheduled(&self) -> usize {
+        let Some(scheduler) = self.scheduler.get() else { return 0 };
+
+        let scheduled = scheduler.stop();
+        let count = scheduled.len();
+        for job in scheduled {
+            self.drop_unstarted(job);
+        }
+        count
+    }
+
+    fn drop_unstarted(&self, job: Job) {
+        self.tracker.transition(job.id, TaskState::Cancelled);
+
+        // Never reaches a worker => the result is stored here, otherwise the task looks not finished forever
+        if let Some(result_backend) = self.pool.as_ref().and_then(|pool| pool.get_result_backend()) {
+            let mut result = StoredResult::new(job.id, TaskState::Cancelled, job.result_ttl.unwrap_or(self.config.get_result_ttl()));
+            result.error = Some(String::from("Executor was shut down before the task started"));
+            if let Err(error) = result_backend.store(result) {
+                fail_gracefully(error, "Result of the cancelled task was not stored");
+            }
+        }
+        drop(job);
+    }
 
+    pub fn delay(&self, fut: Task) -> Result<(), ExecutorError> {
+        self.delay_job(Job::new(fut))
     }
 
-    pub fn delay(&self, fut: Task) {
-        let task: Task = fut;
+    pub fn delay_job(&self, job: Job) -> Result<(), ExecutorError> {
+        self.submit_job(job, SubmitMode::Blocking)
+    }
+
+    // Sends the job through the channel and workers receive it, `mode` decides what happens when the queue is full
+    pub fn submit_job(&self, job: Job, mode: SubmitMode) -> Result<(), ExecutorError> {
+        let task: Job = job;
+        let task_id = task.id;
+
+        // Channel is closed by wait_all() or shutdown()
+        let Some(sender) = &self.sender else {
+            self.tracker.transition(task_id, TaskState::Cancelled);
+            return Err(ExecutorError::ChannelConnectionIsNotEstablished);
+        };
 
-        let sender = match &self.sender {
-            Some(v) => v,
-            None => fail(ExecutorError::Other, String::from("Failed when sending task to channel!"))
+        // Queued before the send, otherwise a worker can take the job (Running) before it is Queued
+        self.tracker.transition(task_id, TaskState::Queued);
+        let span = task.span.clone();
+        span.enqueued(); // Before the send, a worker can start the job right after it
+
+        let result = match mode {
+            SubmitMode::Blocking => sender.send(task).map_err(|_| ExecutorError::ChannelConnectionIsNotEstablished),
+            SubmitMode::Unbounded => sender.force_send(task).map_err(|_| ExecutorError::ChannelConnectionIsNotEstablished),
+            SubmitMode::Try => sender.try_send(task).map_err(|error| match error {
+                TrySendError::Full(_) => ExecutorError::WorkerPoolFull,
+                TrySendError::Disconnected(_) => ExecutorError::ChannelConnectionIsNotEstablished,
+            }),
+            SubmitMode::Timeout(timeout) => sender.send_timeout(task, timeout).map_err(|error| match error {
+                SendTimeoutError::Timeout(_) => ExecutorError::Overload,
+                SendTimeoutError::Disconnected(_) => ExecutorError::ChannelConnectionIsNotEstablished,
+            }),
         };
 
-        sender.send(task).err(); // Send this task through the channel and workers receive it
+        if let Err(error) = &result {
+            self.tracker.transition(task_id, TaskState::Cancelled); // Job was not accepted => it never runs
+            span.not_enqueued(error);
+        }
+        result
+    }
+
+    // Job is sent to the workers (through the rate limiter) when `due` is reached
+    pub fn delay_at(&self, due: Instant, priority: Priority, job: Job) -> Result<(), ExecutorError> {
+        // Shutdown of another clone => the scheduler is stopped (or would be started for nothing)
+        if self.sender.is_none() || self.shutdown_state.get_mode().is_some() {
+            self.tracker.transition(job.id, TaskState::Cancelled);
+            return Err(ExecutorError::ChannelConnectionIsNotEstablished);
+        }
+
+        let job = self.admit(job.with_priority(priority.clone()))?; // Pending till `due`, a retry attempt stays Retrying
+        job.span.scheduled(due.saturating_duration_since(Instant::now()));
+        let job_id = job.id;
+        self.get_scheduler()?.schedule(due, priority, job, self.clone())
+            .inspect_err(|_| { self.tracker.transition(job_id, TaskState::Cancelled); })
+    }
+
+    // Tracks the job, a new task has to pass before_submit() of every middleware (retry attempts and released scheduled jobs are known)
+    // The job gets the queue name and its TaskSpan here
+    pub fn admit(&self, job: Job) -> Result<Job, ExecutorError> {
+        let mut job = job.with_queue(&self.queue);
+        if job.span.is_none() {
+            job.span = TaskSpan::new(&job.get_context());
+        }
+
+        if !self.tracker.track(job.id) {
+            return Ok(job);
+        }
+
+        if let Err(error) = self.middleware.before_submit(&job.get_context()) {
+            self.tracker.transition(job.id, TaskState::Cancelled); // Rejected => it never runs
+            return Err(error);
+        }
+        Ok(job)
+    }
+
+    // Named queue of the Proxy => its jobs, TaskContext and spans have this queue name
+    pub fn set_queue_name(&mut self, queue: &str) {
+        self.queue = Arc::from(queue);
+    }
+
+    // Runs in the order of adding, see MiddlewareChain
+    pub fn add_middleware(&self, middleware: MiddlewareArc) {
+        self.middleware.add(middleware);
+    }
+
+    // OnceLock::get_or_try_init() is not stable, when two threads start the scheduler at once the second one is dropped (its thread stops)
+    fn get_scheduler(&self) -> Result<&DelayedScheduler, ExecutorError> {
+        if self.scheduler.get().is_none() {
+            let _ = self.scheduler.set(DelayedScheduler::start()?);
+        }
+        Ok(self.scheduler.get().expect("Scheduler is set above"))
+    }
+
+    // Workers stop taking jobs from the channel, running tasks are finished and submissions keep queueing
+    pub fn pause(&self) -> Result<(), ExecutorError> {
+        self.get_running_pool()?.pause();
+        Ok(())
+    }
+
+    pub fn resume(&self) -> Result<(), ExecutorError> {
+        self.get_running_pool()?.resume();
+        Ok(())
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.pool.as_ref().is_some_and(|pool| pool.is_paused())
+    }
+
+    fn get_running_pool(&self) -> Result<&WorkerPoolArc, ExecutorError> {
+        match (&self.sender, &self.pool) {
+            (Some(_), Some(pool)) => Ok(pool),
+            _ => Err(ExecutorError::ChannelConnectionIsNotEstablished), // Closed by wait_all() or shutdown()
+        }
+    }
+
+    // Workers store the outcome of every finished task from now on
+    pub fn set_result_backend(&self, result_backend: ResultBackendArc) -> Result<(), ExecutorError> {
+        let Some(pool) = self.pool.as_ref() else { return Err(ExecutorError::ChannelConnectionIsNotEstablished) };
+        pool.set_result_backend(result_backend);
+        Ok(())
+    }
+
+    // None when the id is unknown or the task finished long ago (TaskTracker keeps only the last finished tasks)
+    pub fn get_task_state(&self, id: TaskId) -> Option<TaskState> {
+        self.tracker.get_state(id)
     }
 
-    pub fn wait_all(&mut self) {
+    pub fn wait_all(&mut self) -> Result<(), ExecutorError> {
         // Wait till all functions are over because main will finish and will terminate every async unfinished task it will not wait thats why i create this fn
         
         self.sender = None;// Drop the sender to close the channel
+        if let Some(pool) = self.pool.as_ref() {
+            pool.resume(); // Paused workers would never take the queued tasks
+        }
+
+        let joined = self.join_workers();
+        self.close_queue();
+        joined
+    }
+
+    // Workers are gone => executor clones (delayed scheduler, beat) get an error instead of waiting for free space forever
+    fn close_queue(&self) {
+        if let Some(receiver) = self.receiver.as_ref() {
+            receiver.close();
+        }
+    }
+
+    fn join_workers(&self) -> Result<(), ExecutorError> {
+        if let Some(pool) = self.pool.as_ref() {
+            pool.close(); // No new workers after the handles are joined
+        }
 
+        // Poisoned only when a thread panicked while pushing a handle => internal invariant
         let mut handles = self.worker_handles.lock().unwrap_or_else(|_| fail(ExecutorError::Fail, String::from("Failed when tried to lock value using Mutex in .wait_all()")));
 
         // drain() removes all elements from the vector and returns an iterator over them `..` means full range
+        let mut worker_panicked = false;
         for handle in handles.drain(..) {
-            handle.join().unwrap_or_else(|_| fail(ExecutorError::Fail, String::from("Failed when waiting for task execution")));
+            worker_panicked |= handle.join().is_err(); // Task panics are caught, so this is a panic of the worker itself
         }
-    }
 
-    fn spawn_workers(&mut self, rx: ReceiverType) {
-        // This is the workers thread created when this struct is initialized
-        let allowed_workers = self.config.get_total_workers();
-        println!("\n===> Using 3/4 of total CPU cores as workers count. Workers allowed: {}\n", allowed_workers);
+        match worker_panicked {
+            true => Err(ExecutorError::Fail),
+            false => Ok(()),
+        }
+    }
 
-        // I have fixed workers and it works without validation, because when i have free worker he takes a task trough the channel, when all workers are busy => tasks are waiting in the channel!
+    fn spawn_workers(&mut self, rx: ReceiverType) -> Result<(), ExecutorError> {
+        // I have workers between min_workers and max_workers, when a worker has free capacity he takes a task trough the channel, when all workers are full (max_tasks_per_worker in-flight futures) => tasks are waiting in the channel and the pool can grow!
 
         /*
             In tipical Rust i will have Ownership problem when i want to mutate some field from the struct, because in my thread i use `move` keyword => Ownership and Lifetimes issues
              - Thats why i use Arc to create Shared State -> A reference to the original data stored in the heap
              - And mutex to lock this data while i make some changes inside
          */
-        
-        // Spawn multiple threads(workers) that each listens to the channel to recieve a task
-        for _ in 0..allowed_workers {
-            let rx_clone = rx.clone();
-            let metrics_clone = self.metrics.clone(); // with clone() that comes from Arc i create a reference to he heap stored metrics
-            let stop_flag = self.stop_flag.clone();
-            let shutdown_arc_sender_clone = self.shutdown_ack_tx.clone();
-
-            // Instantiate the worker required
-            let future_executor = FutureExecutorBuilder::new(rx_clone, metrics_clone, stop_flag, shutdown_arc_sender_clone);
-
-            // THREAD SPAWN HERE -------->
-            let handle = future_executor.spawn_thread(self.config.task_timeout); // Spawn the thread from my worker
-
-            // Collect all handles
-            self.worker_handles
-                .lock()
-                .unwrap_or_else(|_| fail(ExecutorError::Fail, String::from("Failed when tried to add spawn handle to worker_handles!")))
-                .push(handle);
-
-            // lock() is the Mutex way to ensure that only this thread is mutating this data at this time
-            // Note: This is blocking the other threads that run in parallel until this release MutexGuarded data
-        }
+        let pool = Arc::new(WorkerPool::new(
+            self.config.clone(),
+            rx,
+            self.metrics.clone(),
+            self.stop_flag.clone(),
+            self.shutdown_ack_tx.clone(),
+            self.shutdown_state.clone(),
+            self.worker_handles.clone(),
+        ).with_tracker(self.tracker.clone()).with_middleware(self.middleware.clone()).with_scaling_history(self.scaling_history.clone()));
+        self.pool = Some(pool.clone());
+
+        pool.start() // THREADS SPAWN HERE -------->
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{pin::Pin, thread::{self, available_parallelism, JoinHandle}};
+    use std::thread::{self, available_parallelism, JoinHandle};
+    use std::time::Duration;
 
-    use crossbeam::channel::{self, Sender};
     use futures::executor::block_on;
 
     use super::*;
-    use crate::{executor_config::ExecutorConfig, testing_functions::*};
+    use crate::{channel::{priority_channel::priority_channel, types::SenderType}, executor_config::{ExecutorConfig, ExecutorConfigBuilder}, testing_functions::*};
 
-    fn setup_channel() -> (Sender<Pin<Box<dyn Future<Output = ()> + Send>>>, ReceiverType) {
-        let (tx, rx) = channel::unbounded::<Task>();
+    fn setup_channel() -> (SenderType, ReceiverType) {
+        let (tx, rx) = priority_channel(16);
         (tx, rx)
     }
 
@@ -190,6 +438,15 @@ mod tests {
             sender: None,
             shutdown_ack_tx: Arc::new(None),
             shutdown_ack_rx: None,
+            scheduler: Arc::new(OnceLock::new()),
+            receiver: None,
+            shutdown_state: Arc::new(ShutdownState::new()),
+            pool: None,
+            tracker: Arc::new(TaskTracker::new()),
+            middleware: Arc::new(MiddlewareChain::new()),
+            queue: Arc::from(DEFAULT_QUEUE),
+            scaling_history: Arc::new(Mutex::new(ScalingHistory::new())),
+            rate_limiter: SlotRateLimiter::new(ExecutorConfig::default().rate_limit_per_sec),
         }
     }
 
@@ -198,7 +455,8 @@ mod tests {
         let metrics = Arc::new(Mutex::new(MetricsReport::new()));
         let worker_handles: Arc<Mutex<Vec<JoinHandle<()>>>> = Arc::new(Mutex::new(vec![]));
 
-        let executor = AsyncExecutor::new();
+        let executor = AsyncExecutor::new().unwrap();
+        metrics.lock().unwrap().set_pool_size(setup_total_workers()); // Started workers are in the metrics
 
         assert_eq!(*executor.lock().unwrap().metrics.lock().unwrap(), *metrics.lock().unwrap());
         assert!(worker_handles.lock().unwrap().is_empty());
@@ -211,13 +469,13 @@ mod tests {
         let mut executor = setup_executor();
         executor.sender = Some(sender);
 
-        executor.delay(Box::pin(send_email()));
+        executor.delay(Box::pin(send_email())).unwrap();
 
         let rx_clone = receiver.clone();
         
         let handle = thread::spawn(move || {
             while let Ok(task) = rx_clone.recv() {
-                block_on(task); // Single threaded execution
+                block_on(task.future).unwrap(); // Single threaded execution
             }
         });
 
@@ -226,15 +484,16 @@ mod tests {
             .unwrap()
             .push(handle);
 
-        executor.wait_all();
+        executor.wait_all().unwrap();
         assert!(true);
     }
 
     #[test]
-    #[should_panic]
-    fn test_delay_when_sender_is_none_should_panic() {
+    fn test_delay_when_sender_is_none_should_return_error() {
         let executor = setup_executor();
-        executor.delay(Box::pin(send_email()));
+        let result = executor.delay(Box::pin(send_email()));
+
+        assert!(matches!(result, Err(ExecutorError::ChannelConnectionIsNotEstablished)));
     }
 
     #[test]
@@ -247,13 +506,13 @@ mod tests {
 
         let rx_clone = rx.clone();
 
-        executor.delay(Box::pin(send_email()));
-        executor.delay(Box::pin(send_birthday_present()));
+        executor.delay(Box::pin(send_email())).unwrap();
+        executor.delay(Box::pin(send_birthday_present())).unwrap();
         
 
         let handle = thread::spawn(move || {
             while let Ok(task) = rx_clone.recv() {
-                block_on(task); // Single threaded execution
+                block_on(task.future).unwrap(); // Single threaded execution
             }
         });
 
@@ -262,17 +521,75 @@ mod tests {
             .unwrap()
             .push(handle);
 
-        executor.wait_all();
+        executor.wait_all().unwrap();
 
         assert_eq!(start.elapsed().as_secs(), 2); // Should take 2 seconds
         assert!(true);
     }
 
+    #[test]
+    fn test_submit_job_should_fail_when_queue_is_full() {
+        let (tx, rx) = priority_channel(1);
+        let mut executor = setup_executor();
+        executor.sender = Some(tx);
+
+        executor.submit_job(Job::new(Box::pin(send_email())), SubmitMode::Try).unwrap();
+
+        let try_result = executor.submit_job(Job::new(Box::pin(send_email())), SubmitMode::Try);
+        assert!(matches!(try_result, Err(ExecutorError::WorkerPoolFull)));
+
+        let start = Instant::now();
+        let timeout_result = executor.submit_job(Job::new(Box::pin(send_email())), SubmitMode::Timeout(Duration::from_millis(50)));
+        assert!(matches!(timeout_result, Err(ExecutorError::Overload)));
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        // Follow-up jobs are accepted over the capacity
+        executor.submit_job(Job::new(Box::pin(send_email())), SubmitMode::Unbounded).unwrap();
+        assert_eq!(rx.len(), 2);
+    }
+
+    #[test]
+    fn test_blocking_submit_should_wait_for_free_space() {
+        let (tx, rx) = priority_channel(1);
+        let mut executor = setup_executor();
+        executor.sender = Some(tx);
+
+        executor.delay(Box::pin(send_email())).unwrap();
+
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            drop(rx.recv().unwrap()); // Frees the queue
+            rx
+        });
+
+        let start = Instant::now();
+        executor.delay(Box::pin(send_email())).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        assert_eq!(handle.join().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_clone_should_not_send_after_shutdown() {
+        let config = ExecutorConfigBuilder::new().with_worker_count(1).with_queue_capacity(1).build().unwrap();
+        let executor = AsyncExecutor::with_config(config).unwrap();
+        let scheduler_clone = executor.lock().unwrap().clone(); // Like the delayed scheduler or the beat thread
+
+        executor.lock().unwrap().shutdown(ShutdownMode::FinishRunning).unwrap();
+
+        let start = Instant::now();
+        scheduler_clone.delay(Box::pin(send_email())).unwrap_err(); // Without close() it is queued for nobody
+        let result = scheduler_clone.delay(Box::pin(send_email())); // ... and this one waits for free space forever
+
+        assert!(matches!(result, Err(ExecutorError::ChannelConnectionIsNotEstablished)));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
     #[test]
     fn test_get_machine_cores_should_be_valid() {
         let total_workers = setup_total_workers();
 
-        let executor = AsyncExecutor::new();
+        let executor = AsyncExecutor::new().unwrap();
 
         let workers_result = executor.lock().unwrap().config.get_total_workers();
         assert_eq!(total_workers, workers_result);
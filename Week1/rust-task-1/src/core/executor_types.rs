@@ -1,12 +1,15 @@
 use std::sync::Arc;
-use crossbeam::channel::Sender;
-use crate::{channel::types::ShutdownSender, executor_config::ExecutorConfig};
-use super::types::Task;
+use super::shutdown::ShutdownState;
+use crate::{channel::types::{SenderType, ShutdownSender}, executor_config::ExecutorConfig, worker::worker_pool::WorkerPool};
 
 
 
 pub type ConfigParamsArc = Arc<ExecutorConfig>;
 
-pub type WorkerSenderOpt = Option<Sender<Task>>;   
+pub type WorkerSenderOpt = Option<SenderType>;   
 
-pub type ShutdownSenderArc = Arc<ShutdownSender>;
\ No newline at end of file
+pub type ShutdownSenderArc = Arc<ShutdownSender>;
+
+pub type ShutdownStateArc = Arc<ShutdownState>;
+
+pub type WorkerPoolArc = Arc<WorkerPool>;
\ No newline at end of file
@@ -0,0 +1,214 @@
+use std::{convert::Infallible, fmt::Display, sync::{Arc, Mutex}, time::{Duration, Instant}};
+
+use serde::Serialize;
+
+use crate::error_handler::error_handler::{fail_gracefully, ExecutorError, TaskError};
+use crate::lifecycle::task_id::TaskId;
+use crate::middleware::task_context::TaskContext;
+use crate::telemetry::task_span::TaskSpan;
+use crate::result_backend::types::OutputSlot;
+use crate::priority::priority::Priority;
+use crate::future_executors::future_types::{receive_future_no_output, receive_future_with_result, FutureTypes};
+
+use super::task_handle::{task_handle, TaskHandle};
+use super::types::{FailureCallback, FallibleTask, Task};
+
+/*
+    Job is what travels through the worker channel.
+    - id: TaskId of the task, the TaskState of this id is updated on the way to the worker and by the worker
+    - future: the user task (for typed tasks it is wrapped so the output is sent to the TaskHandle)
+    - on_failure: called by the worker when it gives up on the future (timeout) so the TaskHandle knows why
+    - priority: level of the worker channel, set by the rate limiter when the job is admitted
+    - queued_at: set by the worker channel, used to measure how long the job waited for a worker (autoscaling)
+    - output / result_ttl: what the worker stores in the ResultBackend when the job is finished
+    - timeout: execution time limit of this job, None => task_timeout of the config
+    - name / attempt / queue: what the middleware gets in the TaskContext, queue is set when the job reaches it
+    - span: TaskSpan of the `tracing` feature, created when the job reaches its queue
+*/
+pub struct Job {
+    pub id: TaskId,
+    pub future: FutureTypes,
+    pub on_failure: Option<FailureCallback>,
+    pub priority: Priority,
+    pub queued_at: Option<Instant>,
+    pub output: Option<OutputSlot>,
+    pub result_ttl: Option<Duration>,
+    pub timeout: Option<Duration>,
+    pub name: Option<String>,
+    pub attempt: u32,
+    pub queue: Option<String>,
+    pub span: TaskSpan,
+}
+
+impl Job {
+    pub fn new(future: Task) -> Job {
+        Job {
+            id: TaskId::next(),
+            future: receive_future_no_output(future),
+            on_failure: None,
+            priority: Priority::None,
+            queued_at: None,
+            output: None,
+            result_ttl: None,
+            timeout: None,
+            name: None,
+            attempt: 1,
+            queue: None,
+            span: TaskSpan::none(),
+        }
+    }
+
+    // Err returned from this future is counted as failed task
+    pub fn fallible(future: FallibleTask) -> Job {
+        Job {
+            id: TaskId::next(),
+            future: receive_future_with_result(future),
+            on_failure: None,
+            priority: Priority::None,
+            queued_at: None,
+            output: None,
+            result_ttl: None,
+            timeout: None,
+            name: None,
+            attempt: 1,
+            queue: None,
+            span: TaskSpan::none(),
+        }
+    }
+
+    // Wraps the future so its output is sent to the returned TaskHandle
+    pub fn with_handle<F, T>(fut: F) -> (Job, TaskHandle<T>)
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (completer, handle) = task_handle::<T, Infallible>();
+        let on_failure = completer.failure_callback();
+
+        let future = async move {
+            let output = fut.await;
+            completer.complete(output);
+        };
+
+        let job = Job::new(Box::pin(future)).with_failure_callback(on_failure);
+        let handle = handle.with_task_id(job.id);
+        (job, handle)
+    }
+
+    // Same as with_handle() and the output is stored as JSON in the ResultBackend
+    pub fn with_stored_output<F, T>(fut: F) -> (Job, TaskHandle<T>)
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Serialize + Send + 'static,
+    {
+        let output_slot: OutputSlot = Arc::new(Mutex::new(None));
+        let task_output = output_slot.clone();
+
+        let (mut job, handle) = Job::with_handle(async move {
+            let output = fut.await;
+            match serde_json::to_string(&output) {
+                Ok(json) => *task_output.lock().unwrap() = Some(json),
+                Err(error) => fail_gracefully(ExecutorError::InvalidResult(error.to_string()), "Task output is not stored"),
+            }
+            output
+        });
+
+        job.output = Some(output_slot);
+        (job, handle)
+    }
+
+    // Same as with_handle() for futures that return Result => Err is sent to the handle as TaskError::Failed(error)
+    pub fn fallible_with_handle<F, T, E>(fut: F) -> (Job, TaskHandle<T, E>)
+    where
+        F: Future<Output = Result<T, E>> + Send + 'static,
+        T: Send + 'static,
+        E: Display + Send + 'static,
+    {
+        let (completer, handle) = task_handle::<T, E>();
+        let on_failure = completer.failure_callback();
+
+        let future = async move {
+            match fut.await {
+                Ok(output) => {
+                    completer.complete(output);
+                    Ok(())
+                }
+                Err(error) => {
+                    let message = error.to_string();
+                    completer.fail(error);
+                    Err(message)
+                }
+            }
+        };
+
+        let job = Job::fallible(Box::pin(future)).with_failure_callback(on_failure);
+        let handle = handle.with_task_id(job.id);
+        (job, handle)
+    }
+
+    // Every attempt of a retried task is a new Job with the id of the task
+    pub fn with_id(mut self, id: TaskId) -> Job {
+        self.id = id;
+        self
+    }
+
+    // Result of this job is kept for `result_ttl` instead of the config default
+    pub fn with_result_ttl(mut self, result_ttl: Duration) -> Job {
+        self.result_ttl = Some(result_ttl);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Job {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_name(mut self, name: &str) -> Job {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn with_attempt(mut self, attempt: u32) -> Job {
+        self.attempt = attempt;
+        self
+    }
+
+    // Future is replaced by `wrap(future)` => middleware around the task
+    pub fn map_future<W>(mut self, wrap: W) -> Job
+    where
+        W: FnOnce(FallibleTask) -> FallibleTask,
+    {
+        self.future = receive_future_with_result(wrap(Box::pin(self.future)));
+        self
+    }
+
+    pub fn with_queue(mut self, queue: &str) -> Job {
+        self.queue = Some(queue.to_string());
+        self
+    }
+
+    pub fn get_context(&self) -> TaskContext {
+        let context = TaskContext::new(self.id, self.name.clone(), self.priority.clone(), self.attempt);
+        match &self.queue {
+            Some(queue) => context.with_queue(queue),
+            None => context,
+        }
+    }
+
+    pub fn with_priority(mut self, priority: Priority) -> Job {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_failure_callback(mut self, on_failure: FailureCallback) -> Job {
+        self.on_failure = Some(on_failure);
+        self
+    }
+}
+
+// Called by the worker before the future is dropped, so the reason is known before the handle sees the drop
+pub fn report_failure(on_failure: &mut Option<FailureCallback>, reason: TaskError) {
+    if let Some(callback) = on_failure.take() {
+        callback(reason);
+    }
+}
@@ -1,4 +1,9 @@
 pub mod executor;
 pub mod proxy;
 pub mod types;
-pub mod executor_types;
\ No newline at end of file
+pub mod executor_types;
+pub mod job;
+pub mod task_handle;
+pub mod task_options;
+pub mod submit_mode;
+pub mod shutdown;
\ No newline at end of file
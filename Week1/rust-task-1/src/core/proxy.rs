@@ -1,63 +1,553 @@
+use std::fmt::Display;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
 
 use crate::priority::priority::Priority;
 use crate::rate_limiting::slot_rate_limiter::SlotRateLimiter;
 use crate::core::executor::AsyncExecutor;
+use crate::core::job::Job;
+use crate::canvas::{chain::Chain, chord::Chord, group::Group};
+use crate::core::task_handle::{TaskHandle, TaskResult};
+use crate::performance_monitoring::{metrics::MetricsReport, scaling_event::ScalingEvent};
+use crate::core::task_options::TaskOptions;
+use crate::core::submit_mode::SubmitMode;
+use crate::core::shutdown::{ShutdownMode, ShutdownReport};
+use crate::executor_config::ExecutorConfig;
+use crate::error_handler::error_handler::{fail_gracefully, ExecutorError};
+use crate::retry::retrying_task::RetryingTask;
+use crate::scheduler::beat_scheduler::{BeatHandle, BeatScheduler};
+use crate::routing::{named_queues::{NamedQueues, DEFAULT_QUEUE}, router::{Router, TaskInfo}, types::{NamedQueuesArc, RouterArc}};
+use crate::core::types::ProxyExecutor;
+use crate::durability::{durable_tasks::DurableTasks, task_factory::TaskFactory, wal_entry::WalEntry, write_ahead_log::WriteAheadLog};
+use crate::result_backend::{result_reaper::ResultReaper, stored_result::StoredResult, types::ResultBackendArc};
+use crate::lifecycle::{task_id::TaskId, task_state::TaskState, task_tracker::TaskTracker, types::TaskTrackerArc};
+use crate::registry::task_registry::TaskRegistry;
+use crate::middleware::{middleware_chain::MiddlewareChain, types::{MiddlewareArc, MiddlewareChainArc}};
 
 #[derive(Debug, Clone)]
 pub struct Proxy {
-    executor: Arc<Mutex<AsyncExecutor>>,
+    executor: Arc<Mutex<AsyncExecutor>>, // Default queue
+    queues: NamedQueuesArc, // Named queues added by add_queue(), each with its own workers and rate limit
+    router: RouterArc, // Decides the queue of a task, default queue when no rule matches
+    tracker: TaskTrackerArc, // TaskState of the tasks of every queue
+    result_backend: Arc<Mutex<Option<ResultBackendArc>>>, // Outcome of finished tasks, None till set_result_backend()
+    result_reaper: Arc<Mutex<Option<ResultReaper>>>, // Removes expired results, stops when the last Proxy clone is dropped
+    durable: Arc<Mutex<Option<Arc<DurableTasks>>>>, // Write-ahead log of submit_durable() tasks, None till enable_durability()
+    registry: Arc<Mutex<TaskRegistry>>, // Tasks of submit_registered() by name, empty till set_registry()
+    middleware: MiddlewareChainArc, // Added to every queue, also to the queues added later
 }
 
 impl Proxy {
-    pub fn new() -> Proxy {
-        Proxy {
-            executor: AsyncExecutor::new(), // Starts Threads(Workers) and Channel
+    // Err when the channels or the worker threads can't be created
+    pub fn new() -> Result<Proxy, ExecutorError> {
+        Proxy::with_config(ExecutorConfig::default())
+    }
+
+    // Same as new() with custom limits, timeouts and workers => ExecutorConfigBuilder
+    pub fn with_config(config: ExecutorConfig) -> Result<Proxy, ExecutorError> {
+        let tracker = Arc::new(TaskTracker::new());
+
+        Ok(Proxy {
+            executor: AsyncExecutor::with_tracker(config, tracker.clone()).map_err(|error| error.in_operation("start executor"))?, // Starts Threads(Workers) and Channel
+            queues: Arc::new(NamedQueues::new()),
+            router: Arc::new(Mutex::new(Router::new())),
+            tracker,
+            result_backend: Arc::new(Mutex::new(None)),
+            result_reaper: Arc::new(Mutex::new(None)),
+            durable: Arc::new(Mutex::new(None)),
+            registry: Arc::new(Mutex::new(TaskRegistry::new())),
+            middleware: Arc::new(MiddlewareChain::new()),
+        })
+    }
+
+    // Named queue ("emails", "payments") with its own workers, rate limit and channel => config of the queue
+    pub fn add_queue(&mut self, name: &str, config: ExecutorConfig) -> Result<(), ExecutorError> {
+        if self.queues.contains(name) {
+            return Err(ExecutorError::InvalidConfig(format!("queue \"{}\" is already registered", name)).in_operation("add_queue"));
+        }
+        let executor = AsyncExecutor::with_tracker(config, self.tracker.clone()).map_err(|error| error.in_operation("add_queue"))?;
+        executor.lock().unwrap().set_queue_name(name);
+        if let Some(result_backend) = self.result_backend.lock().unwrap().clone() {
+            executor.lock().unwrap().set_result_backend(result_backend).map_err(|error| error.in_operation("add_queue"))?;
+        }
+        for middleware in self.middleware.get_all() {
+            executor.lock().unwrap().add_middleware(middleware);
+        }
+        self.queues.add(name, executor).map_err(|error| error.in_operation("add_queue"))
+    }
+
+    // Hooks around every task of every queue, they run in the order of adding => MiddlewareChain
+    pub fn add_middleware(&mut self, middleware: MiddlewareArc) {
+        for (_, executor) in self.get_all_queues() {
+            executor.lock().unwrap().add_middleware(middleware.clone());
+        }
+        self.middleware.add(middleware);
+    }
+
+    // Workers of every queue store the outcome of finished tasks => get_result(id), also from another thread or process (FileResultBackend)
+    // Expired results are removed every result_reap_interval of the default queue config
+    pub fn set_result_backend(&mut self, result_backend: ResultBackendArc) -> Result<(), ExecutorError> {
+        self.get_all_queues()
+            .iter()
+            .try_for_each(|(_, executor)| executor.lock().unwrap().set_result_backend(result_backend.clone()))
+            .map_err(|error| error.in_operation("set_result_backend"))?;
+
+        let reap_interval = self.executor.lock().unwrap().config.get_result_reap_interval();
+        let reaper = ResultReaper::start(result_backend.clone(), reap_interval).map_err(|error| error.in_operation("set_result_backend"))?;
+
+        *self.result_backend.lock().unwrap() = Some(result_backend);
+        *self.result_reaper.lock().unwrap() = Some(reaper); // Reaper of the previous backend is stopped
+        Ok(())
+    }
+
+    // Ok(None) while the task is not finished, after the result expired or for tasks finished before set_result_backend()
+    pub fn get_result(&self, id: TaskId) -> Result<Option<StoredResult>, ExecutorError> {
+        let result_backend = self.result_backend.lock().unwrap().clone();
+
+        result_backend
+            .ok_or(ExecutorError::NoResultBackend)
+            .and_then(|result_backend| result_backend.get(id))
+            .map_err(|error| error.in_operation("get_result").for_task(id.get_value()))
+    }
+
+    // Replaces the registered tasks of submit_registered(), shared by every Proxy clone
+    pub fn set_registry(&mut self, registry: TaskRegistry) {
+        *self.registry.lock().unwrap() = registry;
+    }
+
+    pub fn get_registry(&self) -> TaskRegistry {
+        self.registry.lock().unwrap().clone()
+    }
+
+    // Task by name + args => ("send_email", json!({ "to": ... })), `args` must deserialize into the args of the registered task
+    // Retries, timeout and rate limit of the registered TaskConfig are used, `name` is used by the routing rules too
+    pub fn submit_registered<A: Serialize>(&mut self, name: &str, args: A, priority: Priority) -> Result<TaskHandle<(), String>, ExecutorError> {
+        let args = serde_json::to_value(args)
+            .map_err(|error| ExecutorError::InvalidTaskArgs(error.to_string()).in_operation("submit_registered"))?;
+        let task = self.registry.lock().unwrap().get(name);
+        let task = task.ok_or(ExecutorError::UnknownTaskName(name.to_string())).map_err(|error| error.in_operation("submit_registered"))?;
+
+        // Invalid args are rejected here, the future of the first attempt is used then
+        let first_attempt = Mutex::new(Some(task.create(&args).map_err(|error| error.in_operation("submit_registered"))?));
+        let config = task.get_config().clone();
+
+        let mut options = TaskOptions::new(priority).with_name(name).with_retry(config.get_retry_policy());
+        if let Some(timeout) = config.timeout {
+            options = options.with_timeout(timeout);
         }
+        if let Some(rate_limit) = &config.rate_limit {
+            options = options.with_rate_limit(rate_limit.clone()); // Every attempt waits in the scheduler, not in the worker
+        }
+
+        let factory = move || {
+            let future = first_attempt.lock().unwrap().take().map(Ok).unwrap_or_else(|| task.create(&args));
+            async move {
+                match future {
+                    Ok(future) => future.await,
+                    Err(error) => Err(error.to_string()),
+                }
+            }
+        };
+        self.submit_with_options(factory, options).map_err(|error| error.in_operation("submit_registered"))
+    }
+
+    // Same as submit_registered() with the priority of the registered TaskConfig => Celery's send_task()
+    pub fn send_task<A: Serialize>(&mut self, name: &str, args: A) -> Result<TaskHandle<(), String>, ExecutorError> {
+        let priority = self.registry.lock().unwrap().get(name).map(|task| task.get_config().priority.clone()).unwrap_or(Priority::None);
+        self.submit_registered(name, args, priority).map_err(|error| error.in_operation("send_task"))
+    }
+
+    /*
+        Durable mode => submit_durable() tasks are written to the write-ahead log at `path` before they are accepted.
+        Tasks that were not done when the process stopped (crash, kill, shutdown) are replayed now, returns how many.
+        `factory` creates the task from its name and args, for the logged tasks too => use the same factory after a restart.
+    */
+    pub fn enable_durability(&mut self, path: impl Into<PathBuf>, factory: impl TaskFactory + 'static) -> Result<usize, ExecutorError> {
+        let wal = WriteAheadLog::open(path).map_err(|error| error.in_operation("enable_durability"))?;
+        let durable = Arc::new(DurableTasks::new(Arc::new(wal), Arc::new(factory)));
+        *self.durable.lock().unwrap() = Some(durable.clone());
+
+        let mut replayed = 0;
+        for entry in durable.get_wal().get_pending() {
+            let task_id = entry.task_id;
+
+            // Task the factory does not know (anymore) stays in the log
+            match durable.job(&entry).and_then(|job| self.submit_job(Some(&entry.name), job, entry.priority)) {
+                Ok(()) => replayed += 1,
+                Err(error) => fail_gracefully(error.in_operation("enable_durability").for_task(task_id.get_value()), "Durable task was not replayed"),
+            }
+        }
+        Ok(replayed)
+    }
+
+    // Task is created by the factory of enable_durability() from `name` and `args`, it survives a restart of the process
+    // Returned Ok means the task is on the disk, `name` is used by the routing rules too
+    pub fn submit_durable<A: Serialize>(&mut self, name: &str, args: A, priority: Priority) -> Result<TaskId, ExecutorError> {
+        let durable = self.durable.lock().unwrap().clone();
+        let durable = durable.ok_or(ExecutorError::DurabilityNotEnabled).map_err(|error| error.in_operation("submit_durable"))?;
+
+        let args = serde_json::to_value(args)
+            .map_err(|error| ExecutorError::InvalidTaskArgs(error.to_string()).in_operation("submit_durable"))?;
+        let entry = WalEntry::new(name, args, priority.clone());
+        let task_id = entry.task_id;
+
+        // Unknown task is rejected before it is written to the log
+        let job = durable.job(&entry).map_err(|error| error.in_operation("submit_durable").for_task(task_id.get_value()))?;
+        durable.get_wal().append(entry).map_err(|error| error.in_operation("submit_durable").for_task(task_id.get_value()))?;
+
+        if let Err(error) = self.submit_job(Some(name), job, priority) {
+            let _ = durable.get_wal().mark_done(task_id); // Not accepted => the caller gets the error, nothing to replay
+            return Err(error.in_operation("submit_durable").for_task(task_id.get_value()));
+        }
+        Ok(task_id)
+    }
+
+    // Replaces the routing rules, every queue of the rules must be added before
+    pub fn set_router(&mut self, router: Router) -> Result<(), ExecutorError> {
+        if let Some(unknown) = router.get_queues().find(|queue| !self.queues.contains(queue)) {
+            return Err(ExecutorError::UnknownQueue(unknown.to_string()).in_operation("set_router"));
+        }
+        *self.router.lock().unwrap() = router;
+        Ok(())
     }
 
     // This is what stands between the user and my executor
-    pub fn task<F>(&mut self, fut: F, priority: Priority)   
+    // Returned id => get_task_state(id) tells where the task is right now
+    pub fn task<F>(&mut self, fut: F, priority: Priority) -> Result<TaskId, ExecutorError>
     where 
         F: Future<Output = ()> + Send + 'static
     {   
+        let job = Job::new(Box::pin(fut));
+        let id = job.id;
+        self.submit_job(None, job, priority)
+            .map_err(|error| error.in_operation("task").for_task(id.get_value()))?;
+        Ok(id)
+    }   
+
+    // Same as task() but the name is used by the routing rules => "charge_card" can go to the "payments" queue
+    pub fn task_named<F>(&mut self, name: &str, fut: F, priority: Priority) -> Result<TaskId, ExecutorError>
+    where
+        F: Future<Output = ()> + Send + 'static
+    {
+        let job = Job::new(Box::pin(fut));
+        let id = job.id;
+        self.submit_job(Some(name), job, priority)
+            .map_err(|error| error.in_operation("task_named").for_task(id.get_value()))?;
+        Ok(id)
+    }
+
+    // Same as task() but does not wait => Err(WorkerPoolFull) when the worker queue is full
+    pub fn try_task<F>(&mut self, fut: F, priority: Priority) -> Result<TaskId, ExecutorError>
+    where
+        F: Future<Output = ()> + Send + 'static
+    {
+        let job = Job::new(Box::pin(fut));
+        let id = job.id;
+        self.submit_job_with_mode(job, priority, SubmitMode::Try)
+            .map_err(|error| error.in_operation("try_task").for_task(id.get_value()))?;
+        Ok(id)
+    }
+
+    // Waits up to `timeout` for free space in the worker queue, then Err(Overload)
+    pub fn task_timeout<F>(&mut self, fut: F, priority: Priority, timeout: Duration) -> Result<TaskId, ExecutorError>
+    where
+        F: Future<Output = ()> + Send + 'static
+    {
+        let job = Job::new(Box::pin(fut));
+        let id = job.id;
+        self.submit_job_with_mode(job, priority, SubmitMode::Timeout(timeout))
+            .map_err(|error| error.in_operation("task_timeout").for_task(id.get_value()))?;
+        Ok(id)
+    }
+
+    // Task is kept by the scheduler and sent to the workers when eta is reached (timeout starts when a worker gets it)
+    pub fn task_at<F>(&mut self, fut: F, priority: Priority, eta: Instant) -> Result<TaskId, ExecutorError>
+    where
+        F: Future<Output = ()> + Send + 'static
+    {
+        let job = Job::new(Box::pin(fut));
+        let id = job.id;
+        let executor = self.route(None, None, &priority).map_err(|error| error.in_operation("task_at"))?;
+        executor.delay_at(eta, priority, job)
+            .map_err(|error| error.in_operation("task_at").for_task(id.get_value()))?;
+        Ok(id)
+    }
+
+    // Same as task_at() but relative to now => "run this in 30 seconds"
+    pub fn task_after<F>(&mut self, fut: F, priority: Priority, countdown: Duration) -> Result<TaskId, ExecutorError>
+    where
+        F: Future<Output = ()> + Send + 'static
+    {
+        self.task_at(fut, priority, Instant::now() + countdown)
+    }
+
+    // Same as task() but returns a handle to get the output of the future => handle.join() or handle.await
+    pub fn submit<F, T>(&mut self, fut: F, priority: Priority) -> Result<TaskHandle<T>, ExecutorError>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (job, handle) = Job::with_handle(fut);
+        let id = job.id;
+        self.submit_job(None, job, priority).map_err(|error| error.in_operation("submit").for_task(id.get_value()))?;
+        Ok(handle)
+    }
+
+    // Same as submit() with the name for the routing rules
+    pub fn submit_named<F, T>(&mut self, name: &str, fut: F, priority: Priority) -> Result<TaskHandle<T>, ExecutorError>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (job, handle) = Job::with_handle(fut);
+        let id = job.id;
+        self.submit_job(Some(name), job, priority).map_err(|error| error.in_operation("submit_named").for_task(id.get_value()))?;
+        Ok(handle)
+    }
+
+    // Same as submit() and the output is stored as JSON in the ResultBackend => get_result(id)?.get_output::<T>()
+    pub fn submit_stored<F, T>(&mut self, fut: F, priority: Priority) -> Result<TaskHandle<T>, ExecutorError>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Serialize + Send + 'static,
+    {
+        let (job, handle) = Job::with_stored_output(fut);
+        let id = job.id;
+        self.submit_job(None, job, priority).map_err(|error| error.in_operation("submit_stored").for_task(id.get_value()))?;
+        Ok(handle)
+    }
+
+    // For tasks that return Result => Err is counted as failed task in the metrics and returned as TaskError::Failed(error)
+    pub fn submit_fallible<F, T, E>(&mut self, fut: F, priority: Priority) -> Result<TaskHandle<T, E>, ExecutorError>
+    where
+        F: Future<Output = Result<T, E>> + Send + 'static,
+        T: Send + 'static,
+        E: Display + Send + 'static,
+    {
+        let (job, handle) = Job::fallible_with_handle(fut);
+        let id = job.id;
+        self.submit_job(None, job, priority).map_err(|error| error.in_operation("submit_fallible").for_task(id.get_value()))?;
+        Ok(handle)
+    }
+
+    // Task is created from the factory for every attempt, so it can be retried by options.retry_policy
+    // options.queue or options.name (routing rules) choose the queue, every attempt runs on it
+    pub fn submit_with_options<F, Fut, T, E>(&mut self, factory: F, options: TaskOptions<E>) -> Result<TaskHandle<T, E>, ExecutorError>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+        T: Send + 'static,
+        E: Display + Send + 'static,
+    {
+        let executor = self.route(options.name.as_deref(), options.queue.as_deref(), &options.priority)
+            .map_err(|error| error.in_operation("submit_with_options"))?;
+        RetryingTask::start(factory, options, executor)
+            .map_err(|error| error.in_operation("submit_with_options"))
+    }
+
+    // Canvas => steps of the chain are executed one after another, each output is passed to the next step
+    pub fn chain<T: Send + 'static>(&mut self, chain: Chain<T>, priority: Priority) -> Result<TaskHandle<T>, ExecutorError> {
+        let executor = self.route(None, None, &priority).map_err(|error| error.in_operation("chain"))?;
+        chain.apply(&executor, priority).map_err(|error| error.in_operation("chain"))
+    }
+
+    // Canvas => tasks of the group are executed in parallel, the handle returns all results
+    pub fn group<T: Send + 'static>(&mut self, group: Group<T>, priority: Priority) -> Result<TaskHandle<Vec<TaskResult<T>>>, ExecutorError> {
+        let executor = self.route(None, None, &priority).map_err(|error| error.in_operation("group"))?;
+        group.apply(&executor, priority).map_err(|error| error.in_operation("group"))
+    }
+
+    // Canvas => callback is executed with the results of the group when all group tasks are finished
+    pub fn chord<T: Send + 'static, U: Send + 'static>(&mut self, chord: Chord<T, U>, priority: Priority) -> Result<TaskHandle<U>, ExecutorError> {
+        let executor = self.route(None, None, &priority).map_err(|error| error.in_operation("chord"))?;
+        chord.apply(&executor, priority).map_err(|error| error.in_operation("chord"))
+    }
+
+    // Starts the periodic tasks on the default queue, every run goes through the rate limiter like task()
+    // Stop the returned handle before await_completion(), the beat keeps the executor running
+    pub fn beat(&mut self, beat: BeatScheduler) -> Result<BeatHandle, ExecutorError> {
+        let executor = self.executor.lock().unwrap().clone();
+        beat.start(executor).map_err(|error| error.in_operation("beat"))
+    }
+
+    // Explicit queue first, then the routing rules, then the default queue
+    // State of a task of any queue => id from task(), task_at() or handle.get_task_id()
+    // Err(UnknownTask) for ids of another Proxy and for tasks that finished long ago
+    pub fn get_task_state(&self, id: TaskId) -> Result<TaskState, ExecutorError> {
+        self.tracker
+            .get_state(id)
+            .ok_or(ExecutorError::UnknownTask(id.get_value()))
+            .map_err(|error| error.in_operation("get_task_state"))
+    }
+
+    fn route(&self, name: Option<&str>, queue: Option<&str>, priority: &Priority) -> Result<AsyncExecutor, ExecutorError> {
+        let routed_queue = match queue {
+            Some(queue) => Some(queue.to_string()),
+            None => self.router.lock().unwrap().route(&TaskInfo { name, priority }).map(String::from),
+        };
+
+        let executor = match routed_queue.as_deref() {
+            None | Some(DEFAULT_QUEUE) => self.executor.clone(),
+            Some(queue) => self.queues.get(queue)?,
+        };
+        let executor = executor.lock().unwrap().clone();
+        Ok(executor)
+    }
+
+    fn get_queue(&self, queue: &str) -> Result<ProxyExecutor, ExecutorError> {
+        match queue {
+            DEFAULT_QUEUE => Ok(self.executor.clone()),
+            queue => self.queues.get(queue),
+        }
+    }
+
+    // Default queue first
+    fn get_all_queues(&self) -> Vec<(String, ProxyExecutor)> {
+        let mut queues = vec![(DEFAULT_QUEUE.to_string(), self.executor.clone())];
+        queues.extend(self.queues.get_all());
+        queues
+    }
+
+    fn submit_job(&mut self, name: Option<&str>, job: Job, priority: Priority) -> Result<(), ExecutorError> {
         // TODO: I can implement more general logic to apply all limits from base_rate_limiter and after all (layers) pass then i delay() the task to executor
         // Have that in mind when creating addional rate limiting strategies
-        let mut slot_rate_limiter = SlotRateLimiter::new(self.executor.lock().unwrap().config.rate_limit_per_sec);
-        slot_rate_limiter.slot_limited(priority, self.executor.lock().unwrap().clone(), Box::pin(fut));
-    }   
+        let executor = self.route(name, None, &priority)?;
+        let job = match name {
+            Some(name) => job.with_name(name),
+            None => job,
+        };
+        SlotRateLimiter::delay_rate_limited(&executor, priority, job) // Rate limit of the queue
+    }
+
+    fn submit_job_with_mode(&mut self, job: Job, priority: Priority, mode: SubmitMode) -> Result<(), ExecutorError> {
+        let executor = self.route(None, None, &priority)?;
+        SlotRateLimiter::submit_rate_limited(&executor, priority, job, mode)
+    }
+
+    // Deploys and incidents => workers finish their running tasks and stop taking new ones, tasks sent meanwhile are rate limited and queued
+    // When the queue is full task() waits like always, try_task() returns Err(WorkerPoolFull)
+    // Pauses every queue, pause_queue() pauses only one
+    pub fn pause(&self) -> Result<(), ExecutorError> {
+        self.get_all_queues()
+            .iter()
+            .try_for_each(|(_, executor)| executor.lock().unwrap().pause())
+            .map_err(|error| error.in_operation("pause"))
+    }
 
-    pub fn await_completion(&self) {
-        self.executor.lock().unwrap().wait_all();
+    pub fn resume(&self) -> Result<(), ExecutorError> {
+        self.get_all_queues()
+            .iter()
+            .try_for_each(|(_, executor)| executor.lock().unwrap().resume())
+            .map_err(|error| error.in_operation("resume"))
+    }
+
+    pub fn pause_queue(&self, queue: &str) -> Result<(), ExecutorError> {
+        self.get_queue(queue)
+            .and_then(|executor| executor.lock().unwrap().pause())
+            .map_err(|error| error.in_operation("pause_queue"))
+    }
+
+    pub fn resume_queue(&self, queue: &str) -> Result<(), ExecutorError> {
+        self.get_queue(queue)
+            .and_then(|executor| executor.lock().unwrap().resume())
+            .map_err(|error| error.in_operation("resume_queue"))
+    }
+
+    // True when every queue is paused
+    pub fn is_paused(&self) -> bool {
+        self.get_all_queues().iter().all(|(_, executor)| executor.lock().unwrap().is_paused())
+    }
+
+    pub fn is_queue_paused(&self, queue: &str) -> Result<bool, ExecutorError> {
+        Ok(self.get_queue(queue)?.lock().unwrap().is_paused())
+    }
+
+    // Waits for every queue, resumes a paused executor, otherwise it would wait forever
+    pub fn await_completion(&self) -> Result<(), ExecutorError> {
+        self.get_all_queues()
+            .iter()
+            .try_for_each(|(_, executor)| executor.lock().unwrap().wait_all())
+            .map_err(|error| error.in_operation("await_completion"))
     }
 
     pub fn metrics(&mut self) {
-        self.executor.lock().unwrap().metrics.lock().unwrap().metrics_info();
+        for (queue, executor) in self.get_all_queues() {
+            println!("Queue \"{}\":", queue);
+            executor.lock().unwrap().metrics.lock().unwrap().metrics_info();
+        }
+    }
+
+    // Copy of the current metrics of the default queue
+    pub fn get_metrics(&self) -> MetricsReport {
+        *self.executor.lock().unwrap().metrics.lock().unwrap()
     }
 
+    pub fn get_queue_metrics(&self, queue: &str) -> Result<MetricsReport, ExecutorError> {
+        let executor = self.get_queue(queue).map_err(|error| error.in_operation("get_queue_metrics"))?;
+        let metrics = *executor.lock().unwrap().metrics.lock().unwrap();
+        Ok(metrics)
+    }
+
+    // Last changes of the pool size of the default queue (oldest first), the counters are in get_metrics()
+    pub fn get_scaling_events(&self) -> Vec<ScalingEvent> {
+        self.executor.lock().unwrap().scaling_history.lock().unwrap().get_events()
+    }
 
-    pub fn force_shutdown(&mut self) {
-        self.executor.lock().unwrap().force_shutdown();
+    pub fn get_queue_scaling_events(&self, queue: &str) -> Result<Vec<ScalingEvent>, ExecutorError> {
+        let executor = self.get_queue(queue).map_err(|error| error.in_operation("get_queue_scaling_events"))?;
+        let events = executor.lock().unwrap().scaling_history.lock().unwrap().get_events();
+        Ok(events)
+    }
+
+
+    pub fn force_shutdown(&mut self) -> Result<ShutdownReport, ExecutorError> {
+        self.shutdown_queues(ShutdownMode::Drain).map_err(|error| error.in_operation("force_shutdown"))
+    }
+
+    // Stops every queue by the mode (Drain, FinishRunning, Abort) and reports what happened with the tasks of all queues
+    pub fn shutdown(&mut self, mode: ShutdownMode) -> Result<ShutdownReport, ExecutorError> {
+        self.shutdown_queues(mode).map_err(|error| error.in_operation("shutdown"))
+    }
+
+    fn shutdown_queues(&mut self, mode: ShutdownMode) -> Result<ShutdownReport, ExecutorError> {
+        let mut report: Option<ShutdownReport> = None;
+        let mut first_error = None;
+
+        // Every queue is stopped even when one of them fails
+        for (_, executor) in self.get_all_queues() {
+            match executor.lock().unwrap().shutdown(mode) {
+                Ok(queue_report) => report = Some(report.map_or(queue_report, |report| report.merge(queue_report))),
+                Err(error) => { first_error.get_or_insert(error); }
+            }
+        }
+
+        match first_error {
+            Some(error) => Err(error),
+            None => Ok(report.expect("Default queue is always shut down")),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::retry::retry_policy::{Backoff, RetryPolicy};
     use std::sync::atomic::{AtomicUsize, Ordering};
-    use std::time::Duration;
     use std::thread;
 
 
     #[test]
     fn test_metrics() {
-        let mut proxy = Proxy::new();
+        let mut proxy = Proxy::new().unwrap();
         proxy.metrics(); 
     }
 
     #[test]
     fn test_task_execution() {
-        let mut proxy = Proxy::new();
+        let mut proxy = Proxy::new().unwrap();
         let counter = Arc::new(AtomicUsize::new(0));
         let counter_clone = counter.clone();
 
@@ -66,11 +556,906 @@ mod tests {
                 counter_clone.fetch_add(1, Ordering::SeqCst);
             },
             Priority::Medium,
+        ).unwrap();
+
+        thread::sleep(Duration::from_millis(100));
+        proxy.await_completion().unwrap();
+
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_waiting_tasks_should_not_block_the_worker() {
+        let mut proxy = Proxy::new().unwrap();
+        let start = std::time::Instant::now();
+
+        // More tasks than workers, each of them only waits on a timer
+        let total_tasks = proxy.executor.lock().unwrap().config.get_total_workers() * 3;
+        for _ in 0..total_tasks {
+            proxy.task(futures_timer::Delay::new(Duration::from_secs(1)), Priority::None).unwrap();
+        }
+
+        proxy.await_completion().unwrap();
+
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert_eq!(proxy.executor.lock().unwrap().metrics.lock().unwrap().get_tasks_count(), total_tasks as u32);
+    }
+
+    #[test]
+    fn test_submit_should_return_task_output() {
+        let mut proxy = Proxy::new().unwrap();
+
+        let handle = proxy.submit(async { 2 + 2 }, Priority::High).unwrap();
+        let awaited_handle = proxy.submit(async { String::from("awaited") }, Priority::None).unwrap();
+
+        assert_eq!(handle.join(), Ok(4));
+        assert_eq!(futures::executor::block_on(awaited_handle), Ok(String::from("awaited")));
+        proxy.await_completion().unwrap();
+    }
+
+    #[test]
+    fn test_submit_fallible_err_should_count_as_failed() {
+        let mut proxy = Proxy::new().unwrap();
+
+        let ok_handle = proxy.submit_fallible(async { Ok::<u32, String>(1) }, Priority::None).unwrap();
+        let err_handle = proxy.submit_fallible(async { Err::<u32, String>(String::from("service unavailable")) }, Priority::None).unwrap();
+
+        assert_eq!(ok_handle.join(), Ok(1));
+        assert_eq!(err_handle.join(), Err(crate::error_handler::error_handler::TaskError::Failed(String::from("service unavailable"))));
+        proxy.await_completion().unwrap();
+
+        let metrics = proxy.get_metrics();
+        assert_eq!(metrics.get_tasks_count(), 2);
+        assert_eq!(metrics.get_tasks_failed(), 1);
+    }
+
+    #[test]
+    fn test_submit_with_options_should_retry_till_success() {
+        let mut proxy = Proxy::new().unwrap();
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let attempts_clone = attempts.clone();
+
+        let options = TaskOptions::new(Priority::High).with_retry(
+            RetryPolicy::new(3)
+                .with_backoff(Backoff::Fixed(Duration::from_millis(10)))
+                .retry_on_error(|error: &String| error == "service unavailable"),
         );
 
+        let handle = proxy.submit_with_options(move || {
+            let attempt = attempts_clone.fetch_add(1, Ordering::SeqCst) + 1;
+            async move {
+                if attempt < 3 {
+                    Err(String::from("service unavailable"))
+                } else {
+                    Ok(attempt)
+                }
+            }
+        }, options).unwrap();
+
+        assert_eq!(handle.join(), Ok(3));
+        proxy.await_completion().unwrap();
+
+        let metrics = proxy.get_metrics();
+        assert_eq!(metrics.get_tasks_count(), 3);
+        assert_eq!(metrics.get_tasks_failed(), 2);
+        assert_eq!(metrics.get_tasks_retried(), 2);
+        assert_eq!(metrics.get_tasks_retries_exhausted(), 0);
+    }
+
+    #[test]
+    fn test_submit_with_options_should_stop_after_max_attempts() {
+        let mut proxy = Proxy::new().unwrap();
+
+        let options = TaskOptions::new(Priority::None).with_retry(
+            RetryPolicy::new(2)
+                .with_backoff(Backoff::Fixed(Duration::from_millis(10)))
+                .retry_on_panic(),
+        );
+
+        let handle = proxy.submit_with_options(|| async { 
+            if true {
+                panic!("always panics");
+            }
+            Ok::<(), String>(())
+        }, options).unwrap();
+
+        assert_eq!(handle.join(), Err(crate::error_handler::error_handler::TaskError::Panicked(String::from("always panics"))));
+        proxy.await_completion().unwrap();
+
+        let metrics = proxy.get_metrics();
+        assert_eq!(metrics.get_tasks_panicked(), 2);
+        assert_eq!(metrics.get_tasks_retried(), 1);
+        assert_eq!(metrics.get_tasks_retries_exhausted(), 1);
+    }
+
+    #[test]
+    fn test_retries_should_reserve_the_task_rate_limit() {
+        let mut proxy = Proxy::new().unwrap();
+        let starts = Arc::new(Mutex::new(Vec::new()));
+        let starts_clone = starts.clone();
+
+        // Retry without backoff would start right away, the rate limit moves it a second after the first attempt
+        let options = TaskOptions::new(Priority::None)
+            .with_retry(RetryPolicy::new(2).with_backoff(Backoff::Fixed(Duration::ZERO)).retry_on_any_error())
+            .with_rate_limit("1/s".parse().unwrap());
+
+        let handle = proxy.submit_with_options(move || {
+            let starts = starts_clone.clone();
+            async move {
+                let mut starts = starts.lock().unwrap();
+                starts.push(Instant::now());
+                if starts.len() < 2 {
+                    Err(String::from("service unavailable"))
+                } else {
+                    Ok(())
+                }
+            }
+        }, options).unwrap();
+
+        assert_eq!(handle.join(), Ok(()));
+        let starts = starts.lock().unwrap();
+        assert_eq!(starts.len(), 2);
+        assert!(starts[1] - starts[0] >= Duration::from_millis(900));
+    }
+
+    #[test]
+    fn test_panicking_task_should_keep_worker_alive() {
+        let mut proxy = Proxy::new().unwrap();
+
+        let panicked_handle = proxy.submit(async { panic!("worker should survive: {}", 42) }, Priority::None).unwrap();
+        assert_eq!(panicked_handle.join(), Err(crate::error_handler::error_handler::TaskError::Panicked(String::from("worker should survive: 42"))));
+
+        // Workers are still serving the channel
+        let handle = proxy.submit(async { 1 }, Priority::None).unwrap();
+        assert_eq!(handle.join(), Ok(1));
+
+        proxy.await_completion().unwrap();
+        proxy.force_shutdown().unwrap(); // All workers ack the shutdown
+
+        let metrics = proxy.get_metrics();
+        assert_eq!(metrics.get_tasks_panicked(), 1);
+        assert_eq!(metrics.get_tasks_failed(), 1);
+    }
+
+    #[test]
+    fn test_task_after_should_run_after_countdown() {
+        let mut proxy = Proxy::new().unwrap();
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_clone = counter.clone();
+        let start = Instant::now();
+
+        proxy.task_after(async move {
+            counter_clone.fetch_add(1, Ordering::SeqCst);
+        }, Priority::None, Duration::from_millis(300)).unwrap();
+
         thread::sleep(Duration::from_millis(100));
-        proxy.await_completion();
+        assert_eq!(counter.load(Ordering::SeqCst), 0); // Still waiting in the scheduler
+
+        proxy.await_completion().unwrap(); // Waits for scheduled tasks too
+
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert!(start.elapsed() >= Duration::from_millis(300));
+    }
+
+    #[test]
+    fn test_task_at_should_release_tasks_by_eta() {
+        let mut proxy = Proxy::new().unwrap();
+        let order = Arc::new(Mutex::new(vec![]));
+        let now = Instant::now();
+
+        for (name, delay) in [("third", 300), ("first", 100), ("second", 200)] {
+            let order_clone = order.clone();
+            proxy.task_at(async move {
+                order_clone.lock().unwrap().push(name);
+            }, Priority::None, now + Duration::from_millis(delay)).unwrap();
+        }
+
+        proxy.await_completion().unwrap();
+
+        assert_eq!(*order.lock().unwrap(), vec!["first", "second", "third"]);
+    }
+
+    #[test]
+    fn test_scheduled_task_should_not_count_delay_as_timeout() {
+        let mut proxy = Proxy::new().unwrap();
+        let timeout = proxy.executor.lock().unwrap().config.get_task_timeout();
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_clone = counter.clone();
+
+        proxy.task_after(async move {
+            counter_clone.fetch_add(1, Ordering::SeqCst);
+        }, Priority::None, timeout + Duration::from_millis(100)).unwrap();
+
+        proxy.await_completion().unwrap();
 
         assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert_eq!(proxy.get_metrics().get_tasks_failed(), 0);
+    }
+
+    #[test]
+    fn test_scheduled_task_should_not_count_delay_as_custom_timeout() {
+        let config = crate::executor_config::ExecutorConfigBuilder::new().with_task_timeout(Duration::from_millis(200)).build().unwrap();
+        let mut proxy = Proxy::with_config(config).unwrap();
+        let timeout = proxy.executor.lock().unwrap().config.get_task_timeout();
+        assert_eq!(timeout, Duration::from_millis(200));
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_clone = counter.clone();
+
+        proxy.task_after(async move {
+            counter_clone.fetch_add(1, Ordering::SeqCst);
+        }, Priority::None, timeout + Duration::from_millis(100)).unwrap();
+
+        proxy.await_completion().unwrap();
+
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert_eq!(proxy.get_metrics().get_tasks_failed(), 0);
+    }
+
+    #[test]
+    fn test_try_task_and_task_timeout_should_be_accepted_with_free_queue() {
+        let mut proxy = Proxy::new().unwrap();
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let try_counter = counter.clone();
+        assert!(proxy.try_task(async move { try_counter.fetch_add(1, Ordering::SeqCst); }, Priority::High).is_ok());
+
+        let timeout_counter = counter.clone();
+        assert!(proxy.task_timeout(async move { timeout_counter.fetch_add(1, Ordering::SeqCst); }, Priority::None, Duration::from_millis(100)).is_ok());
+
+        proxy.await_completion().unwrap();
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_with_config_should_use_custom_timeout_and_workers() {
+        let config = crate::executor_config::ExecutorConfigBuilder::new()
+            .with_worker_count(2)
+            .with_task_timeout(Duration::from_millis(200))
+            .build()
+            .unwrap();
+        let mut proxy = Proxy::with_config(config).unwrap();
+        let start = Instant::now();
+
+        let handle = proxy.submit(async { futures_timer::Delay::new(Duration::from_secs(5)).await }, Priority::None).unwrap();
+
+        assert_eq!(handle.join(), Err(crate::error_handler::error_handler::TaskError::Timeout));
+        assert!(start.elapsed() < Duration::from_secs(1));
+        proxy.await_completion().unwrap();
+
+        proxy.force_shutdown().unwrap(); // Both workers ack the shutdown
+        assert_eq!(proxy.get_metrics().get_tasks_failed(), 1);
+    }
+
+    #[test]
+    fn test_try_task_should_fail_when_queue_is_full() {
+        let config = crate::executor_config::ExecutorConfigBuilder::new()
+            .with_worker_count(1)
+            .with_max_tasks_per_worker(1)
+            .with_queue_capacity(1)
+            .build()
+            .unwrap();
+        let mut proxy = Proxy::with_config(config).unwrap();
+
+        // Takes the only place in the worker, the next one waits in the queue
+        proxy.task(futures_timer::Delay::new(Duration::from_millis(300)), Priority::None).unwrap();
+        thread::sleep(Duration::from_millis(50));
+        proxy.task(futures_timer::Delay::new(Duration::from_millis(10)), Priority::None).unwrap();
+
+        let result = proxy.try_task(async {}, Priority::High);
+        assert!(matches!(result.as_ref().map_err(ExecutorError::root_cause), Err(ExecutorError::WorkerPoolFull)));
+        assert_eq!(result.unwrap_err().get_operation(), Some("try_task"));
+
+        let result = proxy.task_timeout(async {}, Priority::High, Duration::from_secs(1));
+        assert!(result.is_ok());
+        proxy.await_completion().unwrap();
+    }
+
+    fn single_slot_proxy(shutdown_timeout: Duration) -> Proxy {
+        let config = crate::executor_config::ExecutorConfigBuilder::new()
+            .with_worker_count(1)
+            .with_max_tasks_per_worker(1)
+            .with_shutdown_timeout(shutdown_timeout)
+            .build()
+            .unwrap();
+        Proxy::with_config(config).unwrap()
+    }
+
+    // Polls the condition instead of sleeping a fixed time, false when it was not true till the timeout
+    fn wait_until(timeout: Duration, condition: impl Fn() -> bool) -> bool {
+        let deadline = Instant::now() + timeout;
+
+        while !condition() {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        true
+    }
+
+    #[test]
+    fn test_drain_shutdown_should_finish_queued_and_running_tasks() {
+        let mut proxy = single_slot_proxy(Duration::from_secs(5));
+
+        let handles: Vec<TaskHandle<u32>> = (0..3)
+            .map(|idx| proxy.submit(async move {
+                futures_timer::Delay::new(Duration::from_millis(100)).await;
+                idx
+            }, Priority::None).unwrap())
+            .collect();
+
+        let report = proxy.shutdown(ShutdownMode::Drain).unwrap();
+
+        assert_eq!(report, ShutdownReport { mode: ShutdownMode::Drain, completed: 3, cancelled: 0, abandoned: 0, timed_out: false });
+        assert_eq!(handles.into_iter().map(|handle| handle.join().unwrap()).collect::<Vec<u32>>(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_drain_shutdown_should_not_wait_for_scheduled_tasks() {
+        let mut proxy = single_slot_proxy(Duration::from_secs(5));
+
+        // Scheduled job holds an executor clone => before, Drain waited for it till shutdown_timeout
+        let options = TaskOptions::new(Priority::None).with_countdown(Duration::from_secs(60));
+        let handle = proxy.submit_with_options(|| async { Ok::<(), String>(()) }, options).unwrap();
+        let id = handle.get_task_id().unwrap();
+
+        let start = Instant::now();
+        let report = proxy.shutdown(ShutdownMode::Drain).unwrap();
+
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(report, ShutdownReport { mode: ShutdownMode::Drain, completed: 0, cancelled: 0, abandoned: 1, timed_out: false });
+        assert_eq!(handle.join(), Err(crate::error_handler::error_handler::TaskError::Cancelled));
+        assert_eq!(proxy.get_task_state(id).unwrap(), TaskState::Cancelled);
+    }
+
+    #[test]
+    fn test_finish_running_shutdown_should_cancel_queued_tasks() {
+        let mut proxy = single_slot_proxy(Duration::from_secs(5));
+
+        let running = proxy.submit(futures_timer::Delay::new(Duration::from_millis(300)), Priority::None).unwrap();
+        thread::sleep(Duration::from_millis(50)); // Worker takes the first task, the others stay in the queue
+        let queued: Vec<TaskHandle<()>> = (0..2).map(|_| proxy.submit(async {}, Priority::None).unwrap()).collect();
+
+        let report = proxy.shutdown(ShutdownMode::FinishRunning).unwrap();
+
+        assert_eq!(report.completed, 1);
+        assert_eq!(report.cancelled, 2);
+        assert_eq!(report.abandoned, 0);
+        assert_eq!(running.join(), Ok(()));
+        for handle in queued {
+            assert_eq!(handle.join(), Err(crate::error_handler::error_handler::TaskError::Cancelled));
+        }
+    }
+
+    #[test]
+    fn test_abort_shutdown_should_cancel_running_tasks() {
+        let mut proxy = single_slot_proxy(Duration::from_secs(5));
+        let start = Instant::now();
+
+        let running = proxy.submit(futures_timer::Delay::new(Duration::from_secs(3)), Priority::None).unwrap();
+        thread::sleep(Duration::from_millis(50));
+        let queued = proxy.submit(async {}, Priority::None).unwrap();
+
+        let report = proxy.shutdown(ShutdownMode::Abort).unwrap();
+
+        assert_eq!(report, ShutdownReport { mode: ShutdownMode::Abort, completed: 0, cancelled: 2, abandoned: 0, timed_out: false });
+        assert_eq!(proxy.get_task_state(running.get_task_id().unwrap()).unwrap(), TaskState::Cancelled);
+        assert_eq!(proxy.get_task_state(queued.get_task_id().unwrap()).unwrap(), TaskState::Cancelled);
+        assert_eq!(running.join(), Err(crate::error_handler::error_handler::TaskError::Cancelled));
+        assert_eq!(queued.join(), Err(crate::error_handler::error_handler::TaskError::Cancelled));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_shutdown_timeout_should_report_abandoned_tasks_instead_of_panicking() {
+        let mut proxy = single_slot_proxy(Duration::from_millis(200));
+
+        // Blocks the worker thread, so it can't ack in time
+        proxy.task(async { thread::sleep(Duration::from_millis(600)) }, Priority::None).unwrap();
+        thread::sleep(Duration::from_millis(50));
+
+        let report = proxy.shutdown(ShutdownMode::Drain).unwrap();
+
+        assert!(report.timed_out);
+        assert_eq!(report.abandoned, 1);
+        assert_eq!(report.completed, 0);
+    }
+
+    #[test]
+    fn test_operations_after_shutdown_should_return_errors_with_context() {
+        let mut proxy = single_slot_proxy(Duration::from_millis(200));
+        proxy.shutdown(ShutdownMode::Drain).unwrap();
+
+        let task_error = proxy.task(async {}, Priority::None).unwrap_err();
+        assert_eq!(task_error.get_operation(), Some("task"));
+        assert!(matches!(task_error.root_cause(), ExecutorError::ChannelConnectionIsNotEstablished));
+        assert!(std::error::Error::source(&task_error).is_some());
+
+        let submit_error = proxy.submit(async { 1 }, Priority::High).err().unwrap();
+        let submit_id = submit_error.get_task_id().unwrap();
+        assert_eq!(submit_error.to_string(), format!("submit failed for task {}: {}", submit_id, ExecutorError::ChannelConnectionIsNotEstablished));
+        assert_eq!(proxy.get_task_state(TaskId::next()).unwrap_err().get_operation(), Some("get_task_state"));
+
+        let shutdown_error = proxy.shutdown(ShutdownMode::Abort).unwrap_err();
+        assert!(matches!(shutdown_error.root_cause(), ExecutorError::ShutDownError));
+    }
+
+    #[test]
+    fn test_pool_should_grow_under_load_and_shrink_when_idle() {
+        let config = crate::executor_config::ExecutorConfigBuilder::new()
+            .with_rate_limit_per_sec(100)
+            .with_min_workers(1)
+            .with_max_workers(3)
+            .with_max_tasks_per_worker(1)
+            .with_scale_up_queue_depth(2)
+            .with_worker_keep_alive(Duration::from_millis(300))
+            .with_shutdown_timeout(Duration::from_secs(2))
+            .build()
+            .unwrap();
+        let mut proxy = Proxy::with_config(config).unwrap();
+
+        for _ in 0..8 {
+            proxy.task(futures_timer::Delay::new(Duration::from_millis(200)), Priority::None).unwrap();
+        }
+        assert!(wait_until(Duration::from_secs(10), || proxy.get_metrics().get_peak_pool_size() > 1));
+
+        // Queue is empty, extra workers reach the keep alive
+        assert!(wait_until(Duration::from_secs(10), || {
+            let metrics = proxy.get_metrics();
+            metrics.get_tasks_count() == 8 && metrics.get_pool_size() == 1
+        }));
+        let metrics = proxy.get_metrics();
+        assert!(metrics.get_scale_ups() > 0 && metrics.get_scale_downs() == metrics.get_scale_ups());
+
+        let events = proxy.get_scaling_events();
+        assert_eq!(events.len() as u32, metrics.get_scale_ups() + metrics.get_scale_downs());
+        assert_eq!(events.last().unwrap().pool_size, 1);
+
+        let report = proxy.shutdown(ShutdownMode::Drain).unwrap();
+        assert!(!report.timed_out);
+    }
+
+    #[test]
+    fn test_paused_executor_should_queue_tasks_till_resume() {
+        let mut proxy = single_slot_proxy(Duration::from_secs(2));
+        let finished = Arc::new(AtomicUsize::new(0));
+
+        let running = finished.clone();
+        proxy.task(async move {
+            futures_timer::Delay::new(Duration::from_millis(200)).await;
+            running.fetch_add(1, Ordering::SeqCst);
+        }, Priority::None).unwrap();
+        thread::sleep(Duration::from_millis(50));
+
+        proxy.pause().unwrap();
+        assert!(proxy.is_paused());
+
+        for _ in 0..3 {
+            let queued = finished.clone();
+            proxy.task(async move { queued.fetch_add(1, Ordering::SeqCst); }, Priority::High).unwrap();
+        }
+        thread::sleep(Duration::from_millis(400));
+
+        assert_eq!(finished.load(Ordering::SeqCst), 1); // Running task is finished, the queued ones wait
+        assert_eq!(proxy.get_metrics().get_tasks_count(), 1);
+
+        proxy.resume().unwrap();
+        thread::sleep(Duration::from_millis(300));
+        assert_eq!(finished.load(Ordering::SeqCst), 4);
+
+        proxy.await_completion().unwrap();
+        assert!(proxy.pause().is_err()); // Channel is closed
+    }
+
+    fn queue_config(workers: usize) -> ExecutorConfig {
+        crate::executor_config::ExecutorConfigBuilder::new()
+            .with_rate_limit_per_sec(50)
+            .with_worker_count(workers)
+            .with_max_tasks_per_worker(1)
+            .with_shutdown_timeout(Duration::from_secs(2))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_routed_tasks_should_not_wait_for_slow_queue() {
+        let mut proxy = Proxy::with_config(queue_config(1)).unwrap();
+        proxy.add_queue("payments", queue_config(1)).unwrap();
+        proxy.add_queue("emails", queue_config(1)).unwrap();
+        proxy.set_router(Router::new()
+            .route_task("charge_card", "payments")
+            .route_when(|task| task.name.is_some_and(|name| name.starts_with("send_")), "emails")
+        ).unwrap();
+
+        // Both block the only payments worker
+        for _ in 0..2 {
+            proxy.task_named("charge_card", async { thread::sleep(Duration::from_millis(400)) }, Priority::High).unwrap();
+        }
+        let mut email = proxy.submit_named("send_email", async { "sent" }, Priority::None).unwrap();
+        let mut unrouted = proxy.submit(async { 1 }, Priority::None).unwrap();
+
+        assert_eq!(email.join_timeout(Duration::from_millis(300)), Some(Ok("sent")));
+        assert_eq!(unrouted.join_timeout(Duration::from_millis(300)), Some(Ok(1)));
+
+        let report = proxy.shutdown(ShutdownMode::Drain).unwrap();
+        assert!(!report.timed_out);
+        assert!(report.completed >= 1); // Second payment finishes in the payments queue during the drain
+        assert_eq!(proxy.get_queue_metrics("payments").unwrap().get_tasks_count(), 2);
+        assert_eq!(proxy.get_queue_metrics("emails").unwrap().get_tasks_count(), 1);
+        assert_eq!(proxy.get_metrics().get_tasks_count(), 1);
+    }
+
+    #[test]
+    fn test_queue_errors_and_per_queue_pause() {
+        let mut proxy = Proxy::with_config(queue_config(1)).unwrap();
+        proxy.add_queue("emails", queue_config(1)).unwrap();
+
+        let duplicate = proxy.add_queue("emails", queue_config(1)).unwrap_err();
+        assert!(matches!(duplicate.root_cause(), ExecutorError::InvalidConfig(_)));
+
+        let unknown = proxy.set_router(Router::new().route_task("charge_card", "payments")).unwrap_err();
+        assert!(matches!(unknown.root_cause(), ExecutorError::UnknownQueue(queue) if queue == "payments"));
+
+        let options = TaskOptions::<String>::new(Priority::None).with_queue("payments");
+        let result = proxy.submit_with_options(|| async { Ok::<(), String>(()) }, options);
+        assert!(matches!(result.err().unwrap().root_cause(), ExecutorError::UnknownQueue(_)));
+
+        proxy.pause_queue("emails").unwrap();
+        assert!(proxy.is_queue_paused("emails").unwrap());
+        assert!(!proxy.is_queue_paused(DEFAULT_QUEUE).unwrap());
+        assert!(!proxy.is_paused());
+
+        let options = TaskOptions::<String>::new(Priority::None).with_queue("emails");
+        let mut paused = proxy.submit_with_options(|| async { Ok::<u8, String>(1) }, options).unwrap();
+        assert_eq!(paused.join_timeout(Duration::from_millis(200)), None);
+
+        proxy.resume_queue("emails").unwrap();
+        assert_eq!(paused.join_timeout(Duration::from_secs(1)), Some(Ok(1)));
+        proxy.await_completion().unwrap();
+    }
+
+    #[test]
+    fn test_task_state_should_follow_the_lifecycle() {
+        let mut proxy = single_slot_proxy(Duration::from_secs(2));
+        proxy.pause().unwrap();
+
+        let mut handle = proxy.submit(futures_timer::Delay::new(Duration::from_millis(300)), Priority::None).unwrap();
+        let id = handle.get_task_id().unwrap();
+        assert_eq!(proxy.get_task_state(id).unwrap(), TaskState::Queued);
+
+        proxy.resume().unwrap();
+        let deadline = Instant::now() + Duration::from_millis(250); // Paused worker checks the channel every 100ms
+        while proxy.get_task_state(id).unwrap() == TaskState::Queued && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(proxy.get_task_state(id).unwrap(), TaskState::Running);
+
+        assert_eq!(handle.join_timeout(Duration::from_secs(1)), Some(Ok(())));
+        proxy.await_completion().unwrap(); // Worker records the state after the handle is completed
+        assert_eq!(proxy.get_task_state(id).unwrap(), TaskState::Succeeded);
+
+        let unknown = proxy.get_task_state(TaskId::next()).unwrap_err();
+        assert!(matches!(unknown.root_cause(), ExecutorError::UnknownTask(_)));
+    }
+
+    #[test]
+    fn test_retried_task_should_keep_its_id() {
+        let mut proxy = Proxy::new().unwrap();
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let attempts_clone = attempts.clone();
+
+        let options = TaskOptions::new(Priority::None)
+            .with_retry(RetryPolicy::new(2).with_backoff(Backoff::Fixed(Duration::from_millis(300))).retry_on_any_error());
+
+        let handle = proxy.submit_with_options(move || {
+            let attempt = attempts_clone.fetch_add(1, Ordering::SeqCst) + 1;
+            async move { if attempt < 2 { Err(String::from("try again")) } else { Ok(attempt) } }
+        }, options).unwrap();
+        let id = handle.get_task_id().unwrap();
+
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(proxy.get_task_state(id).unwrap(), TaskState::Retrying);
+
+        assert_eq!(handle.join(), Ok(2));
+        proxy.await_completion().unwrap();
+        assert_eq!(proxy.get_task_state(id).unwrap(), TaskState::Succeeded);
+    }
+
+    #[test]
+    fn test_workers_should_store_results_in_the_backend() {
+        let mut proxy = Proxy::new().unwrap();
+        let not_set = proxy.get_result(TaskId::next()).unwrap_err();
+        assert!(matches!(not_set.root_cause(), ExecutorError::NoResultBackend));
+
+        let backend = Arc::new(crate::result_backend::memory_backend::MemoryResultBackend::new());
+        proxy.set_result_backend(backend.clone()).unwrap();
+
+        let stored = proxy.submit_stored(async { vec![String::from("sent")] }, Priority::None).unwrap();
+        let failed = proxy.submit_fallible(async { Err::<(), String>(String::from("smtp down")) }, Priority::None).unwrap();
+        let options = TaskOptions::<String>::new(Priority::None).with_result_ttl(Duration::from_millis(100));
+        let short_lived = proxy.submit_with_options(|| async { Ok::<u8, String>(1) }, options).unwrap();
+        let (stored_id, failed_id, short_lived_id) = (stored.get_task_id().unwrap(), failed.get_task_id().unwrap(), short_lived.get_task_id().unwrap());
+
+        assert!(stored.join().is_ok() && failed.join().is_err() && short_lived.join().is_ok());
+        proxy.await_completion().unwrap(); // Results are stored by the workers after the handles are completed
+
+        let result = proxy.get_result(stored_id).unwrap().unwrap();
+        assert_eq!(result.state, TaskState::Succeeded);
+        assert_eq!(result.get_output::<Vec<String>>().unwrap(), Some(vec![String::from("sent")]));
+
+        let result = proxy.get_result(failed_id).unwrap().unwrap();
+        assert_eq!((result.state, result.error.as_deref(), result.output), (TaskState::Failed, Some("smtp down"), None));
+
+        assert!(proxy.get_result(short_lived_id).unwrap().is_some());
+        thread::sleep(Duration::from_millis(150));
+        assert_eq!(proxy.get_result(short_lived_id).unwrap(), None); // Config TTL is 1 day, the task has its own
+        assert_eq!(backend.len(), 3); // Reaper runs every minute by default
+    }
+
+    #[test]
+    fn test_queued_tasks_cancelled_by_shutdown_should_store_cancelled_results() {
+        let mut proxy = single_slot_proxy(Duration::from_secs(5));
+        proxy.set_result_backend(Arc::new(crate::result_backend::memory_backend::MemoryResultBackend::new())).unwrap();
+
+        let running = proxy.submit(futures_timer::Delay::new(Duration::from_millis(300)), Priority::None).unwrap();
+        thread::sleep(Duration::from_millis(50)); // Worker takes the first task, the second stays in the queue
+        let queued = proxy.submit(async {}, Priority::None).unwrap();
+        let (running_id, queued_id) = (running.get_task_id().unwrap(), queued.get_task_id().unwrap());
+
+        proxy.shutdown(ShutdownMode::FinishRunning).unwrap();
+
+        let result = proxy.get_result(queued_id).unwrap().unwrap();
+        assert_eq!(result.state, TaskState::Cancelled);
+        assert!(result.error.is_some());
+
+        let result = proxy.get_result(running_id).unwrap().unwrap();
+        assert_eq!(result.state, TaskState::Succeeded);
+        assert!(result.execution_time >= Duration::from_millis(250)); // Sub-second tasks are not stored as 0
+    }
+
+    fn counting_factory(counter: Arc<AtomicUsize>) -> impl crate::durability::task_factory::TaskFactory {
+        move |name: &str, args: &serde_json::Value| -> Result<crate::core::types::FallibleTask, ExecutorError> {
+            let amount = args.as_u64().ok_or(ExecutorError::InvalidTaskArgs(args.to_string()))? as usize;
+            let counter = counter.clone();
+
+            match name {
+                "count" => Ok(Box::pin(async move {
+                    counter.fetch_add(amount, Ordering::SeqCst);
+                    Ok(())
+                })),
+                name => Err(ExecutorError::UnknownTaskName(name.to_string())),
+            }
+        }
+    }
+
+    #[test]
+    fn test_durable_tasks_should_be_replayed_after_restart() {
+        let dir = std::env::temp_dir().join(format!("rust-task-1-proxy-wal-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("tasks.wal");
+
+        // Previous run accepted the task and stopped before it was done
+        let previous_run = WriteAheadLog::open(&path).unwrap();
+        previous_run.append(WalEntry::new("count", serde_json::json!(10), Priority::High)).unwrap();
+        drop(previous_run);
+
+        let mut proxy = Proxy::new().unwrap();
+        let not_enabled = proxy.submit_durable("count", 1, Priority::None).unwrap_err();
+        assert!(matches!(not_enabled.root_cause(), ExecutorError::DurabilityNotEnabled));
+
+        let counter = Arc::new(AtomicUsize::new(0));
+        assert_eq!(proxy.enable_durability(&path, counting_factory(counter.clone())).unwrap(), 1);
+
+        proxy.submit_durable("count", 1, Priority::None).unwrap();
+        let unknown = proxy.submit_durable("send_email", 1, Priority::None).unwrap_err();
+        assert!(matches!(unknown.root_cause(), ExecutorError::UnknownTaskName(name) if name == "send_email"));
+        let invalid = proxy.submit_durable("count", "ten", Priority::None).unwrap_err();
+        assert!(matches!(invalid.root_cause(), ExecutorError::InvalidTaskArgs(_)));
+
+        proxy.await_completion().unwrap();
+        assert_eq!(counter.load(Ordering::SeqCst), 11);
+        assert!(WriteAheadLog::open(&path).unwrap().get_pending().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_submit_registered_should_run_the_task_by_name() {
+        #[derive(serde::Deserialize)]
+        struct EmailArgs {
+            to: String,
+        }
+
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let sent_clone = sent.clone();
+        let mut registry = TaskRegistry::new();
+        registry.register("send_email", move |args: EmailArgs| {
+            let sent = sent_clone.clone();
+            async move {
+                if args.to.is_empty() {
+                    return Err("no recipient");
+                }
+                sent.lock().unwrap().push(args.to);
+                Ok(())
+            }
+        });
+
+        let mut proxy = Proxy::new().unwrap();
+        proxy.set_registry(registry);
+
+        let sent_handle = proxy.submit_registered("send_email", serde_json::json!({ "to": "satoshi@example.com" }), Priority::High).unwrap();
+        let failed_handle = proxy.submit_registered("send_email", serde_json::json!({ "to": "" }), Priority::None).unwrap();
+        assert_eq!(sent_handle.join(), Ok(()));
+        assert_eq!(failed_handle.join(), Err(crate::error_handler::error_handler::TaskError::Failed(String::from("no recipient"))));
+        assert_eq!(*sent.lock().unwrap(), vec![String::from("satoshi@example.com")]);
+
+        let unknown = proxy.submit_registered("send_sms", serde_json::json!({}), Priority::None).err().unwrap();
+        assert!(matches!(unknown.root_cause(), ExecutorError::UnknownTaskName(_)));
+        let invalid = proxy.submit_registered("send_email", 42, Priority::None).err().unwrap();
+        assert!(matches!(invalid.root_cause(), ExecutorError::InvalidTaskArgs(_)));
+        assert!(proxy.get_registry().contains("send_email"));
+    }
+
+    #[test]
+    fn test_panicking_registered_factory_should_not_poison_the_registry() {
+        let mut registry = TaskRegistry::new();
+        registry.register("explode", |_: u32| -> futures::future::Ready<Result<(), String>> { panic!("factory exploded") });
+        registry.register("count", |amount: u32| async move { Ok::<u32, String>(amount) });
+
+        let mut proxy = Proxy::new().unwrap();
+        proxy.set_registry(registry);
+
+        // Factory runs outside of the registry lock => the panic doesn't poison it
+        let exploded = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| proxy.submit_registered("explode", 1, Priority::None)));
+        assert!(exploded.is_err());
+
+        assert!(proxy.get_registry().contains("explode"));
+        assert_eq!(proxy.submit_registered("count", 2, Priority::None).unwrap().join(), Ok(()));
+    }
+
+    #[test]
+    fn test_panicking_middleware_should_not_stop_the_worker() {
+        use crate::middleware::{middleware::Middleware, task_context::TaskContext};
+        use crate::core::types::FallibleTask;
+        use crate::error_handler::error_handler::TaskError;
+
+        struct Exploding;
+
+        impl Middleware for Exploding {
+            fn on_start(&self, _task: &TaskContext) {
+                panic!("on_start exploded");
+            }
+
+            fn on_success(&self, _task: &TaskContext) {
+                panic!("on_success exploded");
+            }
+
+            fn on_failure(&self, _task: &TaskContext, _error: &str) {
+                panic!("on_failure exploded");
+            }
+
+            fn on_timeout(&self, _task: &TaskContext) {
+                panic!("on_timeout exploded");
+            }
+
+            fn wrap(&self, task: &TaskContext, future: FallibleTask) -> FallibleTask {
+                if task.name.as_deref() == Some("wrapped") {
+                    panic!("wrap exploded");
+                }
+                future
+            }
+        }
+
+        let mut proxy = single_slot_proxy(Duration::from_secs(5)); // One worker => every task below needs the same thread
+        proxy.add_middleware(Arc::new(Exploding));
+
+        let succeeded = proxy.submit(async { 1 }, Priority::None).unwrap();
+        assert_eq!(succeeded.join(), Ok(1));
+        let failed = proxy.submit_fallible(async { Err::<(), String>(String::from("smtp down")) }, Priority::None).unwrap();
+        assert_eq!(failed.join(), Err(TaskError::Failed(String::from("smtp down"))));
+        let options = TaskOptions::<String>::new(Priority::None).with_timeout(Duration::from_millis(50));
+        let timed_out = proxy.submit_with_options(|| async {
+            futures_timer::Delay::new(Duration::from_secs(1)).await;
+            Ok::<(), String>(())
+        }, options).unwrap();
+        assert_eq!(timed_out.join(), Err(TaskError::Timeout));
+
+        // wrap() took the future before it panicked => the attempt fails instead of running
+        let options = TaskOptions::<String>::new(Priority::None).with_name("wrapped");
+        let wrapped = proxy.submit_with_options(|| async { Ok::<(), String>(()) }, options).unwrap();
+        let wrapped_id = wrapped.get_task_id().unwrap();
+        assert!(wrapped.join().is_err());
+
+        let still_serving = proxy.submit(async { 2 }, Priority::None).unwrap();
+        assert_eq!(still_serving.join(), Ok(2));
+        proxy.await_completion().unwrap();
+        assert_eq!(proxy.get_task_state(wrapped_id).unwrap(), TaskState::Failed);
+    }
+
+    #[test]
+    fn test_middleware_should_see_every_hook_of_the_tasks() {
+        use crate::middleware::{middleware::Middleware, task_context::TaskContext};
+        use crate::core::types::FallibleTask;
+
+        #[derive(Default)]
+        struct Audit {
+            calls: Mutex<Vec<String>>,
+            polled_inside: AtomicUsize,
+        }
+
+        impl Audit {
+            fn record(&self, hook: &str, task: &TaskContext) {
+                self.calls.lock().unwrap().push(format!("{} {} #{}", hook, task.name.as_deref().unwrap_or("anonymous"), task.attempt));
+            }
+        }
+
+        impl Middleware for Audit {
+            fn before_submit(&self, task: &TaskContext) -> Result<(), ExecutorError> {
+                self.record("before_submit", task);
+                match task.name.as_deref() {
+                    Some("forbidden") => Err(ExecutorError::TaskRejected(String::from("no auth context"))),
+                    _ => Ok(()),
+                }
+            }
+            fn on_start(&self, task: &TaskContext) { self.record("on_start", task) }
+            fn on_success(&self, task: &TaskContext) { self.record("on_success", task) }
+            fn on_failure(&self, task: &TaskContext, _error: &str) { self.record("on_failure", task) }
+            fn on_timeout(&self, task: &TaskContext) { self.record("on_timeout", task) }
+            fn on_retry(&self, task: &TaskContext, _error: &str, _backoff: Duration) { self.record("on_retry", task) }
+        }
+
+        struct CountPolls(Arc<Audit>);
+
+        impl Middleware for CountPolls {
+            fn wrap(&self, _task: &TaskContext, future: FallibleTask) -> FallibleTask {
+                let audit = self.0.clone();
+                Box::pin(async move {
+                    audit.polled_inside.fetch_add(1, Ordering::SeqCst);
+                    future.await
+                })
+            }
+        }
+
+        let audit = Arc::new(Audit::default());
+        let mut proxy = Proxy::new().unwrap();
+        proxy.add_middleware(audit.clone());
+        proxy.add_middleware(Arc::new(CountPolls(audit.clone())));
+
+        let rejected = proxy.task_named("forbidden", async {}, Priority::None).unwrap_err();
+        assert!(matches!(rejected.root_cause(), ExecutorError::TaskRejected(_)));
+        proxy.task_named("send_email", async {}, Priority::None).unwrap();
+
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let attempts_clone = attempts.clone();
+        let flaky = TaskOptions::new(Priority::None)
+            .with_name("flaky")
+            .with_retry(RetryPolicy::new(2).with_backoff(Backoff::Fixed(Duration::from_millis(10))).retry_on_any_error());
+        let flaky = proxy.submit_with_options(move || {
+            let attempts = attempts_clone.clone();
+            async move {
+                match attempts.fetch_add(1, Ordering::SeqCst) {
+                    0 => Err(String::from("first attempt fails")),
+                    _ => Ok(()),
+                }
+            }
+        }, flaky).unwrap();
+        let failing = proxy.submit_fallible(async { Err::<(), String>(String::from("always fails")) }, Priority::None).unwrap();
+        let sleepy = proxy.submit_with_options(
+            || async { futures_timer::Delay::new(Duration::from_secs(5)).await; Ok::<(), String>(()) },
+            TaskOptions::new(Priority::None).with_name("sleepy").with_timeout(Duration::from_millis(50)),
+        ).unwrap();
+
+        assert_eq!(flaky.join(), Ok(()));
+        assert!(failing.join().is_err());
+        assert_eq!(sleepy.join(), Err(crate::error_handler::error_handler::TaskError::Timeout));
+        proxy.await_completion().unwrap();
+
+        let calls = audit.calls.lock().unwrap();
+        let calls_of = |name: &str| calls.iter().filter(|call| call.contains(name)).cloned().collect::<Vec<_>>();
+        assert_eq!(calls_of("forbidden"), ["before_submit forbidden #1"]);
+        assert_eq!(calls_of("send_email"), ["before_submit send_email #1", "on_start send_email #1", "on_success send_email #1"]);
+        assert_eq!(calls_of("flaky"), ["before_submit flaky #1", "on_start flaky #1", "on_retry flaky #1", "on_start flaky #2", "on_success flaky #2"]);
+        assert_eq!(calls_of("anonymous"), ["before_submit anonymous #1", "on_start anonymous #1", "on_failure anonymous #1"]);
+        assert_eq!(calls_of("sleepy"), ["before_submit sleepy #1", "on_start sleepy #1", "on_timeout sleepy #1"]);
+        assert_eq!(audit.polled_inside.load(Ordering::SeqCst), 5); // Every attempt that started is wrapped
     }
 }
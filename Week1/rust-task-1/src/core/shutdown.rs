@@ -0,0 +1,115 @@
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+
+/*
+    How the executor stops:
+    - Drain: queued and running tasks are finished, then workers stop
+    - FinishRunning: running tasks are finished, queued tasks are dropped (cancelled)
+    - Abort: running tasks are cancelled at the next loop of their worker, queued tasks are dropped
+    Handles of dropped/cancelled tasks get TaskError::Cancelled.
+    Every mode closes the queue first and cancels the jobs waiting in the scheduler (countdown, eta, retry backoff).
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShutdownMode {
+    Drain,
+    FinishRunning,
+    Abort,
+}
+
+impl ShutdownMode {
+    fn to_value(self) -> u8 {
+        match self {
+            ShutdownMode::Drain => 1,
+            ShutdownMode::FinishRunning => 2,
+            ShutdownMode::Abort => 3,
+        }
+    }
+
+    fn from_value(value: u8) -> Option<ShutdownMode> {
+        match value {
+            1 => Some(ShutdownMode::Drain),
+            2 => Some(ShutdownMode::FinishRunning),
+            3 => Some(ShutdownMode::Abort),
+            _ => None,
+        }
+    }
+}
+
+// Returned by shutdown() instead of panicking when workers don't stop in time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShutdownReport {
+    pub mode: ShutdownMode,
+    pub completed: usize, // Tasks finished (succeeded or failed) after the shutdown started
+    pub cancelled: usize, // Queued tasks dropped + running tasks aborted
+    pub abandoned: usize, // Scheduled tasks that never ran + tasks still queued or running when shutdown_timeout was reached
+    pub timed_out: bool,
+}
+
+impl ShutdownReport {
+    // Report of all named queues => counters are summed, timed out when any queue timed out
+    pub fn merge(self, other: ShutdownReport) -> ShutdownReport {
+        ShutdownReport {
+            mode: self.mode,
+            completed: self.completed + other.completed,
+            cancelled: self.cancelled + other.cancelled,
+            abandoned: self.abandoned + other.abandoned,
+            timed_out: self.timed_out || other.timed_out,
+        }
+    }
+}
+
+// Shared by the executor and its workers
+#[derive(Debug, Default)]
+pub struct ShutdownState {
+    mode: AtomicU8, // 0 => executor is running
+    completed: AtomicUsize,
+    cancelled: AtomicUsize,
+    in_flight: AtomicUsize, // Tasks in the workers local run queues
+}
+
+impl ShutdownState {
+    pub fn new() -> ShutdownState {
+        ShutdownState::default()
+    }
+
+    pub fn begin(&self, mode: ShutdownMode) {
+        self.mode.store(mode.to_value(), Ordering::SeqCst);
+    }
+
+    pub fn get_mode(&self) -> Option<ShutdownMode> {
+        ShutdownMode::from_value(self.mode.load(Ordering::SeqCst))
+    }
+
+    pub fn task_started(&self) {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn task_finished(&self) {
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+        if self.get_mode().is_some() {
+            self.completed.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    pub fn tasks_cancelled(&self, count: usize) {
+        self.cancelled.fetch_add(count, Ordering::SeqCst);
+    }
+
+    // Cancelled tasks that were in a run queue
+    pub fn running_tasks_cancelled(&self, count: usize) {
+        self.in_flight.fetch_sub(count, Ordering::SeqCst);
+        self.tasks_cancelled(count);
+    }
+
+    pub fn get_completed(&self) -> usize {
+        self.completed.load(Ordering::SeqCst)
+    }
+
+    pub fn get_cancelled(&self) -> usize {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub fn get_in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+}
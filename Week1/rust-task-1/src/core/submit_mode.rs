@@ -0,0 +1,10 @@
+use std::time::Duration;
+
+// How a job waits for a free place in the worker queue (ExecutorConfig::queue_capacity)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SubmitMode {
+    Blocking, // Waits till there is free space
+    Try, // Fails with WorkerPoolFull when the queue is full
+    Timeout(Duration), // Waits up to the duration, then fails with Overload
+    Unbounded, // Ignores the capacity (still waits for the rate limit)
+}
@@ -0,0 +1,289 @@
+use std::{convert::Infallible, pin::Pin, sync::{Arc, Condvar, Mutex}, task::{Context, Poll, Waker}, thread, time::{Duration, Instant}};
+
+use crate::error_handler::error_handler::TaskError;
+use crate::lifecycle::task_id::TaskId;
+
+use super::types::FailureCallback;
+
+pub type TaskResult<T, E = Infallible> = Result<T, TaskError<E>>;
+
+type CompletionCallback<T, E> = Box<dyn FnOnce(TaskResult<T, E>) + Send + 'static>;
+
+struct HandleState<T, E> {
+    result: Option<TaskResult<T, E>>,
+    taken: bool,
+    waker: Option<Waker>, // Set when the handle is awaited from async code
+    on_complete: Option<CompletionCallback<T, E>>, // Set by on_complete(), gets the result instead of the handle
+}
+
+struct SharedState<T, E> {
+    state: Mutex<HandleState<T, E>>,
+    finished: Condvar, // Used by join() from sync code
+}
+
+impl<T, E> SharedState<T, E> {
+    // First result wins, everything after that is ignored
+    fn complete(&self, result: TaskResult<T, E>) {
+        let mut state = self.state.lock().unwrap();
+        if state.result.is_some() || state.taken {
+            return;
+        }
+
+        if let Some(callback) = state.on_complete.take() {
+            state.taken = true;
+            drop(state); // Callback can submit new tasks, it is called without holding the lock
+            callback(result);
+            return;
+        }
+
+        state.result = Some(result);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        self.finished.notify_all();
+    }
+}
+
+/*
+    Returned to the user from Proxy::submit() and Proxy::submit_fallible()
+    - join() / join_timeout() block the current thread till the task is finished
+    - .await from async code
+    Result is the task output or why the task did not finish (timeout, panic, cancellation, Err returned by the task)
+*/
+pub struct TaskHandle<T, E = Infallible> {
+    shared: Arc<SharedState<T, E>>,
+    task_id: Option<TaskId>, // None for handles of chain, group and chord (many tasks)
+}
+
+// Lives inside the submitted future, when it is dropped before complete() the task counts as cancelled
+pub struct TaskCompleter<T, E = Infallible> {
+    shared: Arc<SharedState<T, E>>,
+}
+
+pub fn task_handle<T: Send + 'static, E: Send + 'static>() -> (TaskCompleter<T, E>, TaskHandle<T, E>) {
+    let shared = Arc::new(SharedState {
+        state: Mutex::new(HandleState { result: None, taken: false, waker: None, on_complete: None }),
+        finished: Condvar::new(),
+    });
+
+    (TaskCompleter { shared: shared.clone() }, TaskHandle { shared, task_id: None })
+}
+
+impl<T: Send + 'static, E: Send + 'static> TaskCompleter<T, E> {
+    pub fn complete(&self, output: T) {
+        self.shared.complete(Ok(output));
+    }
+
+    // The task returned Err(error)
+    pub fn fail(&self, error: E) {
+        self.complete_with_error(TaskError::Failed(error));
+    }
+
+    pub fn complete_with_error(&self, error: TaskError<E>) {
+        self.shared.complete(Err(error));
+    }
+
+    // Forwards the result of another task
+    pub fn complete_with_result(&self, result: TaskResult<T, E>) {
+        self.shared.complete(result);
+    }
+
+    // Callback for the worker to report why the task failed, when it is dropped without a call the task counts as cancelled
+    pub fn failure_callback(&self) -> FailureCallback {
+        let completer = TaskCompleter { shared: self.shared.clone() };
+        Box::new(move |reason| completer.shared.complete(Err(reason.with_error_type())))
+    }
+}
+
+impl<T, E> Drop for TaskCompleter<T, E> {
+    fn drop(&mut self) {
+        // Dropped while the task panics => the worker catches the panic and reports it through the failure callback
+        if !thread::panicking() {
+            self.shared.complete(Err(TaskError::Cancelled));
+        }
+    }
+}
+
+impl<T, E> TaskHandle<T, E> {
+    pub fn with_task_id(mut self, task_id: TaskId) -> TaskHandle<T, E> {
+        self.task_id = Some(task_id);
+        self
+    }
+
+    // Id for Proxy::get_task_state()
+    pub fn get_task_id(&self) -> Option<TaskId> {
+        self.task_id
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.shared.state.lock().unwrap().result.is_some()
+    }
+
+    // Blocks till the task is finished
+    pub fn join(self) -> TaskResult<T, E> {
+        let mut state = self.shared.state.lock().unwrap();
+
+        loop {
+            if let Some(result) = Self::take_result(&mut state) {
+                return result;
+            }
+            state = self.shared.finished.wait(state).unwrap();
+        }
+    }
+
+    // Blocks till the task is finished or the timeout is reached.
+    // Returns None when the task is still running, then the handle can be joined again.
+    pub fn join_timeout(&mut self, timeout: Duration) -> Option<TaskResult<T, E>> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.shared.state.lock().unwrap();
+
+        loop {
+            if let Some(result) = Self::take_result(&mut state) {
+                return Some(result);
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return None;
+            }
+            state = self.shared.finished.wait_timeout(state, deadline - now).unwrap().0;
+        }
+    }
+
+    // Instead of waiting, the callback is called with the result on the thread that finishes the task (usually a worker)
+    // Callback must not block, it is used to continue with other tasks (chain, group, chord)
+    pub fn on_complete<C>(self, callback: C)
+    where
+        C: FnOnce(TaskResult<T, E>) + Send + 'static,
+        T: Send + 'static,
+        E: Send + 'static,
+    {
+        let mut state = self.shared.state.lock().unwrap();
+
+        match Self::take_result(&mut state) {
+            Some(result) => {
+                drop(state);
+                callback(result);
+            }
+            None => state.on_complete = Some(Box::new(callback)),
+        }
+    }
+
+    fn take_result(state: &mut HandleState<T, E>) -> Option<TaskResult<T, E>> {
+        if state.taken {
+            panic!("TaskHandle result was already taken!");
+        }
+
+        let result = state.result.take();
+        state.taken = result.is_some();
+        result
+    }
+}
+
+impl<T, E> Future for TaskHandle<T, E> {
+    type Output = TaskResult<T, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.shared.state.lock().unwrap();
+
+        match Self::take_result(&mut state) {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use futures::executor::block_on;
+
+    use super::*;
+
+    #[test]
+    fn test_join_should_return_output() {
+        let (completer, handle) = task_handle::<u32, Infallible>();
+
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            completer.complete(42);
+        });
+
+        assert_eq!(handle.join(), Ok(42));
+    }
+
+    #[test]
+    fn test_handle_should_be_awaitable() {
+        let (completer, handle) = task_handle::<String, Infallible>();
+
+        thread::spawn(move || completer.complete(String::from("done")));
+
+        assert_eq!(block_on(handle), Ok(String::from("done")));
+    }
+
+    #[test]
+    fn test_join_timeout_should_return_none_while_running() {
+        let (completer, mut handle) = task_handle::<u32, Infallible>();
+
+        assert_eq!(handle.join_timeout(Duration::from_millis(20)), None);
+        assert!(!handle.is_finished());
+
+        completer.complete(7);
+        assert_eq!(handle.join_timeout(Duration::from_millis(20)), Some(Ok(7)));
+    }
+
+    #[test]
+    fn test_failure_reported_before_drop_should_win() {
+        let (completer, handle) = task_handle::<u32, Infallible>();
+
+        let on_failure = completer.failure_callback();
+        on_failure(TaskError::Timeout);
+        drop(completer);
+
+        assert_eq!(handle.join(), Err(TaskError::Timeout));
+    }
+
+    #[test]
+    fn test_fail_should_return_task_error() {
+        let (completer, handle) = task_handle::<u32, String>();
+
+        completer.fail(String::from("service unavailable"));
+        drop(completer);
+
+        assert_eq!(handle.join(), Err(TaskError::Failed(String::from("service unavailable"))));
+    }
+
+    #[test]
+    fn test_on_complete_should_receive_result() {
+        let (completer, handle) = task_handle::<u32, Infallible>();
+        let (result_tx, result_rx) = std::sync::mpsc::channel();
+
+        handle.on_complete(move |result| result_tx.send(result).unwrap());
+        completer.complete(5);
+
+        assert_eq!(result_rx.recv_timeout(Duration::from_secs(1)).unwrap(), Ok(5));
+    }
+
+    #[test]
+    fn test_on_complete_after_finish_should_be_called_immediately() {
+        let (completer, handle) = task_handle::<u32, Infallible>();
+        drop(completer);
+
+        let (result_tx, result_rx) = std::sync::mpsc::channel();
+        handle.on_complete(move |result| result_tx.send(result).unwrap());
+
+        assert_eq!(result_rx.try_recv().unwrap(), Err(TaskError::Cancelled));
+    }
+
+    #[test]
+    fn test_dropped_completer_should_cancel() {
+        let (completer, handle) = task_handle::<u32, Infallible>();
+        drop(completer);
+
+        assert_eq!(handle.join(), Err(TaskError::Cancelled));
+    }
+}
@@ -0,0 +1,70 @@
+use std::{convert::Infallible, time::{Duration, Instant}};
+
+use crate::{priority::priority::Priority, rate_limiting::task_rate_limit::TaskRateLimit, retry::retry_policy::RetryPolicy};
+
+// Options sent together with the task to Proxy::submit_with_options()
+pub struct TaskOptions<E = Infallible> {
+    pub priority: Priority,
+    pub retry_policy: RetryPolicy<E>,
+    pub name: Option<String>, // Used by the routing rules
+    pub queue: Option<String>, // Named queue for this task, the routing rules are not used
+    pub result_ttl: Option<Duration>, // How long the ResultBackend keeps the result, None => result_ttl of the config
+    pub timeout: Option<Duration>, // Execution time limit of every attempt, None => task_timeout of the config
+    pub eta: Option<Instant>, // First attempt is sent to the workers at eta, None => now
+    pub rate_limit: Option<TaskRateLimit>, // Every attempt reserves its start, it waits in the scheduler till then
+}
+
+impl<E> TaskOptions<E> {
+    pub fn new(priority: Priority) -> TaskOptions<E> {
+        TaskOptions {
+            priority,
+            retry_policy: RetryPolicy::none(),
+            name: None,
+            queue: None,
+            result_ttl: None,
+            timeout: None,
+            eta: None,
+            rate_limit: None,
+        }
+    }
+
+    pub fn with_retry(mut self, retry_policy: RetryPolicy<E>) -> TaskOptions<E> {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn with_name(mut self, name: &str) -> TaskOptions<E> {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn with_queue(mut self, queue: &str) -> TaskOptions<E> {
+        self.queue = Some(queue.to_string());
+        self
+    }
+
+    pub fn with_result_ttl(mut self, result_ttl: Duration) -> TaskOptions<E> {
+        self.result_ttl = Some(result_ttl);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> TaskOptions<E> {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_eta(mut self, eta: Instant) -> TaskOptions<E> {
+        self.eta = Some(eta);
+        self
+    }
+
+    pub fn with_rate_limit(mut self, rate_limit: TaskRateLimit) -> TaskOptions<E> {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    // Same as with_eta() but relative to now
+    pub fn with_countdown(self, countdown: Duration) -> TaskOptions<E> {
+        self.with_eta(Instant::now() + countdown)
+    }
+}
@@ -1,6 +1,6 @@
 use std::{pin::Pin, sync::{atomic::AtomicBool, Arc, Mutex}, thread::JoinHandle};
 
-use crate::{executor_config::ExecutorConfig, performance_monitoring::metrics::MetricsReport};
+use crate::{error_handler::error_handler::TaskError, executor_config::ExecutorConfig, performance_monitoring::{metrics::MetricsReport, scaling_history::ScalingHistory}};
 
 use super::executor::AsyncExecutor;
 
@@ -8,9 +8,16 @@ use super::executor::AsyncExecutor;
 // Since this is `dyn trait` -> Rust doesn't know how much stack space to reserve -> Thats why i Pin and Box(Smart pointers) into heap memory where dynamic values are stored
 pub type Task = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
 
+// Task that can fail, Err contains the error message that the worker logs
+pub type FallibleTask = Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'static>>;
+
+// Called by the worker with the reason when the task did not finish
+pub type FailureCallback = Box<dyn FnOnce(TaskError) + Send + 'static>;
+
 pub type WorkerHandles = Arc<Mutex<Vec<JoinHandle<()>>>>;
 
 pub type MetricsData = Arc<Mutex<MetricsReport>>;
+pub type ScalingHistoryData = Arc<Mutex<ScalingHistory>>;
 pub type StopFlag = Arc<AtomicBool>;
 
 pub type ProxyExecutor = Arc<Mutex<AsyncExecutor>>;
@@ -0,0 +1,51 @@
+use crate::core::job::Job;
+use crate::error_handler::error_handler::{fail_gracefully, ExecutorError, TaskError};
+
+use super::{types::{TaskFactoryArc, WriteAheadLogArc}, wal_entry::WalEntry};
+
+/*
+    Durable mode of a Proxy => WriteAheadLog + TaskFactory.
+    - Entry is marked done when the task finished (succeeded, failed, timed out or panicked)
+    - Task cancelled by a shutdown stays in the log and runs again after the restart
+*/
+pub struct DurableTasks {
+    wal: WriteAheadLogArc,
+    factory: TaskFactoryArc,
+}
+
+impl DurableTasks {
+    pub fn new(wal: WriteAheadLogArc, factory: TaskFactoryArc) -> DurableTasks {
+        DurableTasks { wal, factory }
+    }
+
+    // Err when the factory does not know the task or its args
+    pub fn job(&self, entry: &WalEntry) -> Result<Job, ExecutorError> {
+        let future = self.factory.create(&entry.name, &entry.args)?;
+        let (job, handle) = Job::fallible_with_handle(future);
+
+        let wal = self.wal.clone();
+        let task_id = entry.task_id;
+        handle.on_complete(move |result| {
+            if result == Err(TaskError::Cancelled) {
+                return;
+            }
+            if let Err(error) = wal.mark_done(task_id) {
+                fail_gracefully(error, "Durable task was not marked as done, it runs again after a restart");
+            }
+        });
+
+        Ok(job.with_id(task_id))
+    }
+
+    pub fn get_wal(&self) -> &WriteAheadLogArc {
+        &self.wal
+    }
+}
+
+impl std::fmt::Debug for DurableTasks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DurableTasks")
+            .field("wal", &self.wal.get_path())
+            .finish()
+    }
+}
@@ -0,0 +1,5 @@
+pub mod task_factory;
+pub mod wal_entry;
+pub mod write_ahead_log;
+pub mod durable_tasks;
+pub mod types;
@@ -0,0 +1,24 @@
+use serde_json::Value;
+
+use crate::core::types::FallibleTask;
+use crate::error_handler::error_handler::ExecutorError;
+
+/*
+    Creates the future of a durable task from its name and serialized args.
+    A future can't be written to the WriteAheadLog, the name and the args can => after a restart
+    the same factory creates the task again.
+    Err(UnknownTaskName) for names the factory does not know, Err(InvalidTaskArgs) when the args don't fit the task.
+*/
+pub trait TaskFactory: Send + Sync {
+    fn create(&self, name: &str, args: &Value) -> Result<FallibleTask, ExecutorError>;
+}
+
+// Closure as a factory => |name, args| match name { "send_email" => ..., _ => Err(UnknownTaskName) }
+impl<F> TaskFactory for F
+where
+    F: Fn(&str, &Value) -> Result<FallibleTask, ExecutorError> + Send + Sync,
+{
+    fn create(&self, name: &str, args: &Value) -> Result<FallibleTask, ExecutorError> {
+        self(name, args)
+    }
+}
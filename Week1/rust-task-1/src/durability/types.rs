@@ -0,0 +1,8 @@
+use std::sync::Arc;
+
+use super::{task_factory::TaskFactory, write_ahead_log::WriteAheadLog};
+
+// Shared by the Proxy clones and the completion callbacks of the durable tasks
+pub type WriteAheadLogArc = Arc<WriteAheadLog>;
+
+pub type TaskFactoryArc = Arc<dyn TaskFactory>;
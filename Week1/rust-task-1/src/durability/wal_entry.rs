@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::lifecycle::task_id::TaskId;
+use crate::priority::priority::Priority;
+
+// Durable task as it is written to the WriteAheadLog
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WalEntry {
+    pub task_id: TaskId,
+    pub name: String, // Name for the TaskFactory (and the routing rules)
+    pub args: Value,
+    pub priority: Priority,
+}
+
+impl WalEntry {
+    pub fn new(name: &str, args: Value, priority: Priority) -> WalEntry {
+        WalEntry {
+            task_id: TaskId::next(),
+            name: name.to_string(),
+            args,
+            priority,
+        }
+    }
+}
+
+// One line of the log => {"op":"submit",...} when the task is accepted, {"op":"done",...} when it finished
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum WalRecord {
+    Submit(WalEntry),
+    Done { task_id: TaskId },
+}
@@ -0,0 +1,225 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::error_handler::error_handler::{fail_gracefully, ExecutorError};
+use crate::lifecycle::task_id::TaskId;
+
+use super::wal_entry::{WalEntry, WalRecord};
+
+#[derive(Debug)]
+struct WalState {
+    file: File,
+    pending: BTreeMap<TaskId, WalEntry>, // Submitted and not done => replayed after a restart
+    done_since_compaction: usize,
+}
+
+/*
+    Append-only log of the durable tasks, one JSON record per line.
+    - Submit record is flushed to the disk (fsync) before the submission is acknowledged
+    - Done record is written when the task finished, it is not synced => after a crash the task can run again (at-least-once)
+    - After compact_threshold done records the log is rewritten with the pending tasks only (tmp file + rename)
+    - Broken line (the process was killed in the middle of a write) is skipped when the log is read
+*/
+#[derive(Debug)]
+pub struct WriteAheadLog {
+    path: PathBuf,
+    compact_threshold: usize,
+    state: Mutex<WalState>,
+}
+
+impl WriteAheadLog {
+    const DEFAULT_COMPACT_THRESHOLD: usize = 1024;
+
+    // Opens or creates the log, the tasks that were not done are in get_pending()
+    pub fn open(path: impl Into<PathBuf>) -> Result<WriteAheadLog, ExecutorError> {
+        let path = path.into();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|source| ExecutorError::Io { operation: "create write-ahead log directory", source })?;
+        }
+
+        let pending = WriteAheadLog::read_pending(&path)?;
+        if let Some(last_id) = pending.keys().next_back() {
+            TaskId::advance_past(*last_id);
+        }
+
+        let wal = WriteAheadLog {
+            compact_threshold: WriteAheadLog::DEFAULT_COMPACT_THRESHOLD,
+            state: Mutex::new(WalState {
+                file: WriteAheadLog::open_append(&path)?,
+                pending,
+                done_since_compaction: 0,
+            }),
+            path,
+        };
+        wal.compact()?; // Done records of the previous run are not needed
+        Ok(wal)
+    }
+
+    pub fn with_compact_threshold(mut self, compact_threshold: usize) -> WriteAheadLog {
+        self.compact_threshold = compact_threshold.max(1);
+        self
+    }
+
+    // Tasks that were submitted and are not done, oldest first
+    pub fn get_pending(&self) -> Vec<WalEntry> {
+        self.state.lock().unwrap().pending.values().cloned().collect()
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    // Returns when the record is on the disk
+    pub fn append(&self, entry: WalEntry) -> Result<(), ExecutorError> {
+        let mut state = self.state.lock().unwrap();
+        WriteAheadLog::write_record(&mut state.file, &WalRecord::Submit(entry.clone()))?;
+        state.file.sync_data().map_err(|source| ExecutorError::Io { operation: "sync write-ahead log", source })?;
+
+        state.pending.insert(entry.task_id, entry);
+        Ok(())
+    }
+
+    pub fn mark_done(&self, task_id: TaskId) -> Result<(), ExecutorError> {
+        let compact = {
+            let mut state = self.state.lock().unwrap();
+            if state.pending.remove(&task_id).is_none() {
+                return Ok(()); // Already done
+            }
+
+            WriteAheadLog::write_record(&mut state.file, &WalRecord::Done { task_id })?;
+            state.done_since_compaction += 1;
+            state.done_since_compaction >= self.compact_threshold
+        };
+
+        match compact {
+            true => self.compact(),
+            false => Ok(()),
+        }
+    }
+
+    // Rewrites the log with the pending tasks only
+    pub fn compact(&self) -> Result<(), ExecutorError> {
+        let mut state = self.state.lock().unwrap();
+        let tmp_path = self.path.with_extension("compact.tmp");
+
+        let mut tmp_file = File::create(&tmp_path).map_err(|source| ExecutorError::Io { operation: "compact write-ahead log", source })?;
+        for entry in state.pending.values() {
+            WriteAheadLog::write_record(&mut tmp_file, &WalRecord::Submit(entry.clone()))?;
+        }
+        tmp_file.sync_all().map_err(|source| ExecutorError::Io { operation: "compact write-ahead log", source })?;
+        fs::rename(&tmp_path, &self.path).map_err(|source| ExecutorError::Io { operation: "compact write-ahead log", source })?;
+
+        state.file = WriteAheadLog::open_append(&self.path)?;
+        state.done_since_compaction = 0;
+        Ok(())
+    }
+
+    fn open_append(path: &Path) -> Result<File, ExecutorError> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|source| ExecutorError::Io { operation: "open write-ahead log", source })
+    }
+
+    fn write_record(file: &mut File, record: &WalRecord) -> Result<(), ExecutorError> {
+        let mut line = serde_json::to_string(record).map_err(|error| ExecutorError::InvalidWalRecord(error.to_string()))?;
+        line.push('\n');
+
+        // One write per record => a killed process leaves at most one broken line at the end
+        file.write_all(line.as_bytes()).map_err(|source| ExecutorError::Io { operation: "write to write-ahead log", source })
+    }
+
+    fn read_pending(path: &Path) -> Result<BTreeMap<TaskId, WalEntry>, ExecutorError> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(source) => return Err(ExecutorError::Io { operation: "read write-ahead log", source }),
+        };
+
+        let mut pending = BTreeMap::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|source| ExecutorError::Io { operation: "read write-ahead log", source })?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match serde_json::from_str::<WalRecord>(&line) {
+                Ok(WalRecord::Submit(entry)) => { pending.insert(entry.task_id, entry); }
+                Ok(WalRecord::Done { task_id }) => { pending.remove(&task_id); }
+                Err(error) => fail_gracefully(ExecutorError::InvalidWalRecord(error.to_string()), "Broken write-ahead log record was skipped"),
+            }
+        }
+        Ok(pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::priority::priority::Priority;
+
+    fn temp_log(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rust-task-1-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join("tasks.wal")
+    }
+
+    #[test]
+    fn test_pending_tasks_should_be_read_after_reopen() {
+        let path = temp_log("wal-reopen");
+        let wal = WriteAheadLog::open(&path).unwrap();
+
+        let done = WalEntry::new("send_email", json!({ "to": "a@b.c" }), Priority::High);
+        let pending = WalEntry::new("charge_card", json!([1, 2]), Priority::None);
+        wal.append(done.clone()).unwrap();
+        wal.append(pending.clone()).unwrap();
+        wal.mark_done(done.task_id).unwrap();
+        drop(wal); // Same as a crash, nothing is flushed on drop
+
+        let reopened = WriteAheadLog::open(&path).unwrap();
+        assert_eq!(reopened.get_pending(), vec![pending.clone()]);
+        assert!(TaskId::next() > pending.task_id);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_broken_last_line_should_be_skipped() {
+        let path = temp_log("wal-broken");
+        let wal = WriteAheadLog::open(&path).unwrap();
+        let entry = WalEntry::new("send_email", json!(null), Priority::Low);
+        wal.append(entry.clone()).unwrap();
+        drop(wal);
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"op":"submit","task_id":99,"na"#).unwrap(); // Killed in the middle of a write
+
+        assert_eq!(WriteAheadLog::open(&path).unwrap().get_pending(), vec![entry]);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_log_should_be_compacted_after_threshold() {
+        let path = temp_log("wal-compact");
+        let wal = WriteAheadLog::open(&path).unwrap().with_compact_threshold(3);
+
+        let entries: Vec<WalEntry> = (0..4).map(|n| WalEntry::new("task", json!(n), Priority::None)).collect();
+        for entry in &entries {
+            wal.append(entry.clone()).unwrap();
+        }
+        for entry in &entries[..3] {
+            wal.mark_done(entry.task_id).unwrap();
+        }
+
+        // Only the submit record of the last task is left
+        let log = fs::read_to_string(&path).unwrap();
+        assert_eq!(log.lines().count(), 1);
+        assert_eq!(wal.get_pending(), vec![entries[3].clone()]);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
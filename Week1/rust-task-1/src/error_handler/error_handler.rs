@@ -1,3 +1,5 @@
+use std::{any::Any, convert::Infallible};
+
 use log::info;
 use thiserror::Error;
 
@@ -14,7 +16,7 @@ pub enum ExecutorError {
     #[error("Something went wrong, please try again later!")]
     Other,
 
-    #[error("Task execution took longer that 5 seconds!")]
+    #[error("Task execution took longer than the configured task timeout!")]
     Timeout,
 
     #[error("Rate Limit exceeded!")]
@@ -27,18 +29,182 @@ pub enum ExecutorError {
     ChannelConnectionIsNotEstablished,
 
     #[error("Shutdown error")]
-    ShutDownError
+    ShutDownError,
+
+    #[error("Task returned an error!")]
+    TaskFailed,
+
+    #[error("Task panicked!")]
+    TaskPanicked,
+
+    #[error("Invalid schedule of a periodic task!")]
+    InvalidSchedule,
+
+    #[error("Run of periodic task \"{0}\" was skipped!")]
+    RunSkipped(String),
+
+    #[error("Invalid executor config: {0}")]
+    InvalidConfig(String),
+
+    #[error("Queue \"{0}\" is not registered!")]
+    UnknownQueue(String),
+
+    #[error("Task {0} is unknown or finished too long ago!")]
+    UnknownTask(u64),
+
+    #[error("No result backend, set it with Proxy::set_result_backend()!")]
+    NoResultBackend,
+
+    #[error("Invalid task result: {0}")]
+    InvalidResult(String),
+
+    #[error("Task \"{0}\" is not registered!")]
+    UnknownTaskName(String),
+
+    #[error("Invalid task arguments: {0}")]
+    InvalidTaskArgs(String),
+
+    #[error("Invalid write-ahead log record: {0}")]
+    InvalidWalRecord(String),
+
+    #[error("Durable mode is not enabled, call Proxy::enable_durability()!")]
+    DurabilityNotEnabled,
+
+    #[error("Task was rejected by a middleware: {0}")]
+    TaskRejected(String),
+
+    #[error("Middleware panicked: {0}")]
+    MiddlewarePanicked(String),
+
+    // Context of a public operation => Proxy::task() returns Operation { operation: "task", source: ChannelConnectionIsNotEstablished, .. }
+    #[error("{operation} failed{}: {source}", task_suffix(.task_id))]
+    Operation {
+        operation: &'static str,
+        task_id: Option<u64>,
+        #[source]
+        source: Box<ExecutorError>,
+    },
+
+    #[error("{operation} failed: {source}")]
+    Io {
+        operation: &'static str,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+fn task_suffix(task_id: &Option<u64>) -> String {
+    task_id.map(|task_id| format!(" for task {}", task_id)).unwrap_or_default()
 }
 
-// This is panic Wrapper
+impl ExecutorError {
+    // Adds the name of the operation that failed
+    pub fn in_operation(self, operation: &'static str) -> ExecutorError {
+        ExecutorError::Operation { operation, task_id: None, source: Box::new(self) }
+    }
+
+    pub fn for_task(self, task_id: u64) -> ExecutorError {
+        match self {
+            ExecutorError::Operation { operation, source, .. } => ExecutorError::Operation { operation, task_id: Some(task_id), source },
+            error => ExecutorError::Operation { operation: "task", task_id: Some(task_id), source: Box::new(error) },
+        }
+    }
+
+    // The error without the context => match on this to know what went wrong
+    pub fn root_cause(&self) -> &ExecutorError {
+        match self {
+            ExecutorError::Operation { source, .. } => source.root_cause(),
+            error => error,
+        }
+    }
+
+    pub fn get_operation(&self) -> Option<&'static str> {
+        match self {
+            ExecutorError::Operation { operation, .. } | ExecutorError::Io { operation, .. } => Some(operation),
+            _ => None,
+        }
+    }
+
+    pub fn get_task_id(&self) -> Option<u64> {
+        match self {
+            ExecutorError::Operation { task_id, .. } => *task_id,
+            _ => None,
+        }
+    }
+}
+
+// Why a submitted task did not return its output => returned to the user through TaskHandle
+// E is the error type of fallible tasks (Future<Output = Result<T, E>>)
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum TaskError<E = Infallible> {
+    #[error("Task execution took longer than the task timeout!")]
+    Timeout,
+
+    #[error("Task panicked: {0}")]
+    Panicked(String),
+
+    #[error("Task was cancelled before it finished!")]
+    Cancelled,
+
+    #[error("Task returned an error: {0}")]
+    Failed(E),
+}
+
+impl TaskError {
+    // Worker reports only reasons without task error, this converts them for the TaskHandle of any task
+    pub fn with_error_type<E>(self) -> TaskError<E> {
+        match self {
+            TaskError::Timeout => TaskError::Timeout,
+            TaskError::Panicked(message) => TaskError::Panicked(message),
+            TaskError::Cancelled => TaskError::Cancelled,
+            TaskError::Failed(never) => match never {},
+        }
+    }
+}
+
+// This is panic Wrapper => only for internal invariant violations, public operations return Result<_, ExecutorError>
 pub fn fail(error: ExecutorError, context: String) -> ! {
     panic!("Execution failed with Error: {}, Custom Message: {}", error, context);
 }
 
+// panic!("literal") has &str payload, panic!("{}", value) has String payload
+pub fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => String::from("Unknown panic payload"),
+        },
+    }
+}
+
 pub fn fail_gracefully(error: ExecutorError, context: &str) {
     // It's print for debuging purposes
     eprintln!("[ERROR] {:?} - Context: {}", error, context);
     
     // This is production log
     info!("[ERROR] {:?} - Context: {}", error, context)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operation_context_should_keep_the_root_cause() {
+        let error = ExecutorError::WorkerPoolFull.in_operation("try_task");
+
+        assert!(matches!(error.root_cause(), ExecutorError::WorkerPoolFull));
+        assert_eq!(error.get_operation(), Some("try_task"));
+        assert_eq!(error.to_string(), "try_task failed: Worker Pool is Full!");
+    }
+
+    #[test]
+    fn test_for_task_should_add_task_id_to_the_message() {
+        let error = ExecutorError::ChannelConnectionIsNotEstablished.in_operation("delay").for_task(7);
+
+        assert_eq!(error.get_task_id(), Some(7));
+        assert_eq!(error.to_string(), "delay failed for task 7: Channel is not working");
+        assert!(std::error::Error::source(&error).is_some());
+    }
+}
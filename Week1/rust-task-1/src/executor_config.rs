@@ -1,18 +1,35 @@
 use std::{thread::available_parallelism, time::Duration};
 
+use crate::error_handler::error_handler::ExecutorError;
+
 
 #[derive(Debug, Clone)]
 pub struct ExecutorConfig {
     pub rate_limit_per_sec: usize,
     pub task_timeout: Duration,
-    pub worker_count: usize, 
+    pub min_workers: usize, // Workers started with the executor, the pool never shrinks below this
+    pub max_workers: usize, // Pool grows till this under load, same as min_workers => fixed pool (default)
     pub shutdown_timeout: Duration,
+    pub max_tasks_per_worker: usize, // In-flight futures per worker thread
+    pub queue_capacity: usize, // Jobs waiting in the worker channel, after that submissions wait or fail
+    pub scale_up_queue_depth: usize, // Queued jobs that start a new worker
+    pub scale_up_wait_time: Duration, // Time a job waited for a worker that starts a new worker
+    pub worker_keep_alive: Duration, // Idle time after which a worker over min_workers stops
+    pub result_ttl: Duration, // How long the ResultBackend keeps a result, TaskOptions::with_result_ttl() changes it for one task
+    pub result_reap_interval: Duration, // How often expired results are removed from the ResultBackend
 }
 
 impl ExecutorConfig {
     const DEFAULT_RATE_LIMIT: usize = 5; // Default constant for this class
     const DEFAULT_TASK_TIMEOUT: Duration = Duration::from_secs(5);
     const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+    const DEFAULT_MAX_TASKS_PER_WORKER: usize = 64;
+    const DEFAULT_QUEUE_CAPACITY: usize = 1024;
+    const DEFAULT_SCALE_UP_QUEUE_DEPTH: usize = 16;
+    const DEFAULT_SCALE_UP_WAIT_TIME: Duration = Duration::from_millis(500);
+    const DEFAULT_WORKER_KEEP_ALIVE: Duration = Duration::from_secs(30);
+    const DEFAULT_RESULT_TTL: Duration = Duration::from_secs(24 * 60 * 60); // 1 day like Celery result_expires
+    const DEFAULT_RESULT_REAP_INTERVAL: Duration = Duration::from_secs(60);
 
 
     fn get_machine_cores() -> usize {
@@ -24,8 +41,41 @@ impl ExecutorConfig {
         workers_count
     }
 
+    // Workers started with the executor => min_workers
     pub fn get_total_workers(&self) -> usize {
-        self.worker_count
+        self.min_workers
+    }
+
+    pub fn get_min_workers(&self) -> usize {
+        self.min_workers
+    }
+
+    pub fn get_max_workers(&self) -> usize {
+        self.max_workers
+    }
+
+    pub fn is_autoscaling(&self) -> bool {
+        self.max_workers > self.min_workers
+    }
+
+    pub fn get_scale_up_queue_depth(&self) -> usize {
+        self.scale_up_queue_depth
+    }
+
+    pub fn get_scale_up_wait_time(&self) -> Duration {
+        self.scale_up_wait_time
+    }
+
+    pub fn get_worker_keep_alive(&self) -> Duration {
+        self.worker_keep_alive
+    }
+
+    pub fn get_result_ttl(&self) -> Duration {
+        self.result_ttl
+    }
+
+    pub fn get_result_reap_interval(&self) -> Duration {
+        self.result_reap_interval
     }
 
     pub fn get_task_timeout(&self) -> Duration {
@@ -35,6 +85,14 @@ impl ExecutorConfig {
     pub fn get_shutdown_timeout(&self) -> Duration {
         self.shutdown_timeout
     }
+
+    pub fn get_max_tasks_per_worker(&self) -> usize {
+        self.max_tasks_per_worker
+    }
+
+    pub fn get_queue_capacity(&self) -> usize {
+        self.queue_capacity
+    }
 }
 
 impl Default for ExecutorConfig {
@@ -44,8 +102,239 @@ impl Default for ExecutorConfig {
         Self {
             rate_limit_per_sec: ExecutorConfig::DEFAULT_RATE_LIMIT,
             task_timeout: ExecutorConfig::DEFAULT_TASK_TIMEOUT,
-            worker_count: workers_allowed,
+            min_workers: workers_allowed,
+            max_workers: workers_allowed,
             shutdown_timeout: ExecutorConfig::DEFAULT_SHUTDOWN_TIMEOUT,
+            max_tasks_per_worker: ExecutorConfig::DEFAULT_MAX_TASKS_PER_WORKER,
+            queue_capacity: ExecutorConfig::DEFAULT_QUEUE_CAPACITY,
+            scale_up_queue_depth: ExecutorConfig::DEFAULT_SCALE_UP_QUEUE_DEPTH,
+            scale_up_wait_time: ExecutorConfig::DEFAULT_SCALE_UP_WAIT_TIME,
+            worker_keep_alive: ExecutorConfig::DEFAULT_WORKER_KEEP_ALIVE,
+            result_ttl: ExecutorConfig::DEFAULT_RESULT_TTL,
+            result_reap_interval: ExecutorConfig::DEFAULT_RESULT_REAP_INTERVAL,
+        }
+    }
+}
+
+/*
+    Builder to change the defaults of ExecutorConfig:
+        ExecutorConfigBuilder::new().with_worker_count(2).with_task_timeout(Duration::from_millis(500)).build()?
+    - Every value that is not set keeps its default
+    - build() validates the values and returns ExecutorError::InvalidConfig instead of starting a broken executor
+*/
+#[derive(Debug, Clone, Default)]
+pub struct ExecutorConfigBuilder {
+    config: ExecutorConfig,
+    env_vars: Vec<(&'static str, &'static [&'static str])>, // (env variable, fields it set) => build() errors point to the variable
+}
+
+impl ExecutorConfigBuilder {
+    pub fn new() -> ExecutorConfigBuilder {
+        ExecutorConfigBuilder { config: ExecutorConfig::default(), env_vars: vec![] }
+    }
+
+    // Fields were set from the env variable by the config loader
+    pub(crate) fn with_env_var(mut self, env_var: &'static str, fields: &'static [&'static str]) -> Self {
+        self.env_vars.push((env_var, fields));
+        self
+    }
+
+    pub fn with_rate_limit_per_sec(mut self, rate_limit_per_sec: usize) -> Self {
+        self.config.rate_limit_per_sec = rate_limit_per_sec;
+        self
+    }
+
+    pub fn with_task_timeout(mut self, task_timeout: Duration) -> Self {
+        self.config.task_timeout = task_timeout;
+        self
+    }
+
+    // Fixed pool => sets min_workers and max_workers
+    pub fn with_worker_count(mut self, worker_count: usize) -> Self {
+        self.config.min_workers = worker_count;
+        self.config.max_workers = worker_count;
+        self
+    }
+
+    pub fn with_min_workers(mut self, min_workers: usize) -> Self {
+        self.config.min_workers = min_workers;
+        self
+    }
+
+    pub fn with_max_workers(mut self, max_workers: usize) -> Self {
+        self.config.max_workers = max_workers;
+        self
+    }
+
+    pub fn with_scale_up_queue_depth(mut self, scale_up_queue_depth: usize) -> Self {
+        self.config.scale_up_queue_depth = scale_up_queue_depth;
+        self
+    }
+
+    pub fn with_scale_up_wait_time(mut self, scale_up_wait_time: Duration) -> Self {
+        self.config.scale_up_wait_time = scale_up_wait_time;
+        self
+    }
+
+    pub fn with_worker_keep_alive(mut self, worker_keep_alive: Duration) -> Self {
+        self.config.worker_keep_alive = worker_keep_alive;
+        self
+    }
+
+    pub fn with_result_ttl(mut self, result_ttl: Duration) -> Self {
+        self.config.result_ttl = result_ttl;
+        self
+    }
+
+    pub fn with_result_reap_interval(mut self, result_reap_interval: Duration) -> Self {
+        self.config.result_reap_interval = result_reap_interval;
+        self
+    }
+
+    pub fn with_shutdown_timeout(mut self, shutdown_timeout: Duration) -> Self {
+        self.config.shutdown_timeout = shutdown_timeout;
+        self
+    }
+
+    pub fn with_max_tasks_per_worker(mut self, max_tasks_per_worker: usize) -> Self {
+        self.config.max_tasks_per_worker = max_tasks_per_worker;
+        self
+    }
+
+    pub fn with_queue_capacity(mut self, queue_capacity: usize) -> Self {
+        self.config.queue_capacity = queue_capacity;
+        self
+    }
+
+    pub fn build(self) -> Result<ExecutorConfig, ExecutorError> {
+        let config = self.config;
+
+        // (fields that make the value invalid, message)
+        let invalid_value: Option<(&[&str], &str)> = if config.min_workers == 0 {
+            Some((&["min_workers"], "worker_count (min_workers) must be greater than 0"))
+        } else if config.max_workers < config.min_workers {
+            Some((&["min_workers", "max_workers"], "max_workers must not be smaller than min_workers"))
+        } else if config.rate_limit_per_sec == 0 {
+            Some((&["rate_limit_per_sec"], "rate_limit_per_sec must be greater than 0"))
+        } else if config.task_timeout.is_zero() {
+            Some((&["task_timeout"], "task_timeout must not be zero"))
+        } else if config.shutdown_timeout.is_zero() {
+            Some((&["shutdown_timeout"], "shutdown_timeout must not be zero"))
+        } else if config.max_tasks_per_worker == 0 {
+            Some((&["max_tasks_per_worker"], "max_tasks_per_worker must be greater than 0"))
+        } else if config.queue_capacity == 0 {
+            Some((&["queue_capacity"], "queue_capacity must be greater than 0"))
+        } else if config.scale_up_queue_depth == 0 {
+            Some((&["scale_up_queue_depth"], "scale_up_queue_depth must be greater than 0"))
+        } else if config.scale_up_wait_time.is_zero() {
+            Some((&["scale_up_wait_time"], "scale_up_wait_time must not be zero"))
+        } else if config.worker_keep_alive.is_zero() {
+            Some((&["worker_keep_alive"], "worker_keep_alive must not be zero"))
+        } else if config.result_ttl.is_zero() {
+            Some((&["result_ttl"], "result_ttl must not be zero"))
+        } else if config.result_reap_interval.is_zero() {
+            Some((&["result_reap_interval"], "result_reap_interval must not be zero"))
+        } else {
+            None
+        };
+
+        let Some((fields, message)) = invalid_value else { return Ok(config) };
+
+        // Value from the environment => the variable is in front, e.g. "TASK_EXEC_RATE_LIMIT: rate_limit_per_sec must be greater than 0"
+        let env_vars: Vec<&str> = self.env_vars
+            .iter()
+            .filter(|(_, set_fields)| set_fields.iter().any(|field| fields.contains(field)))
+            .map(|(env_var, _)| *env_var)
+            .collect();
+
+        match env_vars.is_empty() {
+            true => Err(ExecutorError::InvalidConfig(message.to_string())),
+            false => Err(ExecutorError::InvalidConfig(format!("{}: {}", env_vars.join(", "), message))),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_without_changes_should_return_defaults() {
+        let config = ExecutorConfigBuilder::new().build().unwrap();
+        let default_config = ExecutorConfig::default();
+
+        assert_eq!(config.rate_limit_per_sec, default_config.rate_limit_per_sec);
+        assert_eq!(config.get_task_timeout(), default_config.get_task_timeout());
+        assert_eq!(config.get_total_workers(), default_config.get_total_workers());
+        assert_eq!(config.get_queue_capacity(), default_config.get_queue_capacity());
+    }
+
+    #[test]
+    fn test_builder_should_set_values() {
+        let config = ExecutorConfigBuilder::new()
+            .with_rate_limit_per_sec(50)
+            .with_task_timeout(Duration::from_millis(200))
+            .with_worker_count(3)
+            .with_shutdown_timeout(Duration::from_secs(1))
+            .with_max_tasks_per_worker(8)
+            .with_queue_capacity(16)
+            .build()
+            .unwrap();
+
+        assert_eq!(config.rate_limit_per_sec, 50);
+        assert_eq!(config.get_task_timeout(), Duration::from_millis(200));
+        assert_eq!(config.get_total_workers(), 3);
+        assert_eq!(config.get_shutdown_timeout(), Duration::from_secs(1));
+        assert_eq!(config.get_max_tasks_per_worker(), 8);
+        assert_eq!(config.get_queue_capacity(), 16);
+    }
+
+    #[test]
+    fn test_builder_should_reject_invalid_values() {
+        let zero_workers = ExecutorConfigBuilder::new().with_worker_count(0).build();
+        assert!(matches!(zero_workers, Err(ExecutorError::InvalidConfig(message)) if message.contains("worker_count")));
+
+        let zero_timeout = ExecutorConfigBuilder::new().with_task_timeout(Duration::ZERO).build();
+        assert!(matches!(zero_timeout, Err(ExecutorError::InvalidConfig(message)) if message.contains("task_timeout")));
+
+        let zero_shutdown_timeout = ExecutorConfigBuilder::new().with_shutdown_timeout(Duration::ZERO).build();
+        assert!(matches!(zero_shutdown_timeout, Err(ExecutorError::InvalidConfig(_))));
+
+        let zero_rate_limit = ExecutorConfigBuilder::new().with_rate_limit_per_sec(0).build();
+        assert!(matches!(zero_rate_limit, Err(ExecutorError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_builder_should_set_autoscaling_limits() {
+        let fixed = ExecutorConfigBuilder::new().with_worker_count(2).build().unwrap();
+        assert_eq!((fixed.get_min_workers(), fixed.get_max_workers()), (2, 2));
+        assert!(!fixed.is_autoscaling());
+
+        let autoscaled = ExecutorConfigBuilder::new()
+            .with_min_workers(1)
+            .with_max_workers(4)
+            .with_worker_keep_alive(Duration::from_secs(5))
+            .build()
+            .unwrap();
+        assert!(autoscaled.is_autoscaling());
+        assert_eq!(autoscaled.get_total_workers(), 1);
+        assert_eq!(autoscaled.get_worker_keep_alive(), Duration::from_secs(5));
+
+        let max_below_min = ExecutorConfigBuilder::new().with_min_workers(3).with_max_workers(2).build();
+        assert!(matches!(max_below_min, Err(ExecutorError::InvalidConfig(message)) if message.contains("max_workers")));
+    }
+
+    #[test]
+    fn test_builder_should_set_result_expiry() {
+        let config = ExecutorConfigBuilder::new()
+            .with_result_ttl(Duration::from_secs(60))
+            .with_result_reap_interval(Duration::from_secs(5))
+            .build()
+            .unwrap();
+        assert_eq!(config.get_result_ttl(), Duration::from_secs(60));
+        assert_eq!(config.get_result_reap_interval(), Duration::from_secs(5));
+
+        let zero_ttl = ExecutorConfigBuilder::new().with_result_ttl(Duration::ZERO).build();
+        assert!(matches!(zero_ttl, Err(ExecutorError::InvalidConfig(message)) if message.contains("result_ttl")));
+    }
+}
@@ -0,0 +1,301 @@
+use std::{fs, path::Path, time::Duration};
+
+use serde::Deserialize;
+
+use crate::error_handler::error_handler::ExecutorError;
+use crate::executor_config::{ExecutorConfig, ExecutorConfigBuilder};
+
+/*
+    Loads ExecutorConfig without recompiling:
+        - TOML file (every key is optional, missing keys keep the defaults)
+        - Environment variables override the file (TASK_EXEC_RATE_LIMIT, TASK_EXEC_WORKERS, ...)
+    Durations are written in human format => "500ms", "5s", "1m 30s"
+    Every error contains the key (or env variable) that is invalid, the values are validated by ExecutorConfigBuilder
+    (its errors start with the env variable when the invalid value came from the environment).
+
+    Example executor.toml:
+        rate_limit_per_sec = 20
+        worker_count = 4
+        task_timeout = "500ms"
+        shutdown_timeout = "10s"
+
+        # Autoscaling instead of worker_count
+        min_workers = 2
+        max_workers = 8
+        worker_keep_alive = "30s"
+
+        # ResultBackend
+        result_ttl = "1h"
+        result_reap_interval = "1m"
+*/
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)] // Typo in a key is an error, not a silently ignored value
+struct ExecutorConfigFile {
+    rate_limit_per_sec: Option<usize>,
+    task_timeout: Option<String>,
+    worker_count: Option<usize>,
+    shutdown_timeout: Option<String>,
+    max_tasks_per_worker: Option<usize>,
+    queue_capacity: Option<usize>,
+    min_workers: Option<usize>,
+    max_workers: Option<usize>,
+    scale_up_queue_depth: Option<usize>,
+    scale_up_wait_time: Option<String>,
+    worker_keep_alive: Option<String>,
+    result_ttl: Option<String>,
+    result_reap_interval: Option<String>,
+}
+
+pub const ENV_RATE_LIMIT: &str = "TASK_EXEC_RATE_LIMIT";
+pub const ENV_TASK_TIMEOUT: &str = "TASK_EXEC_TASK_TIMEOUT";
+pub const ENV_WORKERS: &str = "TASK_EXEC_WORKERS";
+pub const ENV_SHUTDOWN_TIMEOUT: &str = "TASK_EXEC_SHUTDOWN_TIMEOUT";
+pub const ENV_MAX_TASKS_PER_WORKER: &str = "TASK_EXEC_MAX_TASKS_PER_WORKER";
+pub const ENV_QUEUE_CAPACITY: &str = "TASK_EXEC_QUEUE_CAPACITY";
+pub const ENV_MIN_WORKERS: &str = "TASK_EXEC_MIN_WORKERS";
+pub const ENV_MAX_WORKERS: &str = "TASK_EXEC_MAX_WORKERS";
+pub const ENV_SCALE_UP_QUEUE_DEPTH: &str = "TASK_EXEC_SCALE_UP_QUEUE_DEPTH";
+pub const ENV_SCALE_UP_WAIT_TIME: &str = "TASK_EXEC_SCALE_UP_WAIT_TIME";
+pub const ENV_WORKER_KEEP_ALIVE: &str = "TASK_EXEC_WORKER_KEEP_ALIVE";
+pub const ENV_RESULT_TTL: &str = "TASK_EXEC_RESULT_TTL";
+pub const ENV_RESULT_REAP_INTERVAL: &str = "TASK_EXEC_RESULT_REAP_INTERVAL";
+
+impl ExecutorConfig {
+    // Only the TOML values, environment is not used
+    pub fn from_toml_str(toml_config: &str) -> Result<ExecutorConfig, ExecutorError> {
+        ExecutorConfig::file_builder(toml_config)?.build()
+    }
+
+    pub fn from_toml_file(path: impl AsRef<Path>) -> Result<ExecutorConfig, ExecutorError> {
+        ExecutorConfig::from_toml_str(&ExecutorConfig::read_file(path.as_ref())?)
+    }
+
+    // Defaults + environment overrides
+    pub fn from_env() -> Result<ExecutorConfig, ExecutorError> {
+        ExecutorConfig::apply_env(ExecutorConfigBuilder::new(), |key| std::env::var(key).ok())?.build()
+    }
+
+    // TOML file + environment overrides => what a deployment should use
+    pub fn load(path: impl AsRef<Path>) -> Result<ExecutorConfig, ExecutorError> {
+        let builder = ExecutorConfig::file_builder(&ExecutorConfig::read_file(path.as_ref())?)?;
+        ExecutorConfig::apply_env(builder, |key| std::env::var(key).ok())?.build()
+    }
+
+    fn read_file(path: &Path) -> Result<String, ExecutorError> {
+        fs::read_to_string(path)
+            .map_err(|error| ExecutorError::InvalidConfig(format!("cannot read config file {}: {}", path.display(), error)))
+    }
+
+    fn file_builder(toml_config: &str) -> Result<ExecutorConfigBuilder, ExecutorError> {
+        // toml error message already points to the key and the line
+        let file: ExecutorConfigFile = toml::from_str(toml_config)
+            .map_err(|error| ExecutorError::InvalidConfig(error.to_string()))?;
+
+        let mut builder = ExecutorConfigBuilder::new();
+
+        if let Some(rate_limit_per_sec) = file.rate_limit_per_sec {
+            builder = builder.with_rate_limit_per_sec(rate_limit_per_sec);
+        }
+        if let Some(task_timeout) = file.task_timeout {
+            builder = builder.with_task_timeout(parse_duration("task_timeout", &task_timeout)?);
+        }
+        if let Some(worker_count) = file.worker_count {
+            builder = builder.with_worker_count(worker_count);
+        }
+        if let Some(shutdown_timeout) = file.shutdown_timeout {
+            builder = builder.with_shutdown_timeout(parse_duration("shutdown_timeout", &shutdown_timeout)?);
+        }
+        if let Some(max_tasks_per_worker) = file.max_tasks_per_worker {
+            builder = builder.with_max_tasks_per_worker(max_tasks_per_worker);
+        }
+        if let Some(queue_capacity) = file.queue_capacity {
+            builder = builder.with_queue_capacity(queue_capacity);
+        }
+        // After worker_count, so min/max can change the fixed pool
+        if let Some(min_workers) = file.min_workers {
+            builder = builder.with_min_workers(min_workers);
+        }
+        if let Some(max_workers) = file.max_workers {
+            builder = builder.with_max_workers(max_workers);
+        }
+        if let Some(scale_up_queue_depth) = file.scale_up_queue_depth {
+            builder = builder.with_scale_up_queue_depth(scale_up_queue_depth);
+        }
+        if let Some(scale_up_wait_time) = file.scale_up_wait_time {
+            builder = builder.with_scale_up_wait_time(parse_duration("scale_up_wait_time", &scale_up_wait_time)?);
+        }
+        if let Some(worker_keep_alive) = file.worker_keep_alive {
+            builder = builder.with_worker_keep_alive(parse_duration("worker_keep_alive", &worker_keep_alive)?);
+        }
+        if let Some(result_ttl) = file.result_ttl {
+            builder = builder.with_result_ttl(parse_duration("result_ttl", &result_ttl)?);
+        }
+        if let Some(result_reap_interval) = file.result_reap_interval {
+            builder = builder.with_result_reap_interval(parse_duration("result_reap_interval", &result_reap_interval)?);
+        }
+        Ok(builder)
+    }
+
+    // `env` is passed so tests don't have to change the environment of the whole test process
+    fn apply_env<F>(mut builder: ExecutorConfigBuilder, env: F) -> Result<ExecutorConfigBuilder, ExecutorError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        if let Some(value) = env(ENV_RATE_LIMIT) {
+            builder = builder.with_rate_limit_per_sec(parse_number(ENV_RATE_LIMIT, &value)?).with_env_var(ENV_RATE_LIMIT, &["rate_limit_per_sec"]);
+        }
+        if let Some(value) = env(ENV_TASK_TIMEOUT) {
+            builder = builder.with_task_timeout(parse_duration(ENV_TASK_TIMEOUT, &value)?).with_env_var(ENV_TASK_TIMEOUT, &["task_timeout"]);
+        }
+        if let Some(value) = env(ENV_WORKERS) {
+            builder = builder.with_worker_count(parse_number(ENV_WORKERS, &value)?).with_env_var(ENV_WORKERS, &["min_workers", "max_workers"]);
+        }
+        if let Some(value) = env(ENV_SHUTDOWN_TIMEOUT) {
+            builder = builder.with_shutdown_timeout(parse_duration(ENV_SHUTDOWN_TIMEOUT, &value)?).with_env_var(ENV_SHUTDOWN_TIMEOUT, &["shutdown_timeout"]);
+        }
+        if let Some(value) = env(ENV_MAX_TASKS_PER_WORKER) {
+            builder = builder.with_max_tasks_per_worker(parse_number(ENV_MAX_TASKS_PER_WORKER, &value)?).with_env_var(ENV_MAX_TASKS_PER_WORKER, &["max_tasks_per_worker"]);
+        }
+        if let Some(value) = env(ENV_QUEUE_CAPACITY) {
+            builder = builder.with_queue_capacity(parse_number(ENV_QUEUE_CAPACITY, &value)?).with_env_var(ENV_QUEUE_CAPACITY, &["queue_capacity"]);
+        }
+        if let Some(value) = env(ENV_MIN_WORKERS) {
+            builder = builder.with_min_workers(parse_number(ENV_MIN_WORKERS, &value)?).with_env_var(ENV_MIN_WORKERS, &["min_workers"]);
+        }
+        if let Some(value) = env(ENV_MAX_WORKERS) {
+            builder = builder.with_max_workers(parse_number(ENV_MAX_WORKERS, &value)?).with_env_var(ENV_MAX_WORKERS, &["max_workers"]);
+        }
+        if let Some(value) = env(ENV_SCALE_UP_QUEUE_DEPTH) {
+            builder = builder.with_scale_up_queue_depth(parse_number(ENV_SCALE_UP_QUEUE_DEPTH, &value)?).with_env_var(ENV_SCALE_UP_QUEUE_DEPTH, &["scale_up_queue_depth"]);
+        }
+        if let Some(value) = env(ENV_SCALE_UP_WAIT_TIME) {
+            builder = builder.with_scale_up_wait_time(parse_duration(ENV_SCALE_UP_WAIT_TIME, &value)?).with_env_var(ENV_SCALE_UP_WAIT_TIME, &["scale_up_wait_time"]);
+        }
+        if let Some(value) = env(ENV_WORKER_KEEP_ALIVE) {
+            builder = builder.with_worker_keep_alive(parse_duration(ENV_WORKER_KEEP_ALIVE, &value)?).with_env_var(ENV_WORKER_KEEP_ALIVE, &["worker_keep_alive"]);
+        }
+        if let Some(value) = env(ENV_RESULT_TTL) {
+            builder = builder.with_result_ttl(parse_duration(ENV_RESULT_TTL, &value)?).with_env_var(ENV_RESULT_TTL, &["result_ttl"]);
+        }
+        if let Some(value) = env(ENV_RESULT_REAP_INTERVAL) {
+            builder = builder.with_result_reap_interval(parse_duration(ENV_RESULT_REAP_INTERVAL, &value)?).with_env_var(ENV_RESULT_REAP_INTERVAL, &["result_reap_interval"]);
+        }
+        Ok(builder)
+    }
+}
+
+fn parse_duration(key: &str, value: &str) -> Result<Duration, ExecutorError> {
+    humantime::parse_duration(value.trim())
+        .map_err(|error| ExecutorError::InvalidConfig(format!("{}: invalid duration \"{}\" ({}), expected e.g. \"500ms\" or \"5s\"", key, value, error)))
+}
+
+fn parse_number(key: &str, value: &str) -> Result<usize, ExecutorError> {
+    value.trim()
+        .parse()
+        .map_err(|_| ExecutorError::InvalidConfig(format!("{}: expected a positive number, got \"{}\"", key, value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn invalid_config_message(result: Result<ExecutorConfig, ExecutorError>) -> String {
+        match result {
+            Err(ExecutorError::InvalidConfig(message)) => message,
+            other => panic!("Expected InvalidConfig, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_from_toml_str_should_read_values_and_durations() {
+        let config = ExecutorConfig::from_toml_str(r#"
+            rate_limit_per_sec = 20
+            worker_count = 3
+            task_timeout = "500ms"
+            shutdown_timeout = "1m 30s"
+        "#).unwrap();
+
+        assert_eq!(config.rate_limit_per_sec, 20);
+        assert_eq!(config.get_total_workers(), 3);
+        assert_eq!(config.get_task_timeout(), Duration::from_millis(500));
+        assert_eq!(config.get_shutdown_timeout(), Duration::from_secs(90));
+        assert_eq!(config.get_queue_capacity(), ExecutorConfig::default().get_queue_capacity()); // Missing key keeps the default
+    }
+
+    #[test]
+    fn test_invalid_toml_values_should_point_to_the_key() {
+        let message = invalid_config_message(ExecutorConfig::from_toml_str(r#"task_timeout = "5 sex""#));
+        assert!(message.starts_with("task_timeout: invalid duration \"5 sex\""), "{}", message);
+
+        let message = invalid_config_message(ExecutorConfig::from_toml_str("worker_cout = 2"));
+        assert!(message.contains("worker_cout"), "{}", message);
+
+        let message = invalid_config_message(ExecutorConfig::from_toml_str(r#"worker_count = "two""#));
+        assert!(message.contains("worker_count"), "{}", message);
+
+        let message = invalid_config_message(ExecutorConfig::from_toml_str("worker_count = 0"));
+        assert!(message.contains("worker_count"), "{}", message);
+    }
+
+    #[test]
+    fn test_env_should_override_file_values() {
+        let env: HashMap<&str, &str> = HashMap::from([(ENV_RATE_LIMIT, "50"), (ENV_TASK_TIMEOUT, "2s"), (ENV_RESULT_TTL, "10m")]);
+
+        let builder = ExecutorConfig::file_builder("rate_limit_per_sec = 10\nworker_count = 2\nresult_ttl = \"1h\"").unwrap();
+        let config = ExecutorConfig::apply_env(builder, |key| env.get(key).map(|value| value.to_string()))
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(config.rate_limit_per_sec, 50);
+        assert_eq!(config.get_task_timeout(), Duration::from_secs(2));
+        assert_eq!(config.get_result_ttl(), Duration::from_secs(600));
+        assert_eq!(config.get_total_workers(), 2); // Not overridden
+    }
+
+    #[test]
+    fn test_min_and_max_workers_should_override_worker_count() {
+        let config = ExecutorConfig::from_toml_str(r#"
+            worker_count = 2
+            max_workers = 6
+            worker_keep_alive = "10s"
+        "#).unwrap();
+
+        assert_eq!((config.get_min_workers(), config.get_max_workers()), (2, 6));
+        assert_eq!(config.get_worker_keep_alive(), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_invalid_env_value_should_point_to_the_variable() {
+        let result = ExecutorConfig::apply_env(ExecutorConfigBuilder::new(), |key| (key == ENV_WORKERS).then(|| String::from("many")));
+
+        let message = invalid_config_message(result.and_then(|builder| builder.build()));
+        assert_eq!(message, "TASK_EXEC_WORKERS: expected a positive number, got \"many\"");
+    }
+
+    #[test]
+    fn test_invalid_env_override_should_point_to_the_variable() {
+        let env: HashMap<&str, &str> = HashMap::from([(ENV_RATE_LIMIT, "0"), (ENV_MAX_WORKERS, "1")]);
+        let builder = ExecutorConfig::file_builder("worker_count = 0").unwrap();
+
+        let result = ExecutorConfig::apply_env(builder, |key| env.get(key).map(|value| value.to_string()));
+        let message = invalid_config_message(result.and_then(|builder| builder.build()));
+        assert_eq!(message, "worker_count (min_workers) must be greater than 0"); // From the file => no variable
+
+        let result = ExecutorConfig::apply_env(ExecutorConfigBuilder::new().with_min_workers(2), |key| env.get(key).map(|value| value.to_string()));
+        let message = invalid_config_message(result.and_then(|builder| builder.build()));
+        assert_eq!(message, "TASK_EXEC_MAX_WORKERS: max_workers must not be smaller than min_workers");
+
+        let result = ExecutorConfig::apply_env(ExecutorConfigBuilder::new(), |key| (key == ENV_RATE_LIMIT).then(|| String::from("0")));
+        let message = invalid_config_message(result.and_then(|builder| builder.build()));
+        assert_eq!(message, "TASK_EXEC_RATE_LIMIT: rate_limit_per_sec must be greater than 0");
+    }
+
+    #[test]
+    fn test_from_toml_file_should_fail_for_missing_file() {
+        let message = invalid_config_message(ExecutorConfig::from_toml_file("does/not/exist.toml"));
+        assert!(message.contains("does/not/exist.toml"), "{}", message);
+    }
+}
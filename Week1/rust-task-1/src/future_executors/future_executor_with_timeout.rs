@@ -1,64 +1,117 @@
-use std::{task::Poll, thread, time::{Duration, Instant}};
+use std::{any::Any, convert::Infallible, panic::{self, AssertUnwindSafe}, pin::Pin, sync::Arc, task::{Context, Poll, Waker}, time::{Duration, Instant}};
 
-use crate::error_handler::error_handler::{fail_gracefully, ExecutorError};
-use std::task::Context;
-use futures::task::noop_waker;
+use crate::core::{job::{report_failure, Job}, types::FailureCallback};
+use crate::error_handler::error_handler::{fail_gracefully, panic_message, ExecutorError, TaskError};
+use crate::lifecycle::task_state::TaskState;
+use crate::result_backend::types::OutputSlot;
 
-use super::{future_status::FutureStatus, future_types::FutureTypes};
+use super::{future_status::FutureStatus, future_types::FutureTypes, task_waker::TaskWaker};
 
+/*
+    One in-flight task of the worker local run queue.
+    - The worker polls it only when its TaskWaker was called
+    - The worker checks the deadline and cancels the task when timeout is reached
+*/
 pub struct CustomFutureExecutorTimeout {
     status: FutureStatus,
     future: FutureTypes,
+    on_failure: Option<FailureCallback>,
+    output: Option<OutputSlot>,
+    waker: Arc<TaskWaker>,
+    start: Instant,
+    deadline: Instant,
 }
 
 impl CustomFutureExecutorTimeout {
-    pub fn poll_future(&mut self, timeout: Duration) -> FutureStatus {
+    pub fn new(job: Job, waker: Arc<TaskWaker>, timeout: Duration) -> CustomFutureExecutorTimeout {
         let start = Instant::now(); // Timer stars here
-        let timeout = timeout;
+        let mut status = FutureStatus::new(job.get_context());
+        status.result_ttl = job.result_ttl;
+        status.span = job.span;
+
+        CustomFutureExecutorTimeout { 
+            status,
+            future: job.future,
+            on_failure: job.on_failure,
+            output: job.output,
+            waker,
+            start,
+            deadline: start + timeout,
+        }
+    }
 
-        let waker = noop_waker();
+    // Polls the future once, returns the status when the future is finished
+    pub fn poll_once(&mut self) -> Option<FutureStatus> {
+        self.waker.reset();
+
+        let waker = Waker::from(self.waker.clone());
         let mut cx = Context::from_waker(&waker);
 
-        let future = match self.future.clone() {
-            FutureTypes::FutureNoOutput(fut) => fut,
+        // Panic inside the future must not kill the worker thread => it is caught and the task counts as panicked
+        // Span of the task is entered for every poll => events of the task code belong to the task
+        let polled = self.status.span.in_scope(|| panic::catch_unwind(AssertUnwindSafe(|| Pin::new(&mut self.future).poll(&mut cx))));
+        let poll_result = match polled {
+            Ok(poll_result) => poll_result,
+            Err(payload) => return Some(self.fail_panicked(payload)),
         };
 
-        loop {
-            // Here i can check if task is running on a thread and which task is running on this thread
+        match poll_result {
+            Poll::Ready(Ok(())) => {
+                self.status.execution_time = self.start.elapsed(); // Span of the task records it in finished()
+                self.status.state = TaskState::Succeeded;
+                self.status.output = self.output.as_ref().and_then(|output| output.lock().unwrap().take());
 
-            // When Poll::Pending i will check if this is true => TIMEOUT CHECKER === 
-            if start.elapsed().as_secs() >= timeout.as_secs() {
-                self.status.failed = true;
-                
-                fail_gracefully(ExecutorError::Timeout, "Task timed out!");
-                drop(future); // Cancel the task via timeout
-                break;
+                Some(self.status.clone())
             }
+            Poll::Ready(Err(message)) => {
+                // The task itself returned Err => the TaskHandle already has the error
+                fail_gracefully(ExecutorError::TaskFailed, &message);
+                self.status.state = TaskState::Failed;
+                self.status.error = Some(message);
 
-            match future.lock().unwrap().as_mut().poll(&mut cx) {
-                Poll::Ready(()) => {
-                    let duration = start.elapsed();
-                    println!("\n✅ Task finished in {:?}.\n", duration);
-                    self.status.execution_time = duration.as_secs() as u32;
-                    self.status.succeeded = true;
+                Some(self.status.clone())
+            }
+            Poll::Pending => None // Waiting the task to get completed, the waker will schedule it again
+        }
+    }
 
-                    return self.status;
-                }
-                Poll::Pending => {
-                    // Waiting the task to get completed
-                    thread::sleep(Duration::from_millis(10)); // polling interval => Meaning it with continue looping until Poll:Ready() or drop(future)
-                }
-            };
-        };  
-        self.status.failed = true;
+    fn fail_panicked(&mut self, payload: Box<dyn Any + Send>) -> FutureStatus {
+        let message = panic_message(payload);
+
+        fail_gracefully(ExecutorError::TaskPanicked, &message);
+        report_failure(&mut self.on_failure, TaskError::Panicked(message.clone()));
+
+        self.status.state = TaskState::Failed;
+        self.status.panicked = true;
+        self.status.error = Some(message);
+        self.status.clone()
+    }
+
+    pub fn timeout_reached(&self, now: Instant) -> bool {
+        now >= self.deadline
+    }
+
+    // TIMEOUT CHECKER === Consumes the task so the future is dropped => Cancel the task via timeout
+    pub fn cancel_timed_out(mut self) -> FutureStatus {
+        fail_gracefully(ExecutorError::Timeout, "Task timed out!");
+        report_failure(&mut self.on_failure, TaskError::Timeout);
+
+        self.status.state = TaskState::TimedOut;
+        self.status.error = Some(TaskError::<Infallible>::Timeout.to_string());
         self.status
     }
 
-    pub fn new(fut: FutureTypes) -> CustomFutureExecutorTimeout {
-        CustomFutureExecutorTimeout { 
-            status: FutureStatus::default(), 
-            future: fut,
-        }
+    // Shutdown with ShutdownMode::Abort => the future is dropped without another poll
+    pub fn abort(mut self) -> FutureStatus {
+        report_failure(&mut self.on_failure, TaskError::Cancelled);
+
+        self.status.state = TaskState::Cancelled;
+        self.status.error = Some(TaskError::<Infallible>::Cancelled.to_string());
+        self.status
+    }
+
+    pub fn get_deadline(&self) -> Instant {
+        self.deadline
     }
 }
 
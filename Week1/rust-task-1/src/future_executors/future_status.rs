@@ -1,21 +1,42 @@
+use std::time::Duration;
 
-#[derive(Clone, Copy)]
+use crate::lifecycle::{task_id::TaskId, task_state::TaskState};
+use crate::middleware::task_context::TaskContext;
+use crate::telemetry::task_span::TaskSpan;
+
+// Outcome of one task run by the worker, stored in the ResultBackend when the task is finished
+#[derive(Debug, Clone)]
 pub struct FutureStatus {
-    pub succeeded: bool, 
-    pub failed: bool,
-    pub execution_time: u32,
+    pub task_id: TaskId,
+    pub state: TaskState, // Running till the task is finished, then Succeeded, Failed, TimedOut or Cancelled
+    pub panicked: bool, // Panicked task is also failed
+    pub execution_time: Duration, // Stored as it is, the metrics count whole seconds
+    pub error: Option<String>, // Err message, panic message or why the task was stopped
+    pub output: Option<String>, // JSON of the output of Job::with_stored_output()
+    pub result_ttl: Option<Duration>, // None => result_ttl of the ExecutorConfig
+    pub context: TaskContext, // Name, priority and attempt of the job for the middleware
+    pub span: TaskSpan, // Span of the attempt, the worker records the finish in it
 }
 
 impl FutureStatus {
-    const DEFAULT_EXECUTION_TIME: u32 = 0;
-}
+    const DEFAULT_EXECUTION_TIME: Duration = Duration::ZERO;
 
-impl Default for FutureStatus {
-    fn default() -> Self {
-        Self {
-            succeeded: false,
-            failed: false,
+    pub fn new(context: TaskContext) -> FutureStatus {
+        FutureStatus {
+            task_id: context.task_id,
+            state: TaskState::Running,
+            panicked: false,
             execution_time: FutureStatus::DEFAULT_EXECUTION_TIME,
+            error: None,
+            output: None,
+            result_ttl: None,
+            context,
+            span: TaskSpan::none(),
         }
     }
-}
\ No newline at end of file
+
+    // Counted as failed task in the metrics, Cancelled is counted by the ShutdownReport
+    pub fn is_failed(&self) -> bool {
+        matches!(self.state, TaskState::Failed | TaskState::TimedOut)
+    }
+}
@@ -1,18 +1,36 @@
 
 
-use std::sync::{Arc, Mutex};
+use std::{pin::Pin, sync::{Arc, Mutex}, task::{Context, Poll}};
 
-use crate::core::types::Task;
+use crate::core::types::{FallibleTask, Task};
 
-use super::types::MutableFuture;
+use super::types::{MutableFallibleFuture, MutableFuture};
 
 
 #[derive(Clone)]
 pub enum FutureTypes {
     FutureNoOutput(MutableFuture),
+    FutureWithResult(MutableFallibleFuture), // Err(message) counts as failed task
 }
 
 pub fn receive_future_no_output(fut: Task) -> FutureTypes {
     let mutable_future = Arc::new(Mutex::new(fut));
     FutureTypes::FutureNoOutput(mutable_future)
 }
+
+pub fn receive_future_with_result(fut: FallibleTask) -> FutureTypes {
+    let mutable_future = Arc::new(Mutex::new(fut));
+    FutureTypes::FutureWithResult(mutable_future)
+}
+
+// Both types are polled the same way, future without output is always Ok
+impl Future for FutureTypes {
+    type Output = Result<(), String>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match &*self {
+            FutureTypes::FutureNoOutput(future) => future.lock().unwrap().as_mut().poll(cx).map(Ok),
+            FutureTypes::FutureWithResult(future) => future.lock().unwrap().as_mut().poll(cx),
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use std::{collections::HashMap, time::{Duration, Instant}};
+
+use crossbeam::channel::{self, Receiver, Select, Sender};
+
+use crate::{channel::types::ReceiverType, core::job::Job};
+
+use super::{future_executor_with_timeout::CustomFutureExecutorTimeout, future_status::FutureStatus, task_waker::TaskWaker};
+
+/*
+    Each worker thread owns one LocalRunQueue with many in-flight futures.
+    - Futures are interleaved cooperatively: a future is polled only after it is woken
+    - While no future is ready the worker parks on the ready queue and the worker channel
+    - min_workers..max_workers limits parallelism (threads), max_tasks limits concurrency (futures per thread)
+*/
+pub struct LocalRunQueue {
+    tasks: HashMap<usize, CustomFutureExecutorTimeout>,
+    next_task_id: usize,
+    max_tasks: usize,
+    ready_tx: Sender<usize>, // Wakers push the id of the task that can make progress
+    ready_rx: Receiver<usize>,
+}
+
+impl LocalRunQueue {
+    pub fn new(max_tasks: usize) -> LocalRunQueue {
+        let (ready_tx, ready_rx) = channel::unbounded();
+
+        LocalRunQueue {
+            tasks: HashMap::new(),
+            next_task_id: 0,
+            max_tasks,
+            ready_tx,
+            ready_rx,
+        }
+    }
+
+    pub fn spawn(&mut self, job: Job, timeout: Duration) {
+        let task_id = self.next_task_id;
+        self.next_task_id = self.next_task_id.wrapping_add(1);
+
+        let waker = TaskWaker::new(task_id, self.ready_tx.clone());
+        let future_exec = CustomFutureExecutorTimeout::new(job, waker, timeout);
+        self.tasks.insert(task_id, future_exec);
+
+        let _ = self.ready_tx.send(task_id); // Every new task is polled once
+    }
+
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    pub fn has_capacity(&self) -> bool {
+        self.tasks.len() < self.max_tasks
+    }
+
+    // Polls every woken task once, returns the status of the tasks that finished
+    // Only the ids woken before this call => a task that wakes itself in every poll is polled again in the next pass,
+    // so the worker still checks the timeouts, the channel and the stop flag in between
+    pub fn poll_ready(&mut self) -> Vec<FutureStatus> {
+        let mut finished = Vec::new();
+        let woken = self.ready_rx.len();
+
+        for task_id in self.ready_rx.try_iter().take(woken) {
+            // Task can be already finished or cancelled by timeout
+            let Some(future_exec) = self.tasks.get_mut(&task_id) else { continue };
+
+            if let Some(status) = future_exec.poll_once() {
+                self.tasks.remove(&task_id);
+                finished.push(status);
+            }
+        }
+        finished
+    }
+
+    // Drops every task that reached its timeout, returns their (failed) status
+    pub fn cancel_timed_out(&mut self) -> Vec<FutureStatus> {
+        let now = Instant::now();
+
+        let timed_out: Vec<usize> = self.tasks
+            .iter()
+            .filter(|(_, future_exec)| future_exec.timeout_reached(now))
+            .map(|(task_id, _)| *task_id)
+            .collect();
+
+        timed_out
+            .into_iter()
+            .filter_map(|task_id| self.tasks.remove(&task_id))
+            .map(|future_exec| future_exec.cancel_timed_out())
+            .collect()
+    }
+
+    // Drops every task, returns their (cancelled) status
+    pub fn abort_all(&mut self) -> Vec<FutureStatus> {
+        self.tasks
+            .drain()
+            .map(|(_, future_exec)| future_exec.abort())
+            .collect()
+    }
+
+    /*
+        Parks the worker until one of these happens:
+            - A task is woken
+            - A new task is in the worker channel (only when worker_rx is passed)
+            - The nearest task deadline or max_wait is reached
+        Nothing is received here, the caller takes the work after this returns.
+    */
+    pub fn wait(&self, worker_rx: Option<&ReceiverType>, max_wait: Duration) {
+        let mut wait_time = max_wait;
+
+        if let Some(deadline) = self.tasks.values().map(|future_exec| future_exec.get_deadline()).min() {
+            wait_time = wait_time.min(deadline.saturating_duration_since(Instant::now()));
+        }
+
+        let mut select = Select::new();
+        select.recv(&self.ready_rx);
+
+        if let Some(rx) = worker_rx {
+            rx.register(&mut select);
+        }
+
+        let _ = select.ready_timeout(wait_time);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::Infallible, task::Poll};
+
+    use futures_timer::Delay;
+
+    use super::*;
+    use crate::{core::task_handle::task_handle, error_handler::error_handler::TaskError, lifecycle::task_state::TaskState};
+
+    fn run_to_completion(run_queue: &mut LocalRunQueue) -> Vec<FutureStatus> {
+        let mut statuses = Vec::new();
+
+        while !run_queue.is_empty() {
+            statuses.extend(run_queue.poll_ready());
+            statuses.extend(run_queue.cancel_timed_out());
+            run_queue.wait(None, Duration::from_millis(100));
+        }
+        statuses
+    }
+
+    #[test]
+    fn test_spawned_tasks_should_run_concurrently() {
+        let mut run_queue = LocalRunQueue::new(10);
+        let start = Instant::now();
+
+        for _ in 0..5 {
+            run_queue.spawn(Job::new(Box::pin(Delay::new(Duration::from_millis(300)))), Duration::from_secs(5));
+        }
+        assert_eq!(run_queue.len(), 5);
+
+        let statuses = run_to_completion(&mut run_queue);
+
+        assert_eq!(statuses.len(), 5);
+        assert!(statuses.iter().all(|status| status.state == TaskState::Succeeded));
+        assert!(start.elapsed() < Duration::from_millis(1000)); // Sequentially it would take 1.5 seconds
+    }
+
+    #[test]
+    fn test_task_longer_than_timeout_should_fail() {
+        let mut run_queue = LocalRunQueue::new(10);
+
+        let slow_job = Job::new(Box::pin(Delay::new(Duration::from_secs(5))));
+        let slow_id = slow_job.id;
+        run_queue.spawn(slow_job, Duration::from_millis(200));
+        run_queue.spawn(Job::new(Box::pin(Delay::new(Duration::from_millis(50)))), Duration::from_millis(200));
+
+        let statuses = run_to_completion(&mut run_queue);
+
+        assert_eq!(statuses.iter().filter(|status| status.state == TaskState::Succeeded).count(), 1);
+        assert_eq!(statuses.iter().filter(|status| status.is_failed()).count(), 1);
+        assert!(statuses.iter().any(|status| status.task_id == slow_id && status.state == TaskState::TimedOut));
+    }
+
+    #[test]
+    fn test_self_waking_task_should_still_time_out() {
+        let mut run_queue = LocalRunQueue::new(10);
+
+        // Yield-now pattern => wakes itself before every Pending, the ready queue is never empty
+        let busy_job = Job::new(Box::pin(futures::future::poll_fn(|cx| {
+            cx.waker().wake_by_ref();
+            Poll::<()>::Pending
+        })));
+        let busy_id = busy_job.id;
+        run_queue.spawn(busy_job, Duration::from_millis(100));
+        run_queue.spawn(Job::new(Box::pin(Delay::new(Duration::from_millis(50)))), Duration::from_secs(1));
+
+        let start = Instant::now();
+        let statuses = run_to_completion(&mut run_queue);
+
+        assert!(statuses.iter().any(|status| status.task_id == busy_id && status.state == TaskState::TimedOut));
+        assert_eq!(statuses.iter().filter(|status| status.state == TaskState::Succeeded).count(), 1);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_timed_out_task_should_report_timeout_to_handle() {
+        let mut run_queue = LocalRunQueue::new(10);
+        let (completer, handle) = task_handle::<(), Infallible>();
+        let on_failure = completer.failure_callback();
+
+        let future = async move {
+            Delay::new(Duration::from_secs(5)).await;
+            completer.complete(());
+        };
+        run_queue.spawn(Job::new(Box::pin(future)).with_failure_callback(on_failure), Duration::from_millis(100));
+
+        run_to_completion(&mut run_queue);
+
+        assert_eq!(handle.join(), Err(TaskError::Timeout));
+    }
+
+    #[test]
+    fn test_fallible_task_returning_err_should_fail() {
+        let mut run_queue = LocalRunQueue::new(10);
+
+        run_queue.spawn(Job::fallible(Box::pin(async { Err(String::from("service unavailable")) })), Duration::from_secs(1));
+        run_queue.spawn(Job::fallible(Box::pin(async { Ok(()) })), Duration::from_secs(1));
+
+        let statuses = run_to_completion(&mut run_queue);
+
+        assert_eq!(statuses.iter().filter(|status| status.state == TaskState::Succeeded).count(), 1);
+        assert_eq!(statuses.iter().filter(|status| status.is_failed()).count(), 1);
+    }
+
+    #[test]
+    fn test_panicking_task_should_not_stop_other_tasks() {
+        let mut run_queue = LocalRunQueue::new(10);
+        let (completer, handle) = task_handle::<(), Infallible>();
+        let on_failure = completer.failure_callback();
+
+        let future = async move {
+            let _completer = completer;
+            Delay::new(Duration::from_millis(20)).await;
+            panic!("boom");
+        };
+        run_queue.spawn(Job::new(Box::pin(future)).with_failure_callback(on_failure), Duration::from_secs(1));
+        run_queue.spawn(Job::new(Box::pin(Delay::new(Duration::from_millis(50)))), Duration::from_secs(1));
+
+        let statuses = run_to_completion(&mut run_queue);
+
+        assert_eq!(statuses.iter().filter(|status| status.panicked && status.is_failed()).count(), 1);
+        assert_eq!(statuses.iter().filter(|status| status.state == TaskState::Succeeded).count(), 1);
+        assert_eq!(handle.join(), Err(TaskError::Panicked(String::from("boom"))));
+    }
+
+    #[test]
+    fn test_has_capacity_should_respect_max_tasks() {
+        let mut run_queue = LocalRunQueue::new(2);
+
+        run_queue.spawn(Job::new(Box::pin(async {})), Duration::from_secs(1));
+        assert!(run_queue.has_capacity());
+
+        run_queue.spawn(Job::new(Box::pin(async {})), Duration::from_secs(1));
+        assert!(!run_queue.has_capacity());
+
+        run_queue.poll_ready();
+        assert!(run_queue.is_empty());
+    }
+
+    #[test]
+    fn test_abort_all_should_cancel_running_tasks() {
+        let mut run_queue = LocalRunQueue::new(10);
+        let (completer, handle) = task_handle::<(), Infallible>();
+        let on_failure = completer.failure_callback();
+
+        let future = async move {
+            Delay::new(Duration::from_secs(5)).await;
+            completer.complete(());
+        };
+        run_queue.spawn(Job::new(Box::pin(future)).with_failure_callback(on_failure), Duration::from_secs(10));
+        run_queue.spawn(Job::new(Box::pin(Delay::new(Duration::from_secs(5)))), Duration::from_secs(10));
+        run_queue.poll_ready();
+
+        let aborted = run_queue.abort_all();
+        assert_eq!(aborted.len(), 2);
+        assert!(aborted.iter().all(|status| status.state == TaskState::Cancelled));
+        assert!(run_queue.is_empty());
+        assert_eq!(handle.join(), Err(TaskError::Cancelled));
+    }
+}
@@ -1,4 +1,6 @@
 pub mod future_executor_with_timeout;
 pub mod future_types;
 pub mod future_status;
-pub mod types;
\ No newline at end of file
+pub mod types;
+pub mod task_waker;
+pub mod local_run_queue;
\ No newline at end of file
@@ -0,0 +1,43 @@
+use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
+use std::task::Wake;
+
+use crossbeam::channel::Sender;
+
+/*
+    Waker for one task inside the worker local run queue.
+    - When the future returns Poll::Pending it stores a clone of this waker (Delay, channels, etc.)
+    - When the future can make progress it calls wake() -> i push the task id into the ready queue of the worker
+    - The worker is parked on the ready queue (and the worker channel) so it is woken up only when there is work to do
+*/
+pub struct TaskWaker {
+    task_id: usize,
+    scheduled: AtomicBool, // Prevents pushing the same task id many times before it is polled again
+    ready_tx: Sender<usize>,
+}
+
+impl TaskWaker {
+    pub fn new(task_id: usize, ready_tx: Sender<usize>) -> Arc<TaskWaker> {
+        Arc::new(TaskWaker {
+            task_id,
+            scheduled: AtomicBool::new(false),
+            ready_tx,
+        })
+    }
+
+    // Called by the worker right before it polls the task, so wake() calls during the poll schedule it again
+    pub fn reset(&self) {
+        self.scheduled.store(false, Ordering::Release);
+    }
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        if !self.scheduled.swap(true, Ordering::AcqRel) {
+            let _ = self.ready_tx.send(self.task_id); // Fails only when the worker is gone, then there is nobody to wake
+        }
+    }
+}
@@ -1,8 +1,9 @@
 use std::sync::{Arc, Mutex};
 
 
-use crate::core::types::Task;
+use crate::core::types::{FallibleTask, Task};
 
 
 
-pub type MutableFuture = Arc<Mutex<Task>>;
\ No newline at end of file
+pub type MutableFuture = Arc<Mutex<Task>>;
+pub type MutableFallibleFuture = Arc<Mutex<FallibleTask>>;
\ No newline at end of file
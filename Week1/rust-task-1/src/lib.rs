@@ -1,3 +1,9 @@
+// #[task] expands to paths of this crate (::rust_task_1::...), this makes them work inside the crate too
+extern crate self as rust_task_1;
+
+// #[task(name = "send_email", priority = High, ...)] on an async fn => registry entry + typed .delay()
+pub use rust_task_1_macros::task;
+
 // Proxy, AsyncExecutor 
 pub mod core;
 
@@ -11,10 +17,33 @@ pub mod priority;
 
 pub mod executor_config;
 
+pub mod executor_config_loader;
+
 pub mod error_handler;
 
 pub mod rate_limiting;
 
 pub mod testing_functions;
 
-pub mod future_executors;
\ No newline at end of file
+pub mod future_executors;
+
+pub mod retry;
+
+pub mod canvas;
+
+pub mod scheduler;
+
+pub mod routing;
+
+pub mod lifecycle;
+
+pub mod result_backend;
+
+pub mod durability;
+
+pub mod registry;
+
+pub mod middleware;
+
+// Per-task spans of the optional `tracing` feature
+pub mod telemetry;
\ No newline at end of file
@@ -0,0 +1,4 @@
+pub mod task_id;
+pub mod task_state;
+pub mod task_tracker;
+pub mod types;
@@ -0,0 +1,84 @@
+use std::fmt::Display;
+use std::hash::{BuildHasher, RandomState};
+use std::sync::{atomic::{AtomicU64, Ordering}, LazyLock};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+/*
+    Id = random salt of the process (upper 32 bits) + counter (lower 32 bits).
+    A counter alone starts at 1 in every process => `<id>.json` of the FileResultBackend from an earlier run
+    or from another process would be returned as the result of a new task or overwritten.
+*/
+static PROCESS_SALT: LazyLock<u64> = LazyLock::new(|| {
+    let random = RandomState::new().hash_one((std::process::id(), SystemTime::now())); // RandomState keys are random for every process
+    (random & 0x7FFF_FFFF) << 32 // Top bit stays free, the counter can't overflow u64
+});
+static NEXT_TASK_ID: LazyLock<AtomicU64> = LazyLock::new(|| AtomicU64::new(*PROCESS_SALT + 1));
+
+// Given to every job when it is created, unique in the whole process (every executor and queue) and across processes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TaskId(u64);
+
+impl TaskId {
+    pub fn next() -> TaskId {
+        TaskId(NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    // Ids replayed from the WriteAheadLog are never given to new tasks
+    // Ids of another process have another salt => skipped, moving into their range could hit their stored results
+    pub fn advance_past(id: TaskId) {
+        if id.0 >> 32 == *PROCESS_SALT >> 32 {
+            NEXT_TASK_ID.fetch_max(id.0 + 1, Ordering::Relaxed);
+        }
+    }
+
+    // Id read back from a ResultBackend or passed by another process
+    pub fn from_value(value: u64) -> TaskId {
+        TaskId(value)
+    }
+
+    pub fn get_value(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Display for TaskId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_should_return_unique_increasing_ids() {
+        let first = TaskId::next();
+        let second = TaskId::next();
+
+        assert_ne!(first, second);
+        assert!(second > first);
+        assert_eq!(first.to_string(), first.get_value().to_string());
+    }
+
+    #[test]
+    fn test_advance_past_should_skip_replayed_ids() {
+        let replayed = TaskId::from_value(TaskId::next().get_value() + 100);
+        TaskId::advance_past(replayed);
+
+        assert!(TaskId::next() > replayed);
+    }
+
+    #[test]
+    fn test_ids_should_be_salted_by_the_process() {
+        let id = TaskId::next();
+        assert_eq!(id.get_value() >> 32, *PROCESS_SALT >> 32);
+
+        // Replayed id of another process does not move the counter into its range
+        let other_process = TaskId::from_value(((*PROCESS_SALT >> 32) ^ 1) << 32 | 5);
+        TaskId::advance_past(other_process);
+        assert_eq!(TaskId::next().get_value() >> 32, *PROCESS_SALT >> 32);
+    }
+}
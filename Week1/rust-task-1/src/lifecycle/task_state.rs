@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+/*
+    Where the task is right now.
+
+    Pending ──> RateLimited ──> Queued ──> Running ──> Succeeded / Failed / TimedOut
+       │             │            │           │
+       └─────────────┴────────────┴───────────┴──> Cancelled
+                                              │
+                                              └──> Retrying ──> RateLimited / Queued (next attempt)
+
+    - Pending: accepted by the Proxy, waits for the rate limiter or for its eta (task_at, retry backoff)
+    - RateLimited: waits for a free slot of the rate limit
+    - Queued: in the worker channel (or waits for free space in it)
+    - Retrying: attempt failed and the retry policy scheduled the next one, the id stays the same
+    Succeeded, Failed, TimedOut and Cancelled are final, nothing moves the task out of them.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TaskState {
+    Pending,
+    RateLimited,
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    TimedOut,
+    Cancelled,
+    Retrying,
+}
+
+impl TaskState {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, TaskState::Succeeded | TaskState::Failed | TaskState::TimedOut | TaskState::Cancelled)
+    }
+
+    pub fn can_move_to(&self, next: TaskState) -> bool {
+        use TaskState::*;
+
+        match self {
+            Pending => matches!(next, RateLimited | Queued | Cancelled),
+            RateLimited => matches!(next, Queued | Cancelled),
+            Queued => matches!(next, Running | Cancelled),
+            Running => matches!(next, Succeeded | Failed | TimedOut | Cancelled | Retrying),
+            Retrying => matches!(next, RateLimited | Queued | Cancelled),
+            Succeeded | Failed | TimedOut | Cancelled => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_happy_path_transitions_should_be_allowed() {
+        let path = [TaskState::Pending, TaskState::RateLimited, TaskState::Queued, TaskState::Running, TaskState::Retrying, TaskState::Queued, TaskState::Running, TaskState::Succeeded];
+
+        assert!(path.windows(2).all(|step| step[0].can_move_to(step[1])));
+    }
+
+    #[test]
+    fn test_finished_states_should_not_move() {
+        let finished = [TaskState::Succeeded, TaskState::Failed, TaskState::TimedOut, TaskState::Cancelled];
+
+        for state in finished {
+            assert!(state.is_finished());
+            assert!(!state.can_move_to(TaskState::Running));
+            assert!(!state.can_move_to(TaskState::Retrying));
+        }
+    }
+
+    #[test]
+    fn test_task_should_not_skip_the_queue() {
+        assert!(!TaskState::Pending.can_move_to(TaskState::Running));
+        assert!(!TaskState::Queued.can_move_to(TaskState::Succeeded));
+        assert!(!TaskState::Retrying.can_move_to(TaskState::Failed));
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+use std::sync::Mutex;
+
+use super::{task_id::TaskId, task_state::TaskState};
+
+#[derive(Debug, Default)]
+struct TrackedTasks {
+    states: HashMap<TaskId, TaskState>,
+    finished: VecDeque<TaskId>, // Oldest finished task first, removed when there are more than MAX_FINISHED_TASKS
+}
+
+/*
+    Current TaskState of every task, updated by the rate limiter, the executor, the workers and the retries.
+    - Transition that is not allowed by TaskState::can_move_to() is ignored => e.g. the worker reports Failed
+      for an attempt that the retry policy already moved to Retrying
+    - Unknown id starts in the state it is moved to (jobs sent straight to AsyncExecutor::delay())
+    - Only the last MAX_FINISHED_TASKS finished tasks are kept, so the map does not grow forever
+*/
+#[derive(Debug, Default)]
+pub struct TaskTracker {
+    tasks: Mutex<TrackedTasks>,
+}
+
+impl TaskTracker {
+    const MAX_FINISHED_TASKS: usize = 10_000;
+
+    pub fn new() -> TaskTracker {
+        TaskTracker::default()
+    }
+
+    // Task is accepted => Pending, a task that is already tracked (next attempt of a retry) keeps its state and false is returned
+    pub fn track(&self, id: TaskId) -> bool {
+        match self.tasks.lock().unwrap().states.entry(id) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(TaskState::Pending);
+                true
+            }
+        }
+    }
+
+    // Returns false when the transition is not allowed
+    pub fn transition(&self, id: TaskId, next: TaskState) -> bool {
+        let mut tasks = self.tasks.lock().unwrap();
+
+        let current = tasks.states.get(&id).copied();
+        if current.is_some_and(|current| !current.can_move_to(next)) {
+            return false;
+        }
+        tasks.states.insert(id, next);
+
+        if next.is_finished() {
+            tasks.finished.push_back(id);
+
+            if tasks.finished.len() > TaskTracker::MAX_FINISHED_TASKS
+                && let Some(oldest) = tasks.finished.pop_front()
+            {
+                tasks.states.remove(&oldest);
+            }
+        }
+        true
+    }
+
+    pub fn get_state(&self, id: TaskId) -> Option<TaskState> {
+        self.tasks.lock().unwrap().states.get(&id).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transition_should_follow_the_state_machine() {
+        let tracker = TaskTracker::new();
+        let id = TaskId::next();
+
+        assert!(tracker.track(id));
+        assert_eq!(tracker.get_state(id), Some(TaskState::Pending));
+
+        assert!(tracker.transition(id, TaskState::Queued));
+        assert!(tracker.transition(id, TaskState::Running));
+        assert!(tracker.transition(id, TaskState::Retrying));
+        assert!(!tracker.transition(id, TaskState::Failed)); // Attempt is already retried
+
+        assert!(!tracker.track(id)); // Next attempt goes through the rate limiter again
+        assert_eq!(tracker.get_state(id), Some(TaskState::Retrying));
+
+        assert!(tracker.transition(id, TaskState::Queued));
+        assert!(tracker.transition(id, TaskState::Running));
+        assert!(tracker.transition(id, TaskState::Succeeded));
+        assert!(!tracker.transition(id, TaskState::Cancelled));
+        assert_eq!(tracker.get_state(id), Some(TaskState::Succeeded));
+    }
+
+    #[test]
+    fn test_unknown_id_should_return_none() {
+        let tracker = TaskTracker::new();
+
+        assert_eq!(tracker.get_state(TaskId::next()), None);
+    }
+
+    #[test]
+    fn test_oldest_finished_tasks_should_be_removed() {
+        let tracker = TaskTracker::new();
+        let first = TaskId::next();
+        tracker.transition(first, TaskState::Succeeded);
+
+        for _ in 0..TaskTracker::MAX_FINISHED_TASKS {
+            tracker.transition(TaskId::next(), TaskState::Cancelled);
+        }
+
+        assert_eq!(tracker.get_state(first), None);
+        assert_eq!(tracker.tasks.lock().unwrap().states.len(), TaskTracker::MAX_FINISHED_TASKS);
+    }
+}
@@ -0,0 +1,6 @@
+use std::sync::Arc;
+
+use super::task_tracker::TaskTracker;
+
+// Shared by every queue of a Proxy and by their workers
+pub type TaskTrackerArc = Arc<TaskTracker>;
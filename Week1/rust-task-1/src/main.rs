@@ -1,44 +1,46 @@
 use std::time::Duration;
 use futures_timer::Delay;
-use rust_task_1::{priority::priority::Priority, core::proxy::Proxy};
+// Used by the commented example in main()
+// use rust_task_1::{priority::priority::Priority, core::proxy::Proxy};
 
 
 fn main() {
     // Concurrent tasks in parallel is the idea     
-    // let mut executor = Proxy::new();
+    // Every operation returns Result<_, ExecutorError> => use `?` (main() -> Result<(), ExecutorError>) or handle the error
+    // let mut executor = Proxy::new()?;
 
     // // .task() sends the future(!You should send only Futures) to the task executor
     // // as First parameter you send your Future function as a second you send the priority(High, Medium, Low) or None if you dont want Priority => Priority::None
-    // executor.task(send_email(), Priority::High);
-    // executor.task(send_email(), Priority::None);
-    // executor.task(send_email(), Priority::High);
-    // executor.task(send_email(),  Priority::High);
-    // executor.task(send_ethers(), Priority::Medium);
-    // executor.task(send_ethers(), Priority::Low);
-    // executor.task(send_ethers(), Priority::Low);
-    // executor.task(send_ethers(), Priority::None);
-    // executor.task(send_email(), Priority::None);
-    // executor.task(send_email(), Priority::None);
-    // executor.task(send_email(), Priority::None);
-    // executor.task(send_email(), Priority::None);
-
-    // executor.task(task_with_parameters(String::from("John"), 24, String::from("John@gmail.com")), Priority::None);
+    // executor.task(send_email(), Priority::High)?;
+    // executor.task(send_email(), Priority::None)?;
+    // executor.task(send_email(), Priority::High)?;
+    // executor.task(send_email(),  Priority::High)?;
+    // executor.task(send_ethers(), Priority::Medium)?;
+    // executor.task(send_ethers(), Priority::Low)?;
+    // executor.task(send_ethers(), Priority::Low)?;
+    // executor.task(send_ethers(), Priority::None)?;
+    // executor.task(send_email(), Priority::None)?;
+    // executor.task(send_email(), Priority::None)?;
+    // executor.task(send_email(), Priority::None)?;
+    // executor.task(send_email(), Priority::None)?;
+
+    // executor.task(task_with_parameters(String::from("John"), 24, String::from("John@gmail.com")), Priority::None)?;
 
     // // This Function should be called u have finished sending tasks.
     // // !IMPORTANT => if you forget to await_completion() your program can finish before threads finalize.
-    // executor.await_completion();
+    // executor.await_completion()?;
     
     // // Metrics should be called only after .await_completion() func when the program have finalized all thread operations.
     // executor.metrics();
 
-    // executor.force_shutdown(); // Shutdowns all threads from running allowing you to create new executor.
+    // executor.force_shutdown()?; // Shutdowns all threads from running allowing you to create new executor.
     // drop(executor); // Drop executor instance if you are working in the same scope otherwise rust ownership rules will do it for you
     
-    // let mut executor = Proxy::new();
+    // let mut executor = Proxy::new()?;
 
-    // executor.task(send_email(), Priority::None);
+    // executor.task(send_email(), Priority::None)?;
 
-    // executor.await_completion();
+    // executor.await_completion()?;
 
     // executor.metrics();
 }
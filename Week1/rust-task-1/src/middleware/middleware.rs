@@ -0,0 +1,37 @@
+use std::time::Duration;
+
+use crate::core::types::FallibleTask;
+use crate::error_handler::error_handler::ExecutorError;
+
+use super::task_context::TaskContext;
+
+/*
+    Cross-cutting behaviour around every task of an executor (auth context, logging, DB transactions, alerting).
+    Every hook has an empty default => implement only what you need.
+    - before_submit: once per task when the executor accepts it (not for retry attempts), Err rejects the task
+    - on_start: a worker took the task (every attempt)
+    - on_success / on_failure / on_timeout: final outcome, a failed attempt that is retried calls on_retry instead
+    - wrap: the future of every attempt, e.g. to set a task-local context or to measure how long it runs
+    Hooks run on the worker threads, they should not block for long (the worker runs other futures meanwhile).
+*/
+pub trait Middleware: Send + Sync {
+    fn before_submit(&self, _task: &TaskContext) -> Result<(), ExecutorError> {
+        Ok(())
+    }
+
+    fn on_start(&self, _task: &TaskContext) {}
+
+    fn on_success(&self, _task: &TaskContext) {}
+
+    // Err message of the task or the panic message
+    fn on_failure(&self, _task: &TaskContext, _error: &str) {}
+
+    fn on_timeout(&self, _task: &TaskContext) {}
+
+    // `task.attempt` is the failed attempt, the next one starts after `backoff`
+    fn on_retry(&self, _task: &TaskContext, _error: &str, _backoff: Duration) {}
+
+    fn wrap(&self, _task: &TaskContext, future: FallibleTask) -> FallibleTask {
+        future
+    }
+}
@@ -0,0 +1,210 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::RwLock;
+use std::time::Duration;
+
+use crate::core::types::FallibleTask;
+use crate::error_handler::error_handler::{fail_gracefully, panic_message, ExecutorError};
+
+use super::{task_context::TaskContext, types::MiddlewareArc};
+
+/*
+    Middleware of one executor in the order they were added, like layers of an onion:
+    - before_submit, on_start => first added runs first, the first Err stops the chain
+    - wrap => first added is the outermost future (it is polled first)
+    - on_success, on_failure, on_timeout, on_retry => first added runs last
+    Hooks run on the worker threads => a panicking hook is caught and reported, the other middleware and the worker go on.
+*/
+#[derive(Default)]
+pub struct MiddlewareChain {
+    middlewares: RwLock<Vec<MiddlewareArc>>,
+}
+
+impl MiddlewareChain {
+    pub fn new() -> MiddlewareChain {
+        MiddlewareChain { middlewares: RwLock::new(Vec::new()) }
+    }
+
+    pub fn add(&self, middleware: MiddlewareArc) {
+        self.middlewares.write().unwrap().push(middleware);
+    }
+
+    pub fn len(&self) -> usize {
+        self.middlewares.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Panic in a hook rejects the task like an Err
+    pub fn before_submit(&self, task: &TaskContext) -> Result<(), ExecutorError> {
+        self.get_all().iter().try_for_each(|middleware| {
+            MiddlewareChain::catch_hook(|| middleware.before_submit(task)).unwrap_or_else(|message| Err(ExecutorError::MiddlewarePanicked(message)))
+        })
+    }
+
+    pub fn on_start(&self, task: &TaskContext) {
+        self.get_all().iter().for_each(|middleware| MiddlewareChain::report_hook("on_start", || middleware.on_start(task)));
+    }
+
+    pub fn on_success(&self, task: &TaskContext) {
+        self.get_all().iter().rev().for_each(|middleware| MiddlewareChain::report_hook("on_success", || middleware.on_success(task)));
+    }
+
+    pub fn on_failure(&self, task: &TaskContext, error: &str) {
+        self.get_all().iter().rev().for_each(|middleware| MiddlewareChain::report_hook("on_failure", || middleware.on_failure(task, error)));
+    }
+
+    pub fn on_timeout(&self, task: &TaskContext) {
+        self.get_all().iter().rev().for_each(|middleware| MiddlewareChain::report_hook("on_timeout", || middleware.on_timeout(task)));
+    }
+
+    pub fn on_retry(&self, task: &TaskContext, error: &str, backoff: Duration) {
+        self.get_all().iter().rev().for_each(|middleware| MiddlewareChain::report_hook("on_retry", || middleware.on_retry(task, error, backoff)));
+    }
+
+    // Last added wraps first => the first added ends up outside
+    // A panicking wrap() already took the future => the attempt fails with the panic message instead of running
+    pub fn wrap(&self, task: &TaskContext, future: FallibleTask) -> FallibleTask {
+        self.get_all().iter().rev().fold(future, |future, middleware| {
+            MiddlewareChain::catch_hook(|| middleware.wrap(task, future)).unwrap_or_else(|message| {
+                fail_gracefully(ExecutorError::MiddlewarePanicked(message.clone()), "wrap");
+                Box::pin(async move { Err(format!("Middleware panicked: {}", message)) })
+            })
+        })
+    }
+
+    // Hooks run on a copy => a hook can add middleware without a deadlock
+    pub fn get_all(&self) -> Vec<MiddlewareArc> {
+        self.middlewares.read().unwrap().clone()
+    }
+
+    fn catch_hook<R>(hook: impl FnOnce() -> R) -> Result<R, String> {
+        panic::catch_unwind(AssertUnwindSafe(hook)).map_err(panic_message)
+    }
+
+    fn report_hook(name: &str, hook: impl FnOnce()) {
+        if let Err(message) = MiddlewareChain::catch_hook(hook) {
+            fail_gracefully(ExecutorError::MiddlewarePanicked(message), name);
+        }
+    }
+}
+
+impl std::fmt::Debug for MiddlewareChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MiddlewareChain")
+            .field("middlewares", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lifecycle::task_id::TaskId, middleware::middleware::Middleware, priority::priority::Priority};
+    use futures::executor::block_on;
+    use std::sync::{Arc, Mutex};
+
+    struct Recorder {
+        name: &'static str,
+        calls: Arc<Mutex<Vec<String>>>,
+        reject: bool,
+    }
+
+    impl Middleware for Recorder {
+        fn before_submit(&self, _task: &TaskContext) -> Result<(), ExecutorError> {
+            self.calls.lock().unwrap().push(format!("{} before_submit", self.name));
+            match self.reject {
+                true => Err(ExecutorError::TaskRejected(String::from("no auth context"))),
+                false => Ok(()),
+            }
+        }
+
+        fn on_start(&self, _task: &TaskContext) {
+            self.calls.lock().unwrap().push(format!("{} on_start", self.name));
+        }
+
+        fn on_success(&self, _task: &TaskContext) {
+            self.calls.lock().unwrap().push(format!("{} on_success", self.name));
+        }
+
+        fn wrap(&self, _task: &TaskContext, future: FallibleTask) -> FallibleTask {
+            let calls = self.calls.clone();
+            let name = self.name;
+            Box::pin(async move {
+                calls.lock().unwrap().push(format!("{} enter", name));
+                let result = future.await;
+                calls.lock().unwrap().push(format!("{} exit", name));
+                result
+            })
+        }
+    }
+
+    fn recorder(name: &'static str, calls: &Arc<Mutex<Vec<String>>>, reject: bool) -> MiddlewareArc {
+        Arc::new(Recorder { name, calls: calls.clone(), reject })
+    }
+
+    #[test]
+    fn test_middleware_should_run_like_an_onion() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let chain = MiddlewareChain::new();
+        chain.add(recorder("auth", &calls, false));
+        chain.add(recorder("log", &calls, false));
+        let task = TaskContext::new(TaskId::next(), Some(String::from("send_email")), Priority::High, 1);
+
+        chain.before_submit(&task).unwrap();
+        chain.on_start(&task);
+        let task_calls = calls.clone();
+        let future = chain.wrap(&task, Box::pin(async move {
+            task_calls.lock().unwrap().push(String::from("task"));
+            Ok(())
+        }));
+        assert_eq!(block_on(future), Ok(()));
+        chain.on_success(&task);
+
+        let expected = [
+            "auth before_submit", "log before_submit", "auth on_start", "log on_start",
+            "auth enter", "log enter", "task", "log exit", "auth exit", "log on_success", "auth on_success",
+        ];
+        assert_eq!(*calls.lock().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_first_rejection_should_stop_before_submit() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let chain = MiddlewareChain::new();
+        chain.add(recorder("auth", &calls, true));
+        chain.add(recorder("log", &calls, false));
+
+        let rejected = chain.before_submit(&TaskContext::new(TaskId::next(), None, Priority::None, 1));
+        assert!(matches!(rejected, Err(ExecutorError::TaskRejected(_))));
+        assert_eq!(*calls.lock().unwrap(), ["auth before_submit"]);
+    }
+
+    #[test]
+    fn test_panicking_hook_should_be_reported_and_the_chain_should_go_on() {
+        struct Exploding;
+
+        impl Middleware for Exploding {
+            fn before_submit(&self, _task: &TaskContext) -> Result<(), ExecutorError> {
+                panic!("no auth service");
+            }
+
+            fn on_start(&self, _task: &TaskContext) {
+                panic!("no logger");
+            }
+        }
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let chain = MiddlewareChain::new();
+        chain.add(Arc::new(Exploding));
+        chain.add(recorder("log", &calls, false));
+        let task = TaskContext::new(TaskId::next(), None, Priority::None, 1);
+
+        let rejected = chain.before_submit(&task);
+        assert!(matches!(rejected, Err(ExecutorError::MiddlewarePanicked(message)) if message == "no auth service"));
+
+        chain.on_start(&task);
+        assert_eq!(*calls.lock().unwrap(), ["log on_start"]);
+    }
+}
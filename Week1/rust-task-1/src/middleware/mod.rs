@@ -0,0 +1,4 @@
+pub mod middleware;
+pub mod middleware_chain;
+pub mod task_context;
+pub mod types;
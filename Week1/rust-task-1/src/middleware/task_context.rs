@@ -0,0 +1,23 @@
+use crate::lifecycle::task_id::TaskId;
+use crate::priority::priority::Priority;
+
+// What the middleware knows about the task, the same for every hook of one attempt
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskContext {
+    pub task_id: TaskId,
+    pub name: Option<String>, // Name of task_named(), submit_registered(), #[task] ..., None for anonymous futures
+    pub priority: Priority,
+    pub attempt: u32, // 1 for the first run, retries of RetryingTask count up
+    pub queue: Option<String>, // Queue that accepted the task, None before it reached one
+}
+
+impl TaskContext {
+    pub fn new(task_id: TaskId, name: Option<String>, priority: Priority, attempt: u32) -> TaskContext {
+        TaskContext { task_id, name, priority, attempt, queue: None }
+    }
+
+    pub fn with_queue(mut self, queue: &str) -> TaskContext {
+        self.queue = Some(queue.to_string());
+        self
+    }
+}
@@ -0,0 +1,8 @@
+use std::sync::Arc;
+
+use super::{middleware::Middleware, middleware_chain::MiddlewareChain};
+
+pub type MiddlewareArc = Arc<dyn Middleware>;
+
+// Shared by the executor (before_submit), its workers and the retries of its tasks
+pub type MiddlewareChainArc = Arc<MiddlewareChain>;
@@ -1,15 +1,27 @@
 
 
+use super::scaling_event::ScalingEvent;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct MetricsReport {
     tasks_count: u32,
     tasks_failed: u32,
+    tasks_panicked: u32, // Panicked tasks are also counted in tasks_failed
+    tasks_retried: u32, // Every retry attempt, attempts are also counted in tasks_count
+    tasks_retries_exhausted: u32, // Tasks that failed on the last allowed attempt
     total_execution_time: u32,
+    pool_size: usize, // Workers running now
+    peak_pool_size: usize,
+    scale_ups: u32,
+    scale_downs: u32,
 }
 
 impl MetricsReport {
     pub fn new() -> MetricsReport{
-        MetricsReport { tasks_count: 0, tasks_failed: 0, total_execution_time: 0 }
+        MetricsReport {
+            tasks_count: 0, tasks_failed: 0, tasks_panicked: 0, tasks_retried: 0, tasks_retries_exhausted: 0, total_execution_time: 0,
+            pool_size: 0, peak_pool_size: 0, scale_ups: 0, scale_downs: 0,
+        }
     }
 
     // Using Getters and Setters without exposing MetricsReport fields
@@ -22,10 +34,37 @@ impl MetricsReport {
         self.tasks_failed += 1;
     }
 
+    pub fn increment_tasks_panicked(&mut self) {
+        self.tasks_panicked += 1;
+    }
+
+    pub fn increment_tasks_retried(&mut self) {
+        self.tasks_retried += 1;
+    }
+
+    pub fn increment_tasks_retries_exhausted(&mut self) {
+        self.tasks_retries_exhausted += 1;
+    }
+
     pub fn increment_total_execution_time(&mut self, execution_time: u32) {
         self.total_execution_time += execution_time;
     }
 
+    // Workers started with the executor, not counted as scaling
+    pub fn set_pool_size(&mut self, pool_size: usize) {
+        self.pool_size = pool_size;
+        self.peak_pool_size = self.peak_pool_size.max(pool_size);
+    }
+
+    // Only the counters, the events themselves are kept in the ScalingHistory of the executor
+    pub fn record_scaling_event(&mut self, event: ScalingEvent) {
+        match event.is_scale_up() {
+            true => self.scale_ups += 1,
+            false => self.scale_downs += 1,
+        }
+        self.set_pool_size(event.pool_size);
+    }
+
     pub fn get_average_execution_time(&self) -> u32 {
         // Beware of division by zero problem
         if self.total_execution_time > 0 {
@@ -37,7 +76,7 @@ impl MetricsReport {
     }
 
     pub fn metrics_info(&self) {
-        let report_message = format!("\n- Currently the program has runned {} tasks.\n- Which of {} tasks has failed.\n- With average execution time of {}s \n", self.tasks_count, self.tasks_failed, self.get_average_execution_time());
+        let report_message = format!("\n- Currently the program has runned {} tasks.\n- Which of {} tasks has failed ({} panicked).\n- With average execution time of {}s \n- {} attempts were retries, {} tasks failed after the last attempt.\n- Pool has {} workers (peak {}), scaled up {} times and down {} times.\n", self.tasks_count, self.tasks_failed, self.tasks_panicked, self.get_average_execution_time(), self.tasks_retried, self.tasks_retries_exhausted, self.pool_size, self.peak_pool_size, self.scale_ups, self.scale_downs);
         println!("{}", report_message);
     }
 
@@ -49,11 +88,42 @@ impl MetricsReport {
         self.tasks_failed
     }
 
+    pub fn get_tasks_panicked(&self) -> u32 {
+        self.tasks_panicked
+    }
+
+    pub fn get_tasks_retried(&self) -> u32 {
+        self.tasks_retried
+    }
+
+    pub fn get_tasks_retries_exhausted(&self) -> u32 {
+        self.tasks_retries_exhausted
+    }
+
+    pub fn get_pool_size(&self) -> usize {
+        self.pool_size
+    }
+
+    pub fn get_peak_pool_size(&self) -> usize {
+        self.peak_pool_size
+    }
+
+    pub fn get_scale_ups(&self) -> u32 {
+        self.scale_ups
+    }
+
+    pub fn get_scale_downs(&self) -> u32 {
+        self.scale_downs
+    }
+
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use super::*;
+    use crate::performance_monitoring::scaling_event::ScalingReason;
 
     #[test]
     fn test_initial_values() {
@@ -77,6 +147,37 @@ mod tests {
         assert_eq!(report.get_tasks_failed(), 1);
     }
 
+    #[test]
+    fn test_increment_tasks_panicked() {
+        let mut report = MetricsReport::new();
+        report.increment_tasks_panicked();
+        assert_eq!(report.get_tasks_panicked(), 1);
+    }
+
+    #[test]
+    fn test_increment_retry_counters() {
+        let mut report = MetricsReport::new();
+        report.increment_tasks_retried();
+        report.increment_tasks_retried();
+        report.increment_tasks_retries_exhausted();
+
+        assert_eq!(report.get_tasks_retried(), 2);
+        assert_eq!(report.get_tasks_retries_exhausted(), 1);
+    }
+
+    #[test]
+    fn test_record_scaling_event_should_track_pool_size() {
+        let mut report = MetricsReport::new();
+        report.set_pool_size(1);
+
+        report.record_scaling_event(ScalingEvent::new(2, ScalingReason::QueueDepth(20)));
+        report.record_scaling_event(ScalingEvent::new(1, ScalingReason::Idle(Duration::from_secs(30))));
+
+        assert_eq!(report.get_pool_size(), 1);
+        assert_eq!(report.get_peak_pool_size(), 2);
+        assert_eq!((report.get_scale_ups(), report.get_scale_downs()), (1, 1));
+    }
+
     #[test]
     #[should_panic]
     fn test_increment_total_execution_time_while_task_count_is_zero_should_fail() {
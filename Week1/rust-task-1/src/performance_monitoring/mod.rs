@@ -1 +1,3 @@
-pub mod metrics;
\ No newline at end of file
+pub mod metrics;
+pub mod scaling_event;
+pub mod scaling_history;
\ No newline at end of file
@@ -0,0 +1,27 @@
+use std::time::{Duration, Instant};
+
+// Why the pool size changed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScalingReason {
+    QueueDepth(usize), // Jobs waiting in the worker channel when the worker was started
+    WaitTime(Duration), // Longest time a job waited for a worker
+    Idle(Duration), // Worker had no task for this long (worker_keep_alive)
+}
+
+// One change of the pool size, kept in MetricsReport so the pool size can be followed over time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScalingEvent {
+    pub at: Instant,
+    pub pool_size: usize, // Workers after the change
+    pub reason: ScalingReason,
+}
+
+impl ScalingEvent {
+    pub fn new(pool_size: usize, reason: ScalingReason) -> ScalingEvent {
+        ScalingEvent { at: Instant::now(), pool_size, reason }
+    }
+
+    pub fn is_scale_up(&self) -> bool {
+        !matches!(self.reason, ScalingReason::Idle(_))
+    }
+}
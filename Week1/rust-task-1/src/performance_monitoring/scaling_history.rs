@@ -0,0 +1,50 @@
+use std::collections::VecDeque;
+
+use super::scaling_event::ScalingEvent;
+
+// Last MAX_SCALING_EVENTS changes of the pool size, kept out of MetricsReport so the report stays Copy
+#[derive(Debug, Default)]
+pub struct ScalingHistory {
+    events: VecDeque<ScalingEvent>,
+}
+
+impl ScalingHistory {
+    const MAX_SCALING_EVENTS: usize = 256; // Older events are removed, so a long running executor does not grow the history
+
+    pub fn new() -> ScalingHistory {
+        ScalingHistory { events: VecDeque::new() }
+    }
+
+    pub fn record(&mut self, event: ScalingEvent) {
+        if self.events.len() == ScalingHistory::MAX_SCALING_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    // Oldest first
+    pub fn get_events(&self) -> Vec<ScalingEvent> {
+        self.events.iter().copied().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::performance_monitoring::scaling_event::ScalingReason;
+
+    #[test]
+    fn test_record_should_keep_only_the_last_events() {
+        let mut history = ScalingHistory::new();
+
+        for pool_size in 0..ScalingHistory::MAX_SCALING_EVENTS + 2 {
+            history.record(ScalingEvent::new(pool_size, ScalingReason::Idle(Duration::from_secs(30))));
+        }
+
+        let sizes: Vec<usize> = history.get_events().iter().map(|event| event.pool_size).collect();
+        assert_eq!(sizes.len(), ScalingHistory::MAX_SCALING_EVENTS);
+        assert_eq!((sizes[0], *sizes.last().unwrap()), (2, ScalingHistory::MAX_SCALING_EVENTS + 1));
+    }
+}
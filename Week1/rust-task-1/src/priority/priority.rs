@@ -1,6 +1,6 @@
+use serde::{Deserialize, Serialize};
 
-
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Priority {
     None,
     Low,
@@ -9,7 +9,7 @@ pub enum Priority {
 }
 
 impl Priority {
-    pub fn to_value(&self) -> u8 {
+    pub const fn to_value(&self) -> u8 {
         match self {
             Priority::None => 0,
             Priority::Low => 1,
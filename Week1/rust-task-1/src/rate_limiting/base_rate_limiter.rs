@@ -1,6 +1,6 @@
-use crate::{core::executor::AsyncExecutor, core::types::Task};
+use crate::{core::executor::AsyncExecutor, core::job::Job, core::submit_mode::SubmitMode, error_handler::error_handler::ExecutorError};
 
 
 pub trait BaseRateLimiter {
-    fn delay_task_after_limit_pass(&self, executor: AsyncExecutor, fut: Task); // Sends the task after limits are passed for this task
+    fn delay_task_after_limit_pass(&self, executor: AsyncExecutor, job: Job, mode: SubmitMode) -> Result<(), ExecutorError>; // Sends the task after limits are passed for this task
 }
@@ -1,4 +1,5 @@
 pub mod slots;
 pub mod base_rate_limiter;
 pub mod slot_rate_limiter;
-pub mod types;
\ No newline at end of file
+pub mod types;
+pub mod task_rate_limit;
@@ -1,31 +1,38 @@
 
-use crate::{core::executor::AsyncExecutor, priority::priority::Priority, core::types::Task};
-
-use super::{base_rate_limiter::BaseRateLimiter, slots::Slots, types::SlotsVector};
-
+use crate::{core::executor::AsyncExecutor, priority::priority::Priority, core::job::Job, core::submit_mode::SubmitMode};
+use crate::error_handler::error_handler::ExecutorError;
+use crate::lifecycle::task_state::TaskState;
+use std::{sync::{Arc, Mutex}, thread, time::{Duration, Instant}};
+
+use super::{base_rate_limiter::BaseRateLimiter, slots::Slots, types::SharedSlots};
+
+/*
+    One SlotRateLimiter per executor (queue), clones share the slots => the limit is for all submissions of the queue.
+    The slots are locked only to check and take a slot, the job waits for a free slot and is sent to the workers without the lock.
+*/
+#[derive(Debug, Clone)]
 pub struct SlotRateLimiter {
     rate_limit_per_sec: usize,
-    slots: SlotsVector,
+    slots: SharedSlots,
 }
 
 impl SlotRateLimiter {
+    const SLOT_LIFETIME: Duration = Duration::from_secs(1);
+
     pub fn new(rate_limit_per_sec: usize) -> SlotRateLimiter {
         SlotRateLimiter {
             rate_limit_per_sec,
-            slots: Vec::new()
+            slots: Arc::new(Mutex::new(Vec::new()))
         }
     }
 
+    #[cfg(test)] // take_slot() checks and adds under one lock, tests fill the slots directly
     fn add_task_to_slot(&mut self, current_slot: Slots) {
-        self.slots.push(current_slot)
-    }
-
-    fn remove_task_from_slot(&mut self, index_of_slot_to_remove: usize) {
-        self.slots.remove(index_of_slot_to_remove);
+        self.slots.lock().unwrap().push(current_slot)
     }
 
     fn free_slot_space(&mut self) {
-        self.slots.retain(|slot| slot.get_current_timestamp().elapsed().as_secs() < 1); // Keep slots that have less seconds that 1: Similar like .filter() type of methods
+        self.slots.lock().unwrap().retain(|slot| slot.get_current_timestamp().elapsed().as_secs() < 1); // Keep slots that have less seconds that 1: Similar like .filter() type of methods
         // Same as the down bellow, 
 
         // for (idx, slot) in self.slots.iter().enumerate() {
@@ -37,53 +44,86 @@ impl SlotRateLimiter {
         // }
     }
 
-    pub fn slot_limited(&mut self, priority: Priority, executor: AsyncExecutor, fut: Task) {
+    // Every submission path (Proxy, retries, canvas) sends jobs to the executor through here
+    pub fn delay_rate_limited(executor: &AsyncExecutor, priority: Priority, job: Job) -> Result<(), ExecutorError> {
+        SlotRateLimiter::submit_rate_limited(executor, priority, job, SubmitMode::Blocking)
+    }
+
+    // Same as delay_rate_limited() but returns the error when the job is not accepted by the worker queue
+    pub fn submit_rate_limited(executor: &AsyncExecutor, priority: Priority, job: Job, mode: SubmitMode) -> Result<(), ExecutorError> {
+        let job = executor.admit(job.with_priority(priority.clone()))?;
+        let mut slot_rate_limiter = executor.rate_limiter.clone(); // Shares the slots with every clone of the executor
+        slot_rate_limiter.slot_limited(priority, executor.clone(), job, mode)
+    }
+
+    pub fn slot_limited(&mut self, priority: Priority, executor: AsyncExecutor, job: Job, mode: SubmitMode) -> Result<(), ExecutorError> {
         let mut current_slot = Slots::new(&priority);
-        let min_idx_value_to_remove_from_slots = Slots::check_priority(&mut current_slot, &priority, &mut self.slots);
-
-        match min_idx_value_to_remove_from_slots {
-            // If i have returned idx this means i should remove the lowest priority from the slots
-            Some(idx) => {
-                if self.slots.len() >= self.rate_limit_per_sec {
-                    // Remove the task from the slot only if slow is already full
-                    self.remove_task_from_slot(idx);
+        self.free_slot_space();
+
+        {
+            let mut slots = self.slots.lock().unwrap();
+            let min_idx_value_to_remove_from_slots = Slots::check_priority(&mut current_slot, &priority, &mut slots);
+
+            match min_idx_value_to_remove_from_slots {
+                // If i have returned idx this means i should remove the lowest priority from the slots
+                Some(idx) => {
+                    if slots.len() >= self.rate_limit_per_sec {
+                        // Remove the task from the slot only if slow is already full
+                        slots.remove(idx);
+                    }
                 }
+                None => {}
             }
-            None => {}
         }
 
-        // Here before the loop i remove_task_from_slot() if i have priority in self.slots that is lower than my current received one
-        // So i have free space in my slots for a new one, otherwise i will not have and the loop is going to wait till slot is freed.
-        loop {  
-            // println!("SLOTS LEN: {}", self.slots.len());
-            if self.slots.len() < self.rate_limit_per_sec {
-                self.add_task_to_slot(current_slot);
-
-                // Here i can send the task
-                self.delay_task_after_limit_pass(executor, fut);
-                break;
-            } else {
-                // Rate Limit reached wait till one second pass and free slot spaces
+        // Here before the loop i removed the slot if i have priority in self.slots that is lower than my current received one
+        // So i have free space in my slots for a new one, otherwise i will wait till a slot is freed.
+        let mut rate_limited_since = None;
+        while let Some(wait) = self.take_slot(&priority) {
+            if rate_limited_since.is_none() {
+                rate_limited_since = Some(Instant::now());
+                executor.tracker.transition(job.id, TaskState::RateLimited); // Waits for a free slot
+                job.span.rate_limited();
                 // fail_gracefully(crate::error_handler::ExecutorError::RateLimitExceeded, "Rate-Limit reached, your tasks are waiting till space for execution is freed.");
-                self.free_slot_space();
-            }              
+            }
+            thread::sleep(wait); // Without the lock, so the other submissions can check the slots
+        }
+
+        if let Some(since) = rate_limited_since {
+            job.span.rate_limit_passed(since.elapsed());
+        }
+
+        // Here i can send the task, workers take it by its priority
+        self.delay_task_after_limit_pass(executor, job.with_priority(priority), mode)
+    }
+
+    // Takes a free slot => None, otherwise returns how long till the oldest slot is freed
+    fn take_slot(&mut self, priority: &Priority) -> Option<Duration> {
+        self.free_slot_space();
+        let mut slots = self.slots.lock().unwrap();
+
+        if slots.len() < self.rate_limit_per_sec {
+            slots.push(Slots::new(priority)); // Slot time starts now, not when the job started waiting
+            return None;
         }
+
+        let oldest_slot = slots.iter().map(|slot| slot.get_current_timestamp()).min()?;
+        Some((oldest_slot + SlotRateLimiter::SLOT_LIFETIME).saturating_duration_since(Instant::now()))
     }
 }
 
 impl BaseRateLimiter for SlotRateLimiter {
-    fn delay_task_after_limit_pass(&self, executor: AsyncExecutor, fut: Task) {
-        let task = Box::pin(fut); // Executioner expects known memory size at compilation, since i dont know it i store to the heap
-        executor.delay(task); // Send to executioner 
+    fn delay_task_after_limit_pass(&self, executor: AsyncExecutor, job: Job, mode: SubmitMode) -> Result<(), ExecutorError> {
+        executor.submit_job(job, mode) // Send to executioner 
     }
 }
 
 
 #[cfg(test)]
 mod test {
-    use std::{thread::sleep, time::Duration};
+    use std::{sync::{Arc, Mutex}, thread::sleep, time::{Duration, Instant}};
 
-    use crate::priority::priority::Priority;
+    use crate::{executor_config::ExecutorConfigBuilder, lifecycle::task_id::TaskId, priority::priority::Priority};
 
     use super::*;
 
@@ -94,7 +134,7 @@ mod test {
         // let executor = AsyncExecutor::new();
 
         assert_eq!(rate_limiter.rate_limit_per_sec, default_rate_limit);
-        assert_eq!(rate_limiter.slots, vec![]);
+        assert_eq!(*rate_limiter.slots.lock().unwrap(), vec![]);
         
         assert!(true)
     } 
@@ -104,31 +144,31 @@ mod test {
         let mut rate_limiter  = SlotRateLimiter::new(5);
         let task_slot = Slots::new(&Priority::None);
         
-        assert_eq!(rate_limiter.slots.len(), 0);
+        assert_eq!(rate_limiter.slots.lock().unwrap().len(), 0);
 
         rate_limiter.add_task_to_slot(task_slot.clone());
-        assert_eq!(rate_limiter.slots.len(), 1);
+        assert_eq!(rate_limiter.slots.lock().unwrap().len(), 1);
 
         rate_limiter.add_task_to_slot(task_slot.clone());
-        assert_eq!(rate_limiter.slots.len(), 2);
+        assert_eq!(rate_limiter.slots.lock().unwrap().len(), 2);
     } 
 
     #[test]
     fn test_free_slot_space_should_be_valid() {
         let mut rate_limiter = SlotRateLimiter::new(5);
         
-        assert_eq!(rate_limiter.slots.len(), 0);
+        assert_eq!(rate_limiter.slots.lock().unwrap().len(), 0);
 
         let task_slot = Slots::new(&Priority::None);
 
         rate_limiter.add_task_to_slot(task_slot.clone());
-        assert_eq!(rate_limiter.slots.len(), 1);
+        assert_eq!(rate_limiter.slots.lock().unwrap().len(), 1);
 
         rate_limiter.add_task_to_slot(task_slot.clone());
-        assert_eq!(rate_limiter.slots.len(), 2);
+        assert_eq!(rate_limiter.slots.lock().unwrap().len(), 2);
 
         rate_limiter.add_task_to_slot(task_slot.clone());
-        assert_eq!(rate_limiter.slots.len(), 3);
+        assert_eq!(rate_limiter.slots.lock().unwrap().len(), 3);
 
         rate_limiter.add_task_to_slot(task_slot.clone());
         rate_limiter.add_task_to_slot(task_slot.clone());
@@ -137,10 +177,65 @@ mod test {
 
         sleep(Duration::from_secs(1));
         rate_limiter.free_slot_space();
-        assert_eq!(rate_limiter.slots.len(), 0);
+        assert_eq!(rate_limiter.slots.lock().unwrap().len(), 0);
 
         rate_limiter.add_task_to_slot(task_slot.clone());
-        assert_eq!(rate_limiter.slots.len(), 1);
+        assert_eq!(rate_limiter.slots.lock().unwrap().len(), 1);
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_submissions_over_the_limit_should_wait_for_a_free_slot() {
+        let config = ExecutorConfigBuilder::new().with_rate_limit_per_sec(2).with_worker_count(3).build().unwrap();
+        let executor = AsyncExecutor::with_config(config).unwrap();
+        let first_clone = executor.lock().unwrap().clone();
+        let second_clone = executor.lock().unwrap().clone(); // Like another queue handle or the scheduler, same slots
+
+        let starts = Arc::new(Mutex::new(vec![]));
+        let start_job = |starts: &Arc<Mutex<Vec<Instant>>>| {
+            let starts = starts.clone();
+            Job::new(Box::pin(async move { starts.lock().unwrap().push(Instant::now()) }))
+        };
+
+        let submitted = Instant::now();
+        SlotRateLimiter::delay_rate_limited(&first_clone, Priority::None, start_job(&starts)).unwrap();
+        SlotRateLimiter::delay_rate_limited(&first_clone, Priority::None, start_job(&starts)).unwrap();
+        assert!(submitted.elapsed() < Duration::from_millis(500));
+
+        SlotRateLimiter::delay_rate_limited(&second_clone, Priority::None, start_job(&starts)).unwrap(); // Third one in the same second
+        assert!(submitted.elapsed() >= Duration::from_millis(900));
+
+        drop((first_clone, second_clone));
+        executor.lock().unwrap().wait_all().unwrap();
+
+        let mut starts = starts.lock().unwrap().clone();
+        starts.sort();
+        assert_eq!(starts.len(), 3);
+        assert!(starts[2] - starts[0] >= Duration::from_millis(900));
+    }
+
+    #[test]
+    fn test_task_waiting_for_a_slot_should_be_rate_limited() {
+        let config = ExecutorConfigBuilder::new().with_rate_limit_per_sec(1).build().unwrap();
+        let executor = AsyncExecutor::with_config(config).unwrap();
+        let executor_clone = executor.lock().unwrap().clone();
+
+        SlotRateLimiter::delay_rate_limited(&executor_clone, Priority::None, Job::new(Box::pin(async {}))).unwrap();
+
+        let throttled_id = TaskId::next();
+        let submitter_clone = executor_clone.clone();
+        let submitter = std::thread::spawn(move || {
+            SlotRateLimiter::delay_rate_limited(&submitter_clone, Priority::None, Job::new(Box::pin(async {})).with_id(throttled_id))
+        });
+
+        let deadline = Instant::now() + Duration::from_millis(500);
+        while executor_clone.get_task_state(throttled_id) != Some(TaskState::RateLimited) && Instant::now() < deadline {
+            sleep(Duration::from_millis(5));
+        }
+        assert_eq!(executor_clone.get_task_state(throttled_id), Some(TaskState::RateLimited));
+
+        submitter.join().unwrap().unwrap();
+        drop(executor_clone);
+        executor.lock().unwrap().wait_all().unwrap();
+        assert_eq!(executor.lock().unwrap().get_task_state(throttled_id), Some(TaskState::Succeeded));
+    }
+}
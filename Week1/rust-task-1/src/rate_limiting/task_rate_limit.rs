@@ -0,0 +1,180 @@
+use std::collections::VecDeque;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::error_handler::error_handler::ExecutorError;
+
+/*
+    Rate limit of one task type => "10/m" = at most 10 starts of the task in any minute.
+    Unlike SlotRateLimiter (per queue, per second) the task is not rejected, reserve() returns when it may start
+    and the Proxy keeps it in the scheduler till then => the waiting task does not take a worker slot.
+    Every attempt of a retried task reserves its own start, a start of an attempt that was not submitted is released.
+    Clones share the reserved starts.
+*/
+#[derive(Debug, Clone)]
+pub struct TaskRateLimit {
+    count: usize,
+    period: Duration,
+    starts: Arc<Mutex<VecDeque<Instant>>>, // Reserved starts that can still be in a window, sorted
+}
+
+impl TaskRateLimit {
+    pub fn new(count: usize, period: Duration) -> TaskRateLimit {
+        TaskRateLimit {
+            count: count.max(1),
+            period,
+            starts: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+
+    // Start of the next task, now when the limit is not reached
+    pub fn reserve(&self) -> Instant {
+        self.reserve_after(Instant::now())
+    }
+
+    /*
+        First start at or after `earliest` (retry backoff, eta) that keeps the limit.
+        Starts can be reserved out of order (a retry waits for its backoff meanwhile new tasks start) => the start is
+        checked against the reserved starts on both sides, candidates are `earliest` and "a reserved start + period".
+    */
+    pub fn reserve_after(&self, earliest: Instant) -> Instant {
+        let now = Instant::now();
+        let earliest = earliest.max(now);
+        let mut starts = self.starts.lock().unwrap();
+        while starts.front().is_some_and(|start| *start + self.period <= now) {
+            starts.pop_front(); // Out of every window that can still get a start
+        }
+
+        let fits = |start: Instant| {
+            let around = starts.iter().filter(|reserved| **reserved + self.period > start && **reserved < start + self.period);
+            around.count() < self.count
+        };
+        let start = std::iter::once(earliest)
+            .chain(starts.iter().map(|reserved| *reserved + self.period).filter(|candidate| *candidate > earliest))
+            .find(|candidate| fits(*candidate))
+            .unwrap_or_else(|| *starts.back().unwrap() + self.period); // Never reached, nothing is reserved after the last start
+
+        let index = starts.partition_point(|reserved| *reserved <= start);
+        starts.insert(index, start);
+        start
+    }
+
+    // Start of an attempt that was not submitted => the slot is free again
+    pub fn release(&self, start: Instant) {
+        let mut starts = self.starts.lock().unwrap();
+        if let Some(index) = starts.iter().position(|reserved| *reserved == start) {
+            starts.remove(index);
+        }
+    }
+
+    pub fn get_count(&self) -> usize {
+        self.count
+    }
+
+    pub fn get_period(&self) -> Duration {
+        self.period
+    }
+
+    /*
+        Count and period of "10/m", None when it is not like "10/s", "10/m" or "10/h" or the count is 0.
+        const => #[task(rate_limit = "10/m")] checks the literal in a const, a typo is a compile error without a second parser in the macro
+    */
+    pub const fn parse_rate_limit(rate_limit: &str) -> Option<(usize, Duration)> {
+        let bytes = rate_limit.trim_ascii().as_bytes();
+        let mut index = 0;
+        let mut count: usize = 0;
+
+        while index < bytes.len() && bytes[index].is_ascii_digit() {
+            count = match count.checked_mul(10) {
+                Some(count) => match count.checked_add((bytes[index] - b'0') as usize) {
+                    Some(count) => count,
+                    None => return None,
+                },
+                None => return None,
+            };
+            index += 1;
+        }
+        if index == 0 || count == 0 {
+            return None;
+        }
+
+        // Rest must be "/unit", spaces around the slash are allowed
+        let rest = match bytes.split_at(index).1.trim_ascii().split_first() {
+            Some((b'/', rest)) => rest.trim_ascii(),
+            _ => return None,
+        };
+        let period = match rest {
+            [b's'] => Duration::from_secs(1),
+            [b'm'] => Duration::from_secs(60),
+            [b'h'] => Duration::from_secs(60 * 60),
+            _ => return None,
+        };
+        Some((count, period))
+    }
+}
+
+// "10/s", "10/m", "10/h" like in Celery
+impl FromStr for TaskRateLimit {
+    type Err = ExecutorError;
+
+    fn from_str(rate_limit: &str) -> Result<TaskRateLimit, ExecutorError> {
+        let (count, period) = TaskRateLimit::parse_rate_limit(rate_limit)
+            .ok_or_else(|| ExecutorError::InvalidConfig(format!("rate limit \"{}\" is not like \"10/m\"", rate_limit)))?;
+        Ok(TaskRateLimit::new(count, period))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_starts_over_the_limit_should_wait_for_the_period() {
+        let rate_limit = TaskRateLimit::new(2, Duration::from_secs(60));
+        let now = Instant::now();
+
+        let first = rate_limit.reserve();
+        let second = rate_limit.clone().reserve(); // Clones share the starts
+        let third = rate_limit.reserve();
+
+        assert!(first < now + Duration::from_secs(1));
+        assert!(second < now + Duration::from_secs(1));
+        assert!(third >= first + Duration::from_secs(60));
+        assert!(rate_limit.reserve() >= second + Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_released_start_should_be_reserved_again() {
+        let rate_limit = TaskRateLimit::new(1, Duration::from_secs(60));
+        let now = Instant::now();
+
+        let first = rate_limit.reserve();
+        rate_limit.release(first); // Submit failed
+        assert!(rate_limit.reserve() < now + Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_start_after_backoff_should_keep_the_limit_with_later_starts() {
+        let rate_limit = TaskRateLimit::new(1, Duration::from_secs(60));
+        let now = Instant::now();
+
+        let retry = rate_limit.reserve_after(now + Duration::from_secs(30)); // Retry waits for its backoff
+        assert!(retry >= now + Duration::from_secs(30) && retry < now + Duration::from_secs(31));
+
+        // Now would be 30 seconds before the retry => it waits till a minute after the retry
+        assert!(rate_limit.reserve() >= retry + Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_rate_limit_should_be_parsed_from_string() {
+        let rate_limit: TaskRateLimit = "10/m".parse().unwrap();
+        assert_eq!(rate_limit.get_count(), 10);
+        assert_eq!(rate_limit.get_period(), Duration::from_secs(60));
+        assert_eq!(" 5 / h ".parse::<TaskRateLimit>().unwrap().get_period(), Duration::from_secs(3600));
+
+        for invalid in ["10", "0/s", "ten/m", "10/day", "/m", "10/m/s", "99999999999999999999999/s"] {
+            assert!(matches!(invalid.parse::<TaskRateLimit>(), Err(ExecutorError::InvalidConfig(_))), "{}", invalid);
+        }
+    }
+}
@@ -1,4 +1,9 @@
+use std::sync::{Arc, Mutex};
+
 use super::slots::Slots;
 
 
-pub type SlotsVector = Vec<Slots>;
\ No newline at end of file
+pub type SlotsVector = Vec<Slots>;
+
+// Slots of one executor, shared by its clones (SlotRateLimiter is cloned for every submission)
+pub type SharedSlots = Arc<Mutex<SlotsVector>>;
\ No newline at end of file
@@ -0,0 +1,5 @@
+pub mod task_registry;
+pub mod registered_task;
+pub mod task_config;
+pub mod task_outcome;
+pub mod types;
@@ -0,0 +1,36 @@
+use serde_json::Value;
+
+use crate::core::types::FallibleTask;
+use crate::error_handler::error_handler::ExecutorError;
+
+use super::{task_config::TaskConfig, types::TaskCreator};
+
+// One entry of the TaskRegistry, cheap to clone
+#[derive(Clone)]
+pub struct RegisteredTask {
+    creator: TaskCreator,
+    config: TaskConfig,
+}
+
+impl RegisteredTask {
+    pub fn new(creator: TaskCreator, config: TaskConfig) -> RegisteredTask {
+        RegisteredTask { creator, config }
+    }
+
+    // Err(InvalidTaskArgs) when the args don't deserialize into the args of the task
+    pub fn create(&self, args: &Value) -> Result<FallibleTask, ExecutorError> {
+        (self.creator)(args)
+    }
+
+    pub fn get_config(&self) -> &TaskConfig {
+        &self.config
+    }
+}
+
+impl std::fmt::Debug for RegisteredTask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegisteredTask")
+            .field("config", &self.config)
+            .finish()
+    }
+}
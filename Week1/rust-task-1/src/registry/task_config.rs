@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use crate::priority::priority::Priority;
+use crate::rate_limiting::task_rate_limit::TaskRateLimit;
+use crate::retry::retry_policy::RetryPolicy;
+
+/*
+    Options of a registered task, the same for every submission => #[task(priority = High, retries = 3, timeout = "10s", rate_limit = "10/m")]
+    - priority: used by send_task() and the .delay() helper of #[task]
+    - retries: how many times a failed or timed out task is run again (panics are not retried)
+    - timeout: execution time limit of every attempt, None => task_timeout of the queue config
+    - rate_limit: starts of this task type, shared by every submission of the registry
+*/
+#[derive(Debug, Clone)]
+pub struct TaskConfig {
+    pub priority: Priority,
+    pub retries: u32,
+    pub timeout: Option<Duration>,
+    pub rate_limit: Option<TaskRateLimit>,
+}
+
+impl TaskConfig {
+    pub fn new(priority: Priority) -> TaskConfig {
+        TaskConfig {
+            priority,
+            retries: 0,
+            timeout: None,
+            rate_limit: None,
+        }
+    }
+
+    pub fn with_retries(mut self, retries: u32) -> TaskConfig {
+        self.retries = retries;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> TaskConfig {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_rate_limit(mut self, rate_limit: TaskRateLimit) -> TaskConfig {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    pub fn get_retry_policy(&self) -> RetryPolicy<String> {
+        match self.retries {
+            0 => RetryPolicy::none(),
+            retries => RetryPolicy::new(retries + 1).retry_on_any_error().retry_on_timeout(),
+        }
+    }
+}
+
+impl Default for TaskConfig {
+    fn default() -> TaskConfig {
+        TaskConfig::new(Priority::None)
+    }
+}
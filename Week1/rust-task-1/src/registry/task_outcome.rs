@@ -0,0 +1,19 @@
+use std::fmt::Display;
+
+// Output of a registered task => `async {}` and `async { Ok::<_, E>(...) }` can both be registered
+pub trait TaskOutcome {
+    fn into_result(self) -> Result<(), String>;
+}
+
+impl TaskOutcome for () {
+    fn into_result(self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+// Output is dropped, only the error message is kept for the worker and the result backend
+impl<T, E: Display> TaskOutcome for Result<T, E> {
+    fn into_result(self) -> Result<(), String> {
+        self.map(|_| ()).map_err(|error| error.to_string())
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::core::types::FallibleTask;
+use crate::durability::task_factory::TaskFactory;
+use crate::error_handler::error_handler::ExecutorError;
+
+use super::{registered_task::RegisteredTask, task_config::TaskConfig, task_outcome::TaskOutcome, types::TaskCreator};
+
+/*
+    Task types by name => registry.register("send_email", |args: EmailArgs| async move { ... })
+    A job is only a name + JSON args, so it can be written to the disk, sent over the wire or replayed.
+    Cloning is cheap (the tasks are shared), the same registry can be given to the Proxy and to enable_durability().
+*/
+#[derive(Clone, Default)]
+pub struct TaskRegistry {
+    tasks: HashMap<String, RegisteredTask>,
+}
+
+impl TaskRegistry {
+    pub fn new() -> TaskRegistry {
+        TaskRegistry { tasks: HashMap::new() }
+    }
+
+    // Registering the same name again replaces the task
+    pub fn register<A, F, Fut>(&mut self, name: &str, task: F) -> &mut TaskRegistry
+    where
+        A: DeserializeOwned,
+        F: Fn(A) -> Fut + Send + Sync + 'static,
+        Fut: Future + Send + 'static,
+        Fut::Output: TaskOutcome,
+    {
+        self.register_with_config(name, TaskConfig::default(), task)
+    }
+
+    // Same as register() with priority, retries, timeout and rate limit of the task
+    pub fn register_with_config<A, F, Fut>(&mut self, name: &str, config: TaskConfig, task: F) -> &mut TaskRegistry
+    where
+        A: DeserializeOwned,
+        F: Fn(A) -> Fut + Send + Sync + 'static,
+        Fut: Future + Send + 'static,
+        Fut::Output: TaskOutcome,
+    {
+        let task_name = name.to_string();
+        let creator: TaskCreator = Arc::new(move |args: &Value| {
+            let args = A::deserialize(args)
+                .map_err(|error| ExecutorError::InvalidTaskArgs(format!("{}: {}", task_name, error)))?;
+            let future = task(args);
+            let task: FallibleTask = Box::pin(async move { future.await.into_result() });
+            Ok(task)
+        });
+
+        self.tasks.insert(name.to_string(), RegisteredTask::new(creator, config));
+        self
+    }
+
+    pub fn with_task<A, F, Fut>(mut self, name: &str, task: F) -> TaskRegistry
+    where
+        A: DeserializeOwned,
+        F: Fn(A) -> Fut + Send + Sync + 'static,
+        Fut: Future + Send + 'static,
+        Fut::Output: TaskOutcome,
+    {
+        self.register(name, task);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<RegisteredTask> {
+        self.tasks.get(name).cloned()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.tasks.contains_key(name)
+    }
+
+    pub fn get_names(&self) -> impl Iterator<Item = &str> {
+        self.tasks.keys().map(String::as_str)
+    }
+}
+
+impl TaskFactory for TaskRegistry {
+    fn create(&self, name: &str, args: &Value) -> Result<FallibleTask, ExecutorError> {
+        let task = self.tasks.get(name).ok_or(ExecutorError::UnknownTaskName(name.to_string()))?;
+        task.create(args)
+    }
+}
+
+impl std::fmt::Debug for TaskRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TaskRegistry")
+            .field("tasks", &self.tasks.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use serde::Deserialize;
+    use serde_json::json;
+    use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+
+    #[derive(Deserialize)]
+    struct EmailArgs {
+        to: String,
+        retries_left: u32,
+    }
+
+    #[test]
+    fn test_registered_task_should_get_its_args() {
+        let sent = Arc::new(AtomicUsize::new(0));
+        let sent_clone = sent.clone();
+
+        let registry = TaskRegistry::new()
+            .with_task("send_email", move |args: EmailArgs| {
+                let sent = sent_clone.clone();
+                async move {
+                    if args.to.is_empty() {
+                        return Err(format!("no recipient, {} retries left", args.retries_left));
+                    }
+                    sent.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                }
+            })
+            .with_task("noop", |_: ()| async {});
+
+        let task = registry.create("send_email", &json!({ "to": "satoshi@example.com", "retries_left": 3 })).unwrap();
+        assert_eq!(block_on(task), Ok(()));
+        assert_eq!(sent.load(Ordering::SeqCst), 1);
+
+        let task = registry.create("send_email", &json!({ "to": "", "retries_left": 2 })).unwrap();
+        assert_eq!(block_on(task), Err(String::from("no recipient, 2 retries left")));
+        assert_eq!(block_on(registry.create("noop", &Value::Null).unwrap()), Ok(()));
+    }
+
+    #[test]
+    fn test_unknown_name_and_invalid_args_should_be_rejected() {
+        let registry = TaskRegistry::new().with_task("send_email", |_: EmailArgs| async {});
+
+        assert!(matches!(registry.create("send_sms", &json!({})), Err(ExecutorError::UnknownTaskName(name)) if name == "send_sms"));
+        assert!(matches!(registry.create("send_email", &json!({ "to": 1 })), Err(ExecutorError::InvalidTaskArgs(message)) if message.starts_with("send_email")));
+        assert!(registry.contains("send_email"));
+        assert_eq!(registry.get_names().collect::<Vec<_>>(), vec!["send_email"]);
+    }
+}
@@ -0,0 +1,8 @@
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::{core::types::FallibleTask, error_handler::error_handler::ExecutorError};
+
+// Registered task with the generic args already erased => deserializes the JSON args and creates the future
+pub type TaskCreator = Arc<dyn Fn(&Value) -> Result<FallibleTask, ExecutorError> + Send + Sync>;
@@ -0,0 +1,153 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::error_handler::error_handler::{fail_gracefully, ExecutorError};
+use crate::lifecycle::task_id::TaskId;
+
+use super::{result_backend::ResultBackend, stored_result::StoredResult};
+
+/*
+    One JSON file per result in `dir` => <task id>.json
+    - Another process with the same dir can read the results (and they survive a restart)
+    - File is written to <task id>.json.tmp and renamed, so a reader never sees a half written result
+*/
+#[derive(Debug)]
+pub struct FileResultBackend {
+    dir: PathBuf,
+}
+
+impl FileResultBackend {
+    const EXTENSION: &'static str = "json";
+
+    // Creates the directory when it does not exist
+    pub fn new(dir: impl Into<PathBuf>) -> Result<FileResultBackend, ExecutorError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|source| ExecutorError::Io { operation: "create result directory", source })?;
+
+        Ok(FileResultBackend { dir })
+    }
+
+    pub fn get_dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn result_path(&self, id: TaskId) -> PathBuf {
+        self.dir.join(format!("{}.{}", id, FileResultBackend::EXTENSION))
+    }
+
+    fn read_result(path: &Path) -> Result<Option<StoredResult>, ExecutorError> {
+        let json = match fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(source) => return Err(ExecutorError::Io { operation: "read result", source }),
+        };
+
+        serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|error| ExecutorError::InvalidResult(format!("{}: {}", path.display(), error)))
+    }
+}
+
+impl ResultBackend for FileResultBackend {
+    fn store(&self, result: StoredResult) -> Result<(), ExecutorError> {
+        let json = serde_json::to_string(&result).map_err(|error| ExecutorError::InvalidResult(error.to_string()))?;
+        let path = self.result_path(result.task_id);
+        let tmp_path = path.with_extension("json.tmp");
+
+        fs::write(&tmp_path, json).map_err(|source| ExecutorError::Io { operation: "write result", source })?;
+        fs::rename(&tmp_path, &path).map_err(|source| ExecutorError::Io { operation: "write result", source })
+    }
+
+    fn get(&self, id: TaskId) -> Result<Option<StoredResult>, ExecutorError> {
+        let now = SystemTime::now();
+        Ok(FileResultBackend::read_result(&self.result_path(id))?.filter(|result| !result.is_expired(now)))
+    }
+
+    fn remove(&self, id: TaskId) -> Result<bool, ExecutorError> {
+        match fs::remove_file(self.result_path(id)) {
+            Ok(()) => Ok(true),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(false),
+            Err(source) => Err(ExecutorError::Io { operation: "remove result", source }),
+        }
+    }
+
+    fn remove_expired(&self, now: SystemTime) -> Result<usize, ExecutorError> {
+        let entries = fs::read_dir(&self.dir).map_err(|source| ExecutorError::Io { operation: "read result directory", source })?;
+        let mut removed = 0;
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|extension| extension != FileResultBackend::EXTENSION) {
+                continue;
+            }
+
+            // Broken file of one result must not stop the cleanup of the others
+            match FileResultBackend::read_result(&path) {
+                Ok(Some(result)) if result.is_expired(now) => {
+                    if fs::remove_file(&path).is_ok() {
+                        removed += 1;
+                    }
+                }
+                Ok(_) => {}
+                Err(error) => fail_gracefully(error, "Result file was skipped by the reaper"),
+            }
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::lifecycle::task_state::TaskState;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rust-task-1-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_results_should_be_readable_by_another_backend() {
+        let dir = temp_dir("file-backend");
+        let writer = FileResultBackend::new(&dir).unwrap();
+
+        let mut result = StoredResult::new(TaskId::next(), TaskState::Succeeded, Duration::from_secs(60));
+        result.output = Some(String::from("\"sent\""));
+        let id = result.task_id;
+        writer.store(result.clone()).unwrap();
+
+        // Same directory => what another process would see
+        let reader = FileResultBackend::new(&dir).unwrap();
+        assert_eq!(reader.get(id).unwrap(), Some(result));
+        assert_eq!(reader.get(id).unwrap().unwrap().get_output::<String>().unwrap(), Some(String::from("sent")));
+        assert_eq!(reader.get(TaskId::next()).unwrap(), None);
+
+        assert!(reader.remove(id).unwrap());
+        assert!(!reader.remove(id).unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_remove_expired_should_skip_broken_files() {
+        let dir = temp_dir("file-backend-expired");
+        let backend = FileResultBackend::new(&dir).unwrap();
+
+        let expired = StoredResult::new(TaskId::next(), TaskState::TimedOut, Duration::from_millis(1));
+        let kept = StoredResult::new(TaskId::next(), TaskState::Succeeded, Duration::from_secs(60));
+        let (expired_id, kept_id) = (expired.task_id, kept.task_id);
+        backend.store(expired).unwrap();
+        backend.store(kept).unwrap();
+        fs::write(dir.join("broken.json"), "{").unwrap();
+
+        assert_eq!(backend.remove_expired(SystemTime::now() + Duration::from_secs(1)).unwrap(), 1);
+        assert!(!backend.result_path(expired_id).exists());
+        assert!(backend.get(kept_id).unwrap().is_some());
+        assert!(matches!(FileResultBackend::read_result(&dir.join("broken.json")), Err(ExecutorError::InvalidResult(_))));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use crate::error_handler::error_handler::ExecutorError;
+use crate::lifecycle::task_id::TaskId;
+
+use super::{result_backend::ResultBackend, stored_result::StoredResult};
+
+// Results are kept only in this process => for other threads, lost on restart
+#[derive(Debug, Default)]
+pub struct MemoryResultBackend {
+    results: Mutex<HashMap<TaskId, StoredResult>>,
+}
+
+impl MemoryResultBackend {
+    pub fn new() -> MemoryResultBackend {
+        MemoryResultBackend::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.results.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.results.lock().unwrap().is_empty()
+    }
+}
+
+impl ResultBackend for MemoryResultBackend {
+    fn store(&self, result: StoredResult) -> Result<(), ExecutorError> {
+        self.results.lock().unwrap().insert(result.task_id, result);
+        Ok(())
+    }
+
+    fn get(&self, id: TaskId) -> Result<Option<StoredResult>, ExecutorError> {
+        let now = SystemTime::now();
+        Ok(self.results.lock().unwrap().get(&id).filter(|result| !result.is_expired(now)).cloned())
+    }
+
+    fn remove(&self, id: TaskId) -> Result<bool, ExecutorError> {
+        Ok(self.results.lock().unwrap().remove(&id).is_some())
+    }
+
+    fn remove_expired(&self, now: SystemTime) -> Result<usize, ExecutorError> {
+        let mut results = self.results.lock().unwrap();
+        let before = results.len();
+
+        results.retain(|_, result| !result.is_expired(now));
+        Ok(before - results.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::lifecycle::task_state::TaskState;
+
+    #[test]
+    fn test_expired_results_should_be_hidden_and_removed() {
+        let backend = MemoryResultBackend::new();
+        let short = StoredResult::new(TaskId::next(), TaskState::Succeeded, Duration::from_millis(50));
+        let long = StoredResult::new(TaskId::next(), TaskState::Failed, Duration::from_secs(60));
+        let (short_id, long_id) = (short.task_id, long.task_id);
+
+        backend.store(short).unwrap();
+        backend.store(long).unwrap();
+        assert!(backend.get(short_id).unwrap().is_some());
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(backend.get(short_id).unwrap(), None); // Expired before the reaper runs
+        assert_eq!(backend.len(), 2);
+
+        assert_eq!(backend.remove_expired(SystemTime::now()).unwrap(), 1);
+        assert_eq!(backend.get(long_id).unwrap().unwrap().state, TaskState::Failed);
+        assert!(backend.remove(long_id).unwrap());
+        assert!(backend.is_empty());
+    }
+}
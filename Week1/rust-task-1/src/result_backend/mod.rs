@@ -0,0 +1,6 @@
+pub mod result_backend;
+pub mod stored_result;
+pub mod memory_backend;
+pub mod file_backend;
+pub mod result_reaper;
+pub mod types;
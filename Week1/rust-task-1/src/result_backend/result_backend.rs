@@ -0,0 +1,25 @@
+use std::fmt::Debug;
+use std::time::SystemTime;
+
+use crate::error_handler::error_handler::ExecutorError;
+use crate::lifecycle::task_id::TaskId;
+
+use super::stored_result::StoredResult;
+
+/*
+    Where the outcome of finished tasks is kept, so another thread (or process with FileResultBackend) can ask
+    "did task X finish and what did it return?".
+    - Workers store the result when the task reaches its final state
+    - Expired result is never returned by get(), the ResultReaper removes it later
+*/
+pub trait ResultBackend: Send + Sync + Debug {
+    fn store(&self, result: StoredResult) -> Result<(), ExecutorError>;
+
+    fn get(&self, id: TaskId) -> Result<Option<StoredResult>, ExecutorError>;
+
+    // True when the result was there
+    fn remove(&self, id: TaskId) -> Result<bool, ExecutorError>;
+
+    // Returns how many results were removed
+    fn remove_expired(&self, now: SystemTime) -> Result<usize, ExecutorError>;
+}
@@ -0,0 +1,79 @@
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+use crossbeam::channel::{self, RecvTimeoutError, Sender};
+
+use crate::error_handler::error_handler::{fail_gracefully, ExecutorError};
+
+use super::types::ResultBackendArc;
+
+/*
+    Background thread that removes expired results from the ResultBackend every `interval`.
+    Started by Proxy::set_result_backend(), stopped by stop() or when the ResultReaper is dropped.
+*/
+#[derive(Debug)]
+pub struct ResultReaper {
+    stop_tx: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ResultReaper {
+    pub fn start(backend: ResultBackendArc, interval: Duration) -> Result<ResultReaper, ExecutorError> {
+        let (stop_tx, stop_rx) = channel::bounded::<()>(1);
+
+        let thread = thread::Builder::new().name(String::from("executor-result-reaper")).spawn(move || {
+            // Sender dropped or stop() was called => stop the reaper
+            while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(interval) {
+                if let Err(error) = backend.remove_expired(SystemTime::now()) {
+                    fail_gracefully(error, "Expired results were not removed");
+                }
+            }
+        }).map_err(|source| ExecutorError::Io { operation: "start result reaper", source })?;
+
+        Ok(ResultReaper {
+            stop_tx: Some(stop_tx),
+            thread: Some(thread),
+        })
+    }
+
+    pub fn stop(mut self) {
+        self.stop_reaper();
+    }
+
+    fn stop_reaper(&mut self) {
+        if let Some(stop_tx) = self.stop_tx.take() {
+            let _ = stop_tx.send(());
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for ResultReaper {
+    fn drop(&mut self) {
+        self.stop_reaper();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::lifecycle::{task_id::TaskId, task_state::TaskState};
+    use crate::result_backend::{memory_backend::MemoryResultBackend, result_backend::ResultBackend, stored_result::StoredResult};
+
+    #[test]
+    fn test_reaper_should_remove_expired_results() {
+        let backend = Arc::new(MemoryResultBackend::new());
+        backend.store(StoredResult::new(TaskId::next(), TaskState::Succeeded, Duration::from_millis(20))).unwrap();
+        backend.store(StoredResult::new(TaskId::next(), TaskState::Succeeded, Duration::from_secs(60))).unwrap();
+
+        let reaper = ResultReaper::start(backend.clone(), Duration::from_millis(50)).unwrap();
+        thread::sleep(Duration::from_millis(200));
+        reaper.stop();
+
+        assert_eq!(backend.len(), 1);
+    }
+}
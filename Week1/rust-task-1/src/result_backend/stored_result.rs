@@ -0,0 +1,80 @@
+use std::time::{Duration, SystemTime};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::error_handler::error_handler::ExecutorError;
+use crate::future_executors::future_status::FutureStatus;
+use crate::lifecycle::{task_id::TaskId, task_state::TaskState};
+
+// Outcome of one finished task in the ResultBackend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredResult {
+    pub task_id: TaskId,
+    pub state: TaskState, // Succeeded, Failed, TimedOut or Cancelled
+    pub output: Option<String>, // JSON of the output, only for tasks sent with Proxy::submit_stored()
+    pub error: Option<String>, // Err message, panic message or timeout
+    pub execution_time: Duration,
+    pub finished_at: SystemTime,
+    pub expires_at: SystemTime,
+}
+
+impl StoredResult {
+    pub fn new(task_id: TaskId, state: TaskState, ttl: Duration) -> StoredResult {
+        let finished_at = SystemTime::now();
+
+        StoredResult {
+            task_id,
+            state,
+            output: None,
+            error: None,
+            execution_time: Duration::ZERO,
+            finished_at,
+            expires_at: finished_at + ttl,
+        }
+    }
+
+    // Result of the task finished by the worker, `ttl` is the config default when the task has no own TTL
+    pub fn from_status(status: &FutureStatus, ttl: Duration) -> StoredResult {
+        let mut result = StoredResult::new(status.task_id, status.state, status.result_ttl.unwrap_or(ttl));
+        result.output = status.output.clone();
+        result.error = status.error.clone();
+        result.execution_time = status.execution_time;
+        result
+    }
+
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        now >= self.expires_at
+    }
+
+    // Output as the type returned by the task, Ok(None) when no output was stored
+    pub fn get_output<T: DeserializeOwned>(&self) -> Result<Option<T>, ExecutorError> {
+        self.output
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()
+            .map_err(|error| ExecutorError::InvalidResult(format!("output of task {}: {}", self.task_id, error)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_result_should_expire_after_ttl() {
+        let result = StoredResult::new(TaskId::next(), TaskState::Succeeded, Duration::from_secs(10));
+
+        assert!(!result.is_expired(result.finished_at + Duration::from_secs(9)));
+        assert!(result.is_expired(result.finished_at + Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_get_output_should_deserialize_the_json() {
+        let mut result = StoredResult::new(TaskId::next(), TaskState::Succeeded, Duration::from_secs(10));
+        assert_eq!(result.get_output::<u32>().unwrap(), None);
+
+        result.output = Some(String::from("[1,2,3]"));
+        assert_eq!(result.get_output::<Vec<u32>>().unwrap(), Some(vec![1, 2, 3]));
+        assert!(matches!(result.get_output::<String>(), Err(ExecutorError::InvalidResult(_))));
+    }
+}
@@ -0,0 +1,9 @@
+use std::sync::{Arc, Mutex};
+
+use super::result_backend::ResultBackend;
+
+// Shared by the Proxy, the workers of every queue and the reaper thread
+pub type ResultBackendArc = Arc<dyn ResultBackend>;
+
+// JSON of the task output, written by the task future and read by the worker when the task is finished
+pub type OutputSlot = Arc<Mutex<Option<String>>>;
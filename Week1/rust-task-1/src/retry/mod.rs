@@ -0,0 +1,2 @@
+pub mod retry_policy;
+pub mod retrying_task;
\ No newline at end of file
@@ -0,0 +1,191 @@
+use std::{collections::hash_map::RandomState, convert::Infallible, hash::{BuildHasher, Hasher}, sync::Arc, time::{Duration, SystemTime}};
+
+use crate::error_handler::error_handler::TaskError;
+
+// How long to wait before the next attempt
+#[derive(Debug, Clone, PartialEq)]
+pub enum Backoff {
+    Fixed(Duration),
+    Exponential { initial: Duration, multiplier: u32, max: Duration }, // initial * multiplier^(attempt - 1), never more than max
+}
+
+pub type ErrorPredicate<E> = Arc<dyn Fn(&E) -> bool + Send + Sync + 'static>;
+
+/*
+    Celery-like autoretry for one task.
+    - max_attempts includes the first attempt => RetryPolicy::new(3) runs the task at most 3 times
+    - Nothing is retried by default, you choose which failures are retried (timeout, panic, Err matching a predicate)
+    - jitter is a fraction (0.0 - 1.0) of the backoff that is randomly removed so retries of many tasks do not hit the service at the same time
+*/
+pub struct RetryPolicy<E = Infallible> {
+    max_attempts: u32,
+    backoff: Backoff,
+    jitter: f64,
+    retry_on_timeout: bool,
+    retry_on_panic: bool,
+    retry_on_error: Option<ErrorPredicate<E>>,
+}
+
+impl<E> RetryPolicy<E> {
+    const DEFAULT_BACKOFF: Backoff = Backoff::Fixed(Duration::from_secs(1));
+
+    pub fn new(max_attempts: u32) -> RetryPolicy<E> {
+        RetryPolicy {
+            max_attempts: max_attempts.max(1),
+            backoff: RetryPolicy::<E>::DEFAULT_BACKOFF,
+            jitter: 0.0,
+            retry_on_timeout: false,
+            retry_on_panic: false,
+            retry_on_error: None,
+        }
+    }
+
+    // Only one attempt
+    pub fn none() -> RetryPolicy<E> {
+        RetryPolicy::new(1)
+    }
+
+    pub fn with_backoff(mut self, backoff: Backoff) -> RetryPolicy<E> {
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: f64) -> RetryPolicy<E> {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn retry_on_timeout(mut self) -> RetryPolicy<E> {
+        self.retry_on_timeout = true;
+        self
+    }
+
+    pub fn retry_on_panic(mut self) -> RetryPolicy<E> {
+        self.retry_on_panic = true;
+        self
+    }
+
+    pub fn retry_on_error<P>(mut self, predicate: P) -> RetryPolicy<E>
+    where
+        P: Fn(&E) -> bool + Send + Sync + 'static,
+    {
+        self.retry_on_error = Some(Arc::new(predicate));
+        self
+    }
+
+    pub fn retry_on_any_error(self) -> RetryPolicy<E> {
+        self.retry_on_error(|_| true)
+    }
+
+    pub fn get_max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    // Is this failure retried at all (without checking the attempts left)
+    pub fn is_retryable(&self, failure: &TaskError<E>) -> bool {
+        match failure {
+            TaskError::Timeout => self.retry_on_timeout,
+            TaskError::Panicked(_) => self.retry_on_panic,
+            TaskError::Failed(error) => self.retry_on_error.as_ref().is_some_and(|predicate| predicate(error)),
+            TaskError::Cancelled => false,
+        }
+    }
+
+    // attempt is the number of the attempt that failed, starting from 1
+    pub fn should_retry(&self, failure: &TaskError<E>, attempt: u32) -> bool {
+        attempt < self.max_attempts && self.is_retryable(failure)
+    }
+
+    pub fn backoff_delay(&self, attempt: u32) -> Duration {
+        let delay = match &self.backoff {
+            Backoff::Fixed(delay) => *delay,
+            Backoff::Exponential { initial, multiplier, max } => {
+                let factor = multiplier.saturating_pow(attempt.saturating_sub(1));
+                initial.saturating_mul(factor).min(*max)
+            }
+        };
+
+        if self.jitter > 0.0 {
+            delay.mul_f64(1.0 - self.jitter * random_fraction())
+        } else {
+            delay
+        }
+    }
+}
+
+// Clone by hand, derive would require E: Clone while only the predicate uses E
+impl<E> Clone for RetryPolicy<E> {
+    fn clone(&self) -> Self {
+        RetryPolicy {
+            max_attempts: self.max_attempts,
+            backoff: self.backoff.clone(),
+            jitter: self.jitter,
+            retry_on_timeout: self.retry_on_timeout,
+            retry_on_panic: self.retry_on_panic,
+            retry_on_error: self.retry_on_error.clone(),
+        }
+    }
+}
+
+// Random value in [0, 1) => RandomState is seeded randomly, good enough for jitter without extra dependency
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_nanos());
+
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nothing_is_retried_by_default() {
+        let policy = RetryPolicy::<String>::new(3);
+
+        assert!(!policy.should_retry(&TaskError::Timeout, 1));
+        assert!(!policy.should_retry(&TaskError::Panicked(String::from("boom")), 1));
+        assert!(!policy.should_retry(&TaskError::Failed(String::from("error")), 1));
+    }
+
+    #[test]
+    fn test_should_retry_respects_rules_and_max_attempts() {
+        let policy = RetryPolicy::<String>::new(3)
+            .retry_on_timeout()
+            .retry_on_error(|error| error.contains("unavailable"));
+
+        assert!(policy.should_retry(&TaskError::Timeout, 1));
+        assert!(policy.should_retry(&TaskError::Failed(String::from("service unavailable")), 2));
+        assert!(!policy.should_retry(&TaskError::Failed(String::from("bad request")), 1));
+        assert!(!policy.should_retry(&TaskError::Panicked(String::from("boom")), 1));
+        assert!(!policy.should_retry(&TaskError::Cancelled, 1));
+        assert!(!policy.should_retry(&TaskError::Timeout, 3)); // No attempts left
+    }
+
+    #[test]
+    fn test_exponential_backoff_should_grow_till_max() {
+        let policy = RetryPolicy::<Infallible>::new(10).with_backoff(Backoff::Exponential {
+            initial: Duration::from_millis(100),
+            multiplier: 2,
+            max: Duration::from_millis(500),
+        });
+
+        assert_eq!(policy.backoff_delay(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff_delay(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff_delay(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff_delay(4), Duration::from_millis(500));
+        assert_eq!(policy.backoff_delay(40), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_jitter_should_stay_inside_backoff() {
+        let policy = RetryPolicy::<Infallible>::new(3)
+            .with_backoff(Backoff::Fixed(Duration::from_millis(1000)))
+            .with_jitter(0.5);
+
+        for attempt in 1..50 {
+            let delay = policy.backoff_delay(attempt);
+            assert!(delay >= Duration::from_millis(500) && delay <= Duration::from_millis(1000));
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::{fmt::Display, sync::Arc, time::{Duration, Instant}};
+
+use crate::core::{executor::AsyncExecutor, job::Job, task_handle::{task_handle, TaskCompleter, TaskHandle}, task_options::TaskOptions, types::FailureCallback};
+use crate::error_handler::error_handler::{fail_gracefully, ExecutorError, TaskError};
+use crate::lifecycle::{task_id::TaskId, task_state::TaskState};
+use crate::middleware::task_context::TaskContext;
+use crate::priority::priority::Priority;
+use crate::rate_limiting::{slot_rate_limiter::SlotRateLimiter, task_rate_limit::TaskRateLimit};
+
+use super::retry_policy::RetryPolicy;
+
+/*
+    Task that is re-created from the factory for every attempt.
+    - Every attempt is a separate Job => goes through the rate limiter and the worker (timeout, panic catching)
+    - When the attempt fails and the policy allows it, the next attempt is scheduled after the backoff
+    - The TaskHandle gets only the result of the last attempt
+    - Every attempt has the id of the task => Running -> Retrying -> Queued -> Running ... for the same TaskId
+    The executor clone keeps the worker channel open while a retry is waiting, so wait_all() waits for retries too.
+*/
+pub struct RetryingTask<F, T, E> {
+    id: TaskId,
+    factory: F,
+    policy: RetryPolicy<E>,
+    priority: Priority,
+    name: Option<String>,
+    result_ttl: Option<Duration>,
+    timeout: Option<Duration>,
+    rate_limit: Option<TaskRateLimit>,
+    completer: TaskCompleter<T, E>,
+    executor: AsyncExecutor,
+}
+
+impl<F, Fut, T, E> RetryingTask<F, T, E>
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, E>> + Send + 'static,
+    T: Send + 'static,
+    E: Display + Send + 'static,
+{
+    // name and queue of the options are not used => `executor` is the routed queue
+    pub fn start(factory: F, options: TaskOptions<E>, executor: AsyncExecutor) -> Result<TaskHandle<T, E>, ExecutorError> {
+        let id = TaskId::next();
+        let (completer, handle) = task_handle::<T, E>();
+
+        let retrying_task = Arc::new(RetryingTask {
+            id,
+            factory,
+            policy: options.retry_policy,
+            priority: options.priority,
+            name: options.name,
+            result_ttl: options.result_ttl,
+            timeout: options.timeout,
+            rate_limit: options.rate_limit,
+            completer,
+            executor,
+        });
+
+        let job = RetryingTask::attempt_job(&retrying_task, 1);
+        retrying_task.submit_attempt(job, 1, options.eta.unwrap_or_else(Instant::now))?;
+        Ok(handle.with_task_id(id))
+    }
+
+    // Attempt starts at `earliest` or later when the rate limit of the task is reached
+    // The reserved start is released when the attempt is not accepted, so it doesn't count for the limit
+    // Retries are sent from the worker (failure callback) => always through the scheduler, the worker must not wait
+    fn submit_attempt(&self, job: Job, attempt: u32, earliest: Instant) -> Result<(), ExecutorError> {
+        let start = self.rate_limit.as_ref().map(|rate_limit| rate_limit.reserve_after(earliest)).unwrap_or(earliest);
+
+        let submitted = match attempt > 1 || start > Instant::now() {
+            true => self.executor.delay_at(start, self.priority.clone(), job), // Waits in the scheduler, not in the worker
+            false => SlotRateLimiter::delay_rate_limited(&self.executor, self.priority.clone(), job),
+        };
+        if submitted.is_err() && let Some(rate_limit) = &self.rate_limit {
+            rate_limit.release(start);
+        }
+        submitted
+    }
+
+    fn attempt_job(retrying_task: &Arc<Self>, attempt: u32) -> Job {
+        // Worker reports timeout and panic through the callback
+        let callback_task = retrying_task.clone();
+        let on_failure: FailureCallback = Box::new(move |reason| RetryingTask::attempt_failed(callback_task, attempt, reason.with_error_type()));
+
+        let future_task = retrying_task.clone();
+        let future = async move {
+            match (future_task.factory)().await {
+                Ok(output) => {
+                    future_task.completer.complete(output);
+                    Ok(())
+                }
+                Err(error) => {
+                    let message = error.to_string();
+                    RetryingTask::attempt_failed(future_task, attempt, TaskError::Failed(error));
+                    Err(message)
+                }
+            }
+        };
+
+        let mut job = Job::fallible(Box::pin(future)).with_failure_callback(on_failure).with_id(retrying_task.id).with_attempt(attempt);
+        if let Some(name) = &retrying_task.name {
+            job = job.with_name(name);
+        }
+        if let Some(result_ttl) = retrying_task.result_ttl {
+            job = job.with_result_ttl(result_ttl);
+        }
+        if let Some(timeout) = retrying_task.timeout {
+            job = job.with_timeout(timeout);
+        }
+        job
+    }
+
+    fn attempt_failed(retrying_task: Arc<Self>, attempt: u32, failure: TaskError<E>) {
+        if retrying_task.policy.should_retry(&failure, attempt) {
+            retrying_task.executor.metrics.lock().unwrap().increment_tasks_retried();
+            retrying_task.executor.tracker.transition(retrying_task.id, TaskState::Retrying); // Worker's Failed / TimedOut of this attempt is ignored
+
+            let backoff = retrying_task.policy.backoff_delay(attempt);
+            let context = TaskContext::new(retrying_task.id, retrying_task.name.clone(), retrying_task.priority.clone(), attempt)
+                .with_queue(&retrying_task.executor.queue);
+            retrying_task.executor.middleware.on_retry(&context, &failure.to_string(), backoff);
+
+            // Backoff (and the rate limit of the task) is waited in the scheduler, so it does not take a worker slot
+            let job = RetryingTask::attempt_job(&retrying_task, attempt + 1);
+            // When the executor is shut down the attempt is dropped => the handle gets Cancelled
+            if let Err(error) = retrying_task.submit_attempt(job, attempt + 1, Instant::now() + backoff) {
+                fail_gracefully(error, "Retry attempt was not scheduled");
+            }
+            return;
+        }
+
+        if attempt > 1 && retrying_task.policy.is_retryable(&failure) {
+            retrying_task.executor.metrics.lock().unwrap().increment_tasks_retries_exhausted();
+        }
+        retrying_task.completer.complete_with_error(failure);
+    }
+}
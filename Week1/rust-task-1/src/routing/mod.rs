@@ -0,0 +1,3 @@
+pub mod router;
+pub mod named_queues;
+pub mod types;
\ No newline at end of file
@@ -0,0 +1,56 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use crate::core::types::ProxyExecutor;
+use crate::error_handler::error_handler::ExecutorError;
+
+// Queue of the executor created by Proxy::new() / with_config(), gets every task that is not routed
+pub const DEFAULT_QUEUE: &str = "default";
+
+/*
+    Named queues of a Proxy ("emails", "payments", ...).
+    Every queue is its own AsyncExecutor => own channel, workers and rate limit (ExecutorConfig of the queue),
+    so slow jobs of one queue can't take the workers of another queue.
+*/
+#[derive(Debug, Default)]
+pub struct NamedQueues {
+    queues: Mutex<BTreeMap<String, ProxyExecutor>>, // BTreeMap => queues are always listed in the same order
+}
+
+impl NamedQueues {
+    pub fn new() -> NamedQueues {
+        NamedQueues::default()
+    }
+
+    pub fn add(&self, name: &str, executor: ProxyExecutor) -> Result<(), ExecutorError> {
+        let mut queues = self.queues.lock().unwrap();
+
+        if name == DEFAULT_QUEUE || queues.contains_key(name) {
+            return Err(ExecutorError::InvalidConfig(format!("queue \"{}\" is already registered", name)));
+        }
+        queues.insert(name.to_string(), executor);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Result<ProxyExecutor, ExecutorError> {
+        self.queues
+            .lock()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| ExecutorError::UnknownQueue(name.to_string()))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        name == DEFAULT_QUEUE || self.queues.lock().unwrap().contains_key(name)
+    }
+
+    pub fn get_all(&self) -> Vec<(String, ProxyExecutor)> {
+        self.queues
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, executor)| (name.clone(), executor.clone()))
+            .collect()
+    }
+}
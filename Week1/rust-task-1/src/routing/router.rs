@@ -0,0 +1,108 @@
+use std::{fmt, sync::Arc};
+
+use crate::priority::priority::Priority;
+
+use super::types::RoutePredicate;
+
+// What the router knows about a task when it is submitted
+#[derive(Debug, Clone, Copy)]
+pub struct TaskInfo<'a> {
+    pub name: Option<&'a str>, // Set by task_named(), submit_named() or TaskOptions::with_name()
+    pub priority: &'a Priority,
+}
+
+#[derive(Clone)]
+enum RouteMatcher {
+    TaskName(String),
+    Predicate(RoutePredicate),
+}
+
+#[derive(Clone)]
+struct RoutingRule {
+    matcher: RouteMatcher,
+    queue: String,
+}
+
+/*
+    Routing rules => which named queue gets the task (like Celery task_routes):
+        Router::new()
+            .route_task("charge_card", "payments")
+            .route_when(|task| task.priority == &Priority::High, "urgent")
+    - Rules are checked in the order they were added, the first matching rule wins
+    - Task that matches no rule goes to the default queue
+*/
+#[derive(Clone, Default)]
+pub struct Router {
+    rules: Vec<RoutingRule>,
+}
+
+impl Router {
+    pub fn new() -> Router {
+        Router { rules: Vec::new() }
+    }
+
+    pub fn route_task(mut self, task_name: &str, queue: &str) -> Self {
+        self.rules.push(RoutingRule { matcher: RouteMatcher::TaskName(task_name.to_string()), queue: queue.to_string() });
+        self
+    }
+
+    pub fn route_when<P>(mut self, predicate: P, queue: &str) -> Self
+    where
+        P: Fn(&TaskInfo) -> bool + Send + Sync + 'static,
+    {
+        self.rules.push(RoutingRule { matcher: RouteMatcher::Predicate(Arc::new(predicate)), queue: queue.to_string() });
+        self
+    }
+
+    // Name of the queue for this task, None => default queue
+    pub fn route(&self, task: &TaskInfo) -> Option<&str> {
+        self.rules
+            .iter()
+            .find(|rule| match &rule.matcher {
+                RouteMatcher::TaskName(name) => task.name == Some(name.as_str()),
+                RouteMatcher::Predicate(predicate) => predicate(task),
+            })
+            .map(|rule| rule.queue.as_str())
+    }
+
+    // Used to check that every rule points to a registered queue
+    pub fn get_queues(&self) -> impl Iterator<Item = &str> {
+        self.rules.iter().map(|rule| rule.queue.as_str())
+    }
+}
+
+impl fmt::Debug for Router {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Router").field("rules", &self.rules.len()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info<'a>(name: Option<&'a str>, priority: &'a Priority) -> TaskInfo<'a> {
+        TaskInfo { name, priority }
+    }
+
+    #[test]
+    fn test_route_should_use_first_matching_rule() {
+        let router = Router::new()
+            .route_task("charge_card", "payments")
+            .route_when(|task| task.priority == &Priority::High, "urgent")
+            .route_when(|task| task.name.is_some_and(|name| name.starts_with("send_")), "emails");
+
+        assert_eq!(router.route(&info(Some("charge_card"), &Priority::High)), Some("payments"));
+        assert_eq!(router.route(&info(Some("send_email"), &Priority::High)), Some("urgent"));
+        assert_eq!(router.route(&info(Some("send_email"), &Priority::Low)), Some("emails"));
+    }
+
+    #[test]
+    fn test_route_without_matching_rule_should_return_none() {
+        let router = Router::new().route_task("charge_card", "payments");
+
+        assert_eq!(router.route(&info(None, &Priority::None)), None);
+        assert_eq!(router.route(&info(Some("charge"), &Priority::None)), None);
+        assert_eq!(router.get_queues().collect::<Vec<_>>(), vec!["payments"]);
+    }
+}
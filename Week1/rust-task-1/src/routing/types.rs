@@ -0,0 +1,10 @@
+use std::sync::{Arc, Mutex};
+
+use super::{named_queues::NamedQueues, router::{Router, TaskInfo}};
+
+// Decides by the task info if the task goes to the queue of the rule
+pub type RoutePredicate = Arc<dyn Fn(&TaskInfo) -> bool + Send + Sync + 'static>;
+
+// Shared by all Proxy clones
+pub type NamedQueuesArc = Arc<NamedQueues>;
+pub type RouterArc = Arc<Mutex<Router>>;
@@ -0,0 +1,542 @@
+use std::{str::FromStr, sync::{atomic::{AtomicBool, Ordering}, Arc, Mutex}, thread::{self, JoinHandle}, time::Duration};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use crossbeam::channel::{self, RecvTimeoutError, Sender};
+
+use crate::core::{executor::AsyncExecutor, job::Job, submit_mode::SubmitMode, types::Task};
+use crate::error_handler::error_handler::{fail_gracefully, ExecutorError};
+use crate::priority::priority::Priority;
+use crate::rate_limiting::slot_rate_limiter::SlotRateLimiter;
+
+use super::types::{BeatEntries, BeatTaskFactory};
+
+/*
+    Beat scheduler => runs task factories periodically (like celery beat).
+    - Every task has an interval or a cron expression
+    - One beat thread fires the due tasks, every fire is a normal Job => SlotRateLimiter, priority, worker, metrics
+    - Missed runs (the beat thread was late) are skipped or caught up, by MissedRunPolicy
+    - By default a task is not fired again while its previous run is still in progress (overlap prevention)
+      CatchUp => runs that find the previous run in progress wait and are fired one by one after it, Skip => they are dropped
+    - Runs are sent without the entries lock and never wait for queue space => a full or paused queue doesn't block
+      stop() or next_fire_times(), the rejected run is counted as skipped
+    The beat thread keeps an executor clone => stop the beat (or drop the BeatHandle) before await_completion().
+*/
+#[derive(Debug, Clone)]
+pub enum BeatSchedule {
+    Interval(Duration),
+    // Format of the cron crate: "sec min hour day_of_month month day_of_week [year]"
+    Cron(Box<cron::Schedule>),
+}
+
+impl BeatSchedule {
+    // None when the schedule has no more fire times (cron with a year in the past)
+    pub fn next_after(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            BeatSchedule::Interval(interval) => Some(time + TimeDelta::from_std(*interval).ok()?),
+            BeatSchedule::Cron(schedule) => schedule.after(&time).next(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MissedRunPolicy {
+    Skip, // Fire once for all missed runs
+    CatchUp, // Fire once for every missed run, without overlap they run one after another
+}
+
+pub struct PeriodicTask {
+    name: String,
+    schedule: BeatSchedule,
+    factory: BeatTaskFactory,
+    priority: Priority,
+    missed_run_policy: MissedRunPolicy,
+    allow_overlap: bool,
+}
+
+impl PeriodicTask {
+    pub fn every<F, Fut>(name: &str, interval: Duration, factory: F) -> PeriodicTask
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        PeriodicTask::with_schedule(name, BeatSchedule::Interval(interval), factory)
+    }
+
+    pub fn cron<F, Fut>(name: &str, expression: &str, factory: F) -> Result<PeriodicTask, ExecutorError>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let schedule = cron::Schedule::from_str(expression).map_err(|_| ExecutorError::InvalidSchedule)?;
+        Ok(PeriodicTask::with_schedule(name, BeatSchedule::Cron(Box::new(schedule)), factory))
+    }
+
+    fn with_schedule<F, Fut>(name: &str, schedule: BeatSchedule, factory: F) -> PeriodicTask
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        PeriodicTask {
+            name: name.to_string(),
+            schedule,
+            factory: Arc::new(move || Box::pin(factory()) as Task),
+            priority: Priority::None,
+            missed_run_policy: MissedRunPolicy::Skip,
+            allow_overlap: false,
+        }
+    }
+
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_missed_run_policy(mut self, policy: MissedRunPolicy) -> Self {
+        self.missed_run_policy = policy;
+        self
+    }
+
+    // Next run is fired even when the previous one is still running
+    pub fn allow_overlap(mut self) -> Self {
+        self.allow_overlap = true;
+        self
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    // Next `count` fire times after `from`
+    pub fn upcoming(&self, from: DateTime<Utc>, count: usize) -> Vec<DateTime<Utc>> {
+        let mut fire_times = Vec::with_capacity(count);
+        let mut time = from;
+
+        while fire_times.len() < count {
+            let Some(next) = self.schedule.next_after(time) else { break };
+            fire_times.push(next);
+            time = next;
+        }
+        fire_times
+    }
+}
+
+// Registered tasks, started with Proxy::beat()
+#[derive(Default)]
+pub struct BeatScheduler {
+    tasks: Vec<PeriodicTask>,
+}
+
+impl BeatScheduler {
+    // Upper bound of the beat thread sleep, so wall clock changes are noticed
+    const MAX_SLEEP: Duration = Duration::from_secs(1);
+    // How often the beat checks if the previous run finished while caught up runs are waiting
+    const PENDING_RUN_CHECK: Duration = Duration::from_millis(50);
+
+    pub fn new() -> BeatScheduler {
+        BeatScheduler { tasks: vec![] }
+    }
+
+    pub fn register(mut self, task: PeriodicTask) -> Self {
+        self.tasks.push(task);
+        self
+    }
+
+    pub fn start(self, executor: AsyncExecutor) -> Result<BeatHandle, ExecutorError> {
+        let now = Utc::now();
+        let entries: BeatEntries = Arc::new(Mutex::new(
+            self.tasks.into_iter().map(|task| BeatEntry::new(task, now)).collect()
+        ));
+        let (stop_tx, stop_rx) = channel::bounded::<()>(1);
+
+        let thread_entries = entries.clone();
+        let thread = thread::Builder::new().name(String::from("executor-beat")).spawn(move || {
+            loop {
+                let wait = BeatScheduler::fire_due(&thread_entries, &executor, Utc::now());
+
+                // Sender dropped or stop() was called => stop the beat
+                match stop_rx.recv_timeout(wait) {
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    _ => break,
+                }
+            }
+        }).map_err(|source| ExecutorError::Io { operation: "start beat", source })?;
+
+        Ok(BeatHandle {
+            entries,
+            stop_tx: Some(stop_tx),
+            thread: Some(thread),
+        })
+    }
+
+    // Fires all due tasks, returns how long the beat thread can sleep
+    fn fire_due(entries: &BeatEntries, executor: &AsyncExecutor, now: DateTime<Utc>) -> Duration {
+        // Jobs are taken under the lock, sent after it is released
+        let runs: Vec<(usize, Priority, Job)> = entries
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .enumerate()
+            .flat_map(|(index, entry)| {
+                let due_runs = entry.due_runs(now);
+                entry.take_runs(due_runs).into_iter().map(move |(priority, job)| (index, priority, job))
+            })
+            .collect();
+
+        // Try => a full or paused queue rejects the run instead of parking the beat thread
+        let rejected: Vec<usize> = runs
+            .into_iter()
+            .filter_map(|(index, priority, job)| SlotRateLimiter::submit_rate_limited(executor, priority, job, SubmitMode::Try).err().map(|_| index))
+            .collect();
+
+        let mut entries = entries.lock().unwrap();
+        for index in rejected {
+            entries[index].skip_run("the queue did not accept it");
+        }
+
+        let wait = entries
+            .iter()
+            .filter_map(|entry| entry.next_fire)
+            .min()
+            .and_then(|next_fire| (next_fire - Utc::now()).to_std().ok())
+            .unwrap_or(Duration::ZERO)
+            .min(BeatScheduler::MAX_SLEEP);
+
+        match entries.iter().any(|entry| entry.pending_runs > 0) {
+            true => wait.min(BeatScheduler::PENDING_RUN_CHECK),
+            false => wait,
+        }
+    }
+}
+
+pub struct BeatEntry {
+    task: PeriodicTask,
+    next_fire: Option<DateTime<Utc>>,
+    running: Arc<AtomicBool>,
+    pending_runs: u32, // Caught up runs that wait till the previous run is finished
+    skipped_runs: u32, // Dropped while the previous run was running (Skip) or rejected by the queue
+}
+
+impl BeatEntry {
+    fn new(task: PeriodicTask, now: DateTime<Utc>) -> BeatEntry {
+        let next_fire = task.schedule.next_after(now);
+        BeatEntry { task, next_fire, running: Arc::new(AtomicBool::new(false)), pending_runs: 0, skipped_runs: 0 }
+    }
+
+    // How many times the task should be fired now, moves next_fire after `now`
+    fn due_runs(&mut self, now: DateTime<Utc>) -> u32 {
+        let mut missed = 0;
+
+        while let Some(next_fire) = self.next_fire {
+            if next_fire > now {
+                break;
+            }
+            missed += 1;
+            self.next_fire = self.task.schedule.next_after(next_fire);
+        }
+
+        match self.task.missed_run_policy {
+            MissedRunPolicy::Skip => missed.min(1),
+            MissedRunPolicy::CatchUp => missed,
+        }
+    }
+
+    /*
+        Jobs of the due runs + the runs that are still waiting from the previous beats.
+        When the previous run is still in progress:
+            - CatchUp => the runs wait in pending_runs, the beat fires them one by one when the previous run is finished
+              A task that is always slower than its schedule gets an endless backlog, use Skip (or allow_overlap()) for it
+            - Skip => the run is dropped
+    */
+    fn take_runs(&mut self, due_runs: u32) -> Vec<(Priority, Job)> {
+        self.pending_runs += due_runs;
+
+        let mut runs = vec![];
+        while self.pending_runs > 0 && let Some(job) = self.fire() {
+            runs.push((self.task.priority.clone(), job));
+            self.pending_runs -= 1;
+        }
+
+        if self.task.missed_run_policy == MissedRunPolicy::Skip && self.pending_runs > 0 {
+            self.skip_run("the previous run is still running");
+            self.pending_runs = 0;
+        }
+        runs
+    }
+
+    // None when the previous run is still in progress and overlap is not allowed (nothing is fired)
+    fn fire(&self) -> Option<Job> {
+        let mut running_guard = None;
+
+        if !self.task.allow_overlap {
+            if self.running.swap(true, Ordering::SeqCst) {
+                return None;
+            }
+            running_guard = Some(RunningGuard(self.running.clone()));
+        }
+
+        let future = (self.task.factory)();
+        Some(Job::new(Box::pin(async move {
+            let _running_guard = running_guard; // Dropped when the run finishes, panics, times out or the job is rejected
+            future.await;
+        })))
+    }
+
+    fn skip_run(&mut self, reason: &str) {
+        self.skipped_runs += 1;
+        fail_gracefully(ExecutorError::RunSkipped(self.task.name.clone()), reason);
+    }
+}
+
+// Clears the running flag of the periodic task when the job is dropped
+struct RunningGuard(Arc<AtomicBool>);
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+pub struct BeatHandle {
+    entries: BeatEntries,
+    stop_tx: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl BeatHandle {
+    // (task name, next fire time) of every task that will fire again, sorted by time
+    pub fn next_fire_times(&self) -> Vec<(String, DateTime<Utc>)> {
+        let mut fire_times: Vec<(String, DateTime<Utc>)> = self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|entry| entry.next_fire.map(|next_fire| (entry.task.name.clone(), next_fire)))
+            .collect();
+
+        fire_times.sort_by_key(|(_, next_fire)| *next_fire);
+        fire_times
+    }
+
+    // Runs of the task that were not fired: previous run still running (Skip) or rejected by a full / paused queue
+    pub fn get_skipped_runs(&self, name: &str) -> u32 {
+        self.entries.lock().unwrap().iter().filter(|entry| entry.task.name == name).map(|entry| entry.skipped_runs).sum()
+    }
+
+    // Stops firing new runs, already fired runs are finished by the workers
+    pub fn stop(mut self) {
+        self.stop_beat();
+    }
+
+    fn stop_beat(&mut self) {
+        if let Some(stop_tx) = self.stop_tx.take() {
+            let _ = stop_tx.send(());
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join(); // Beat thread drops its executor clone
+        }
+    }
+}
+
+impl Drop for BeatHandle {
+    fn drop(&mut self) {
+        self.stop_beat();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::atomic::AtomicUsize, time::Instant};
+
+    use futures_timer::Delay;
+
+    use super::*;
+    use crate::core::proxy::Proxy;
+
+    fn noop_task(name: &str, interval: Duration) -> PeriodicTask {
+        PeriodicTask::every(name, interval, || async {})
+    }
+
+    #[test]
+    fn test_skip_policy_should_fire_once_for_missed_runs() {
+        let start = Utc::now();
+        let mut entry = BeatEntry::new(noop_task("cleanup", Duration::from_secs(10)), start);
+
+        assert_eq!(entry.due_runs(start), 0);
+
+        // Beat thread was late for 3 runs
+        assert_eq!(entry.due_runs(start + TimeDelta::seconds(35)), 1);
+        assert_eq!(entry.next_fire, Some(start + TimeDelta::seconds(40)));
+    }
+
+    #[test]
+    fn test_catch_up_policy_should_fire_every_missed_run() {
+        let start = Utc::now();
+        let task = noop_task("cleanup", Duration::from_secs(10)).with_missed_run_policy(MissedRunPolicy::CatchUp);
+        let mut entry = BeatEntry::new(task, start);
+
+        assert_eq!(entry.due_runs(start + TimeDelta::seconds(35)), 3);
+        assert_eq!(entry.due_runs(start + TimeDelta::seconds(39)), 0);
+        assert_eq!(entry.next_fire, Some(start + TimeDelta::seconds(40)));
+    }
+
+    #[test]
+    fn test_cron_task_should_list_upcoming_fire_times() {
+        let task = PeriodicTask::cron("report", "0 30 14 * * *", || async {}).unwrap();
+        let from = DateTime::parse_from_rfc3339("2024-05-01T15:00:00Z").unwrap().with_timezone(&Utc);
+
+        let fire_times: Vec<String> = task.upcoming(from, 2).iter().map(|time| time.to_rfc3339()).collect();
+
+        assert_eq!(fire_times, vec!["2024-05-02T14:30:00+00:00", "2024-05-03T14:30:00+00:00"]);
+    }
+
+    #[test]
+    fn test_invalid_cron_expression_should_return_error() {
+        let result = PeriodicTask::cron("report", "every monday", || async {});
+
+        assert!(matches!(result, Err(ExecutorError::InvalidSchedule)));
+    }
+
+    #[test]
+    fn test_beat_should_fire_interval_task_through_executor() {
+        let mut proxy = Proxy::new().unwrap();
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_clone = counter.clone();
+
+        let beat = BeatScheduler::new()
+            .register(PeriodicTask::every("tick", Duration::from_millis(100), move || {
+                let counter = counter_clone.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }
+            }))
+            .register(noop_task("idle", Duration::from_secs(60)));
+        let beat_handle = proxy.beat(beat).unwrap();
+
+        let next_fire_times = beat_handle.next_fire_times();
+        assert_eq!(next_fire_times.len(), 2);
+        assert_eq!(next_fire_times[0].0, "tick");
+
+        thread::sleep(Duration::from_millis(450));
+        beat_handle.stop();
+        proxy.await_completion().unwrap();
+
+        let runs = counter.load(Ordering::SeqCst);
+        assert!((3..=5).contains(&runs), "runs: {}", runs);
+        assert_eq!(proxy.get_metrics().get_tasks_count(), runs as u32);
+    }
+
+    #[test]
+    fn test_beat_should_not_overlap_running_task() {
+        let mut proxy = Proxy::new().unwrap();
+        let started = Arc::new(AtomicUsize::new(0));
+        let started_clone = started.clone();
+
+        let beat = BeatScheduler::new().register(PeriodicTask::every("slow", Duration::from_millis(50), move || {
+            let started = started_clone.clone();
+            async move {
+                started.fetch_add(1, Ordering::SeqCst);
+                Delay::new(Duration::from_millis(300)).await;
+            }
+        }));
+        let beat_handle = proxy.beat(beat).unwrap();
+
+        thread::sleep(Duration::from_millis(400));
+        beat_handle.stop();
+        proxy.await_completion().unwrap();
+
+        // Without overlap prevention it would be started ~8 times
+        assert!(started.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[test]
+    fn test_catch_up_runs_should_wait_for_running_task_instead_of_being_dropped() {
+        let shared_executor = AsyncExecutor::new().unwrap();
+        let executor = shared_executor.lock().unwrap().clone();
+        let started = Arc::new(AtomicUsize::new(0));
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+
+        let (started_clone, running_clone, max_running_clone) = (started.clone(), running.clone(), max_running.clone());
+        let task = PeriodicTask::every("report", Duration::from_secs(10), move || {
+            let (started, running, max_running) = (started_clone.clone(), running_clone.clone(), max_running_clone.clone());
+            async move {
+                started.fetch_add(1, Ordering::SeqCst);
+                max_running.fetch_max(running.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+                Delay::new(Duration::from_millis(100)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+            }
+        }).with_missed_run_policy(MissedRunPolicy::CatchUp);
+
+        let start = Utc::now();
+        let entries: BeatEntries = Arc::new(Mutex::new(vec![BeatEntry::new(task, start)]));
+
+        // Beat thread was late for 3 runs => the first one is fired, the other two wait for it
+        let wait = BeatScheduler::fire_due(&entries, &executor, start + TimeDelta::seconds(35));
+        assert_eq!(entries.lock().unwrap()[0].pending_runs, 2);
+        assert!(wait <= BeatScheduler::PENDING_RUN_CHECK);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while entries.lock().unwrap()[0].pending_runs > 0 && Instant::now() < deadline {
+            thread::sleep(BeatScheduler::fire_due(&entries, &executor, start + TimeDelta::seconds(35)));
+        }
+        drop(executor);
+        shared_executor.lock().unwrap().wait_all().unwrap();
+
+        assert_eq!(started.load(Ordering::SeqCst), 3);
+        assert_eq!(max_running.load(Ordering::SeqCst), 1); // One after another
+    }
+
+    #[test]
+    fn test_skip_policy_should_drop_run_while_task_is_running() {
+        let shared_executor = AsyncExecutor::new().unwrap();
+        let executor = shared_executor.lock().unwrap().clone();
+        let started = Arc::new(AtomicUsize::new(0));
+        let started_clone = started.clone();
+
+        let task = PeriodicTask::every("report", Duration::from_secs(10), move || {
+            let started = started_clone.clone();
+            async move {
+                started.fetch_add(1, Ordering::SeqCst);
+                Delay::new(Duration::from_millis(200)).await;
+            }
+        });
+
+        let start = Utc::now();
+        let entries: BeatEntries = Arc::new(Mutex::new(vec![BeatEntry::new(task, start)]));
+
+        BeatScheduler::fire_due(&entries, &executor, start + TimeDelta::seconds(10));
+        BeatScheduler::fire_due(&entries, &executor, start + TimeDelta::seconds(20)); // First run is still running
+        assert_eq!(entries.lock().unwrap()[0].pending_runs, 0);
+        assert_eq!(entries.lock().unwrap()[0].skipped_runs, 1);
+
+        drop(executor);
+        shared_executor.lock().unwrap().wait_all().unwrap();
+        assert_eq!(started.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_full_paused_queue_should_skip_runs_instead_of_blocking_the_beat() {
+        let config = crate::executor_config::ExecutorConfigBuilder::new()
+            .with_worker_count(1)
+            .with_queue_capacity(1)
+            .with_rate_limit_per_sec(100)
+            .build()
+            .unwrap();
+        let shared_executor = AsyncExecutor::with_config(config).unwrap();
+        let executor = shared_executor.lock().unwrap().clone();
+        executor.pause().unwrap(); // Nothing leaves the queue => the second run finds it full
+
+        let beat = BeatScheduler::new().register(noop_task("tick", Duration::from_millis(20)).allow_overlap());
+        let beat_handle = beat.start(executor).unwrap();
+        thread::sleep(Duration::from_millis(200));
+
+        let start = Instant::now();
+        assert_eq!(beat_handle.next_fire_times().len(), 1);
+        assert!(beat_handle.get_skipped_runs("tick") > 0);
+        beat_handle.stop();
+        assert!(start.elapsed() < Duration::from_millis(500));
+
+        let mut shared_executor = shared_executor.lock().unwrap();
+        shared_executor.resume().unwrap();
+        shared_executor.wait_all().unwrap();
+    }
+}
@@ -0,0 +1,161 @@
+use std::{cmp::Ordering, collections::BinaryHeap, fmt, sync::{Arc, Condvar, Mutex}, thread, time::Instant};
+
+use crate::core::{executor::AsyncExecutor, job::Job};
+use crate::error_handler::error_handler::{fail_gracefully, ExecutorError};
+use crate::priority::priority::Priority;
+use crate::rate_limiting::slot_rate_limiter::SlotRateLimiter;
+
+use super::types::SchedulerState;
+
+/*
+    Timer heap for jobs that should run later (countdown / eta).
+    - Jobs wait here, not in the workers => they don't take a worker slot and task_timeout starts when the worker gets them
+    - One thread sleeps till the nearest due time, then releases the job through the SlotRateLimiter into the worker channel
+    - Each scheduled job keeps its own executor clone (sender) => channel stays open and wait_all() waits for scheduled jobs,
+      but when the heap is empty the scheduler doesn't hold the channel open
+    - shutdown() stops the scheduler, waiting jobs are cancelled instead of keeping the shutdown waiting for their due time
+*/
+pub struct DelayedScheduler {
+    state: SchedulerState,
+}
+
+struct ScheduledJob {
+    due: Instant,
+    sequence: u64, // Jobs with same due time are released in the order they were scheduled
+    priority: Priority,
+    job: Job,
+    executor: AsyncExecutor,
+}
+
+// BinaryHeap is a max-heap => ordering is reversed so the earliest job is on top
+impl Ord for ScheduledJob {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.due.cmp(&self.due).then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+impl PartialOrd for ScheduledJob {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for ScheduledJob {
+    fn eq(&self, other: &Self) -> bool {
+        self.due == other.due && self.sequence == other.sequence
+    }
+}
+
+impl Eq for ScheduledJob {}
+
+pub struct SchedulerQueue {
+    jobs: BinaryHeap<ScheduledJob>,
+    next_sequence: u64,
+    stopped: bool,
+}
+
+impl DelayedScheduler {
+    pub fn start() -> Result<DelayedScheduler, ExecutorError> {
+        let queue = SchedulerQueue {
+            jobs: BinaryHeap::new(),
+            next_sequence: 0,
+            stopped: false,
+        };
+        let state: SchedulerState = Arc::new((Mutex::new(queue), Condvar::new()));
+
+        let thread_state = state.clone();
+        thread::Builder::new()
+            .name(String::from("executor-scheduler"))
+            .spawn(move || DelayedScheduler::run(thread_state))
+            .map_err(|source| ExecutorError::Io { operation: "start scheduler", source })?;
+
+        Ok(DelayedScheduler { state })
+    }
+
+    // Stopped by the shutdown => the job is dropped (its handle gets Cancelled)
+    pub fn schedule(&self, due: Instant, priority: Priority, job: Job, executor: AsyncExecutor) -> Result<(), ExecutorError> {
+        let (queue, changed) = &*self.state;
+        let mut queue = queue.lock().unwrap();
+        if queue.stopped {
+            return Err(ExecutorError::ChannelConnectionIsNotEstablished);
+        }
+
+        let sequence = queue.next_sequence;
+        queue.next_sequence += 1;
+        queue.jobs.push(ScheduledJob { due, sequence, priority, job, executor });
+
+        changed.notify_one(); // New job can be earlier than the one the thread is sleeping for
+        Ok(())
+    }
+
+    /*
+        Shutdown => the thread stops and the waiting jobs are returned instead of being released later.
+        Their executor clones are dropped with them, so they don't keep the channel open till their due time.
+    */
+    pub fn stop(&self) -> Vec<Job> {
+        let (queue, changed) = &*self.state;
+        let mut queue = queue.lock().unwrap();
+        queue.stopped = true;
+        changed.notify_one();
+
+        let scheduled = std::mem::take(&mut queue.jobs);
+        drop(queue); // Executor clone can be the last one => its Drop locks the heap
+        scheduled.into_iter().map(|scheduled| scheduled.job).collect()
+    }
+
+    pub fn get_pending(&self) -> usize {
+        self.state.0.lock().unwrap().jobs.len()
+    }
+
+    fn run(state: SchedulerState) {
+        let (queue, changed) = &*state;
+        let mut guard = queue.lock().unwrap();
+
+        loop {
+            if guard.stopped {
+                break;
+            }
+
+            let now = Instant::now();
+            let next_due = guard.jobs.peek().map(|scheduled| scheduled.due);
+
+            match next_due {
+                Some(due) if due <= now => {
+                    let ScheduledJob { priority, job, executor, .. } = guard.jobs.pop().unwrap();
+
+                    // Rate limiter can block, so the heap is unlocked while the job is released
+                    drop(guard);
+                    // Fails only when the executor is shut down, the job is dropped => its handle gets Cancelled
+                    if let Err(error) = SlotRateLimiter::delay_rate_limited(&executor, priority, job) {
+                        fail_gracefully(error, "Scheduled task is dropped, executor is shut down");
+                    }
+
+                    // Can be the last executor clone => Drop of the scheduler locks the heap, so it must happen before lock()
+                    drop(executor);
+                    guard = queue.lock().unwrap();
+                }
+                Some(due) => {
+                    guard = changed.wait_timeout(guard, due - now).unwrap().0;
+                }
+                None => {
+                    guard = changed.wait(guard).unwrap();
+                }
+            }
+        }
+    }
+}
+
+// Last executor clone is gone => stop the thread (scheduled jobs hold clones, so the heap is already empty here)
+impl Drop for DelayedScheduler {
+    fn drop(&mut self) {
+        let (queue, changed) = &*self.state;
+        queue.lock().unwrap().stopped = true;
+        changed.notify_one();
+    }
+}
+
+impl fmt::Debug for DelayedScheduler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DelayedScheduler").field("pending", &self.get_pending()).finish()
+    }
+}
@@ -0,0 +1,3 @@
+pub mod delayed_scheduler;
+pub mod beat_scheduler;
+pub mod types;
\ No newline at end of file
@@ -0,0 +1,17 @@
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+
+use crate::core::types::Task;
+
+use super::{beat_scheduler::BeatEntry, delayed_scheduler::{DelayedScheduler, SchedulerQueue}};
+
+// Heap of the scheduled jobs + Condvar to wake the scheduler thread when an earlier job is added or the scheduler is stopped
+pub type SchedulerState = Arc<(Mutex<SchedulerQueue>, Condvar)>;
+
+// Shared by all executor clones, the scheduler thread is started with the first delayed job
+pub type DelayedSchedulerArc = Arc<OnceLock<DelayedScheduler>>;
+
+// Creates a new future for every run of a periodic task
+pub type BeatTaskFactory = Arc<dyn Fn() -> Task + Send + Sync + 'static>;
+
+// Shared between the beat thread (fires the tasks) and the BeatHandle (lists next fire times)
+pub type BeatEntries = Arc<Mutex<Vec<BeatEntry>>>;
@@ -0,0 +1,173 @@
+use std::time::Duration;
+
+use crate::error_handler::error_handler::ExecutorError;
+use crate::future_executors::future_status::FutureStatus;
+use crate::middleware::task_context::TaskContext;
+
+/*
+    Span of one task attempt, created when the job reaches its queue and carried by the Job to the worker.
+    - With the `tracing` feature: "task" span (id, name, priority, queue, attempt) that is entered on every poll,
+      the phases (scheduled, rate limit wait, enqueued, started, finished) are events of the span
+    - Without the feature every method is a no-op and TaskSpan has no size
+*/
+#[derive(Debug, Clone)]
+pub struct TaskSpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+#[cfg(feature = "tracing")]
+impl TaskSpan {
+    pub fn new(task: &TaskContext) -> TaskSpan {
+        let span = tracing::info_span!(
+            "task",
+            id = task.task_id.get_value(),
+            name = task.name.as_deref(),
+            priority = ?task.priority,
+            queue = task.queue.as_deref(),
+            attempt = task.attempt,
+        );
+        TaskSpan { span }
+    }
+
+    // Job that did not reach a queue yet
+    pub fn none() -> TaskSpan {
+        TaskSpan { span: tracing::Span::none() }
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.span.is_none()
+    }
+
+    pub fn scheduled(&self, delay: Duration) {
+        tracing::info!(parent: &self.span, delay_ms = delay.as_millis() as u64, "task scheduled");
+    }
+
+    pub fn rate_limited(&self) {
+        tracing::info!(parent: &self.span, "task waits for the rate limit");
+    }
+
+    pub fn rate_limit_passed(&self, waited: Duration) {
+        tracing::info!(parent: &self.span, waited_ms = waited.as_millis() as u64, "task passed the rate limit");
+    }
+
+    pub fn enqueued(&self) {
+        tracing::info!(parent: &self.span, "task enqueued");
+    }
+
+    // Queue is full or closed
+    pub fn not_enqueued(&self, error: &ExecutorError) {
+        tracing::warn!(parent: &self.span, %error, "task was not enqueued");
+    }
+
+    // `queued` => how long the job waited in the channel for a worker
+    pub fn started(&self, queued: Option<Duration>) {
+        tracing::info!(parent: &self.span, queued_ms = queued.map(|queued| queued.as_millis() as u64), "task started");
+    }
+
+    pub fn finished(&self, status: &FutureStatus) {
+        match status.is_failed() {
+            true => tracing::warn!(parent: &self.span, state = ?status.state, panicked = status.panicked, error = status.error.as_deref(), "task finished"),
+            false => tracing::info!(parent: &self.span, state = ?status.state, execution_time_ms = status.execution_time.as_millis() as u64, "task finished"),
+        }
+    }
+
+    pub fn in_scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.span.in_scope(f)
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+impl TaskSpan {
+    pub fn new(_task: &TaskContext) -> TaskSpan {
+        TaskSpan {}
+    }
+
+    pub fn none() -> TaskSpan {
+        TaskSpan {}
+    }
+
+    pub fn is_none(&self) -> bool {
+        true
+    }
+
+    pub fn scheduled(&self, _delay: Duration) {}
+
+    pub fn rate_limited(&self) {}
+
+    pub fn rate_limit_passed(&self, _waited: Duration) {}
+
+    pub fn enqueued(&self) {}
+
+    pub fn not_enqueued(&self, _error: &ExecutorError) {}
+
+    pub fn started(&self, _queued: Option<Duration>) {}
+
+    pub fn finished(&self, _status: &FutureStatus) {}
+
+    pub fn in_scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        f()
+    }
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use crate::core::proxy::Proxy;
+    use crate::executor_config::{ExecutorConfig, ExecutorConfigBuilder};
+    use crate::priority::priority::Priority;
+
+    #[derive(Clone, Default)]
+    struct Output(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Output {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_task_span_should_wrap_the_phases_and_the_polls() {
+        let output = Output::default();
+        let writer = output.clone();
+        tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .without_time()
+            .init(); // Global => the worker threads use it too
+
+        let mut proxy = Proxy::new().unwrap();
+        proxy.add_queue("emails", ExecutorConfig::default()).unwrap();
+        proxy.add_queue("reports", ExecutorConfigBuilder::new().with_rate_limit_per_sec(1).build().unwrap()).unwrap();
+        proxy.set_router(crate::routing::router::Router::new().route_task("send_email", "emails").route_task("build_report", "reports")).unwrap();
+
+        let id = proxy.task_named("send_email", async { tracing::info!("inside the task") }, Priority::High).unwrap();
+        proxy.task_after(async {}, Priority::None, Duration::from_millis(10)).unwrap();
+        proxy.task_named("build_report", async {}, Priority::None).unwrap();
+        let throttled_id = proxy.task_named("build_report", async {}, Priority::None).unwrap(); // Second one in the same second waits
+        proxy.await_completion().unwrap();
+
+        let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        let span = format!("task{{id={} name=\"send_email\" priority=High queue=\"emails\" attempt=1}}", id.get_value());
+        let events_of_task: Vec<&str> = output.lines().filter(|line| line.contains(&span)).collect();
+
+        for event in ["task enqueued", "task started", "inside the task", "task finished"] {
+            assert!(events_of_task.iter().any(|line| line.contains(event)), "{} is missing in:\n{}", event, output);
+        }
+        assert!(output.lines().any(|line| line.contains("queue=\"default\"") && line.contains("task scheduled")), "{}", output);
+
+        let throttled_span = format!("task{{id={} name=\"build_report\" priority=None queue=\"reports\" attempt=1}}", throttled_id.get_value());
+        let events_of_throttled: Vec<&str> = output.lines().filter(|line| line.contains(&throttled_span)).collect();
+        for event in ["task waits for the rate limit", "task passed the rate limit", "task finished"] {
+            assert!(events_of_throttled.iter().any(|line| line.contains(event)), "{} is missing in:\n{}", event, output);
+        }
+    }
+}
@@ -1,8 +1,8 @@
 
-use std::{thread::JoinHandle, time::Duration};
+use std::{io, thread::JoinHandle, time::Duration};
 
 pub trait BaseWorker {
-    fn spawn_thread(self, timeout: Duration) -> JoinHandle<()>;
+    fn spawn_thread(self, timeout: Duration) -> io::Result<JoinHandle<()>>;
 }
 
 
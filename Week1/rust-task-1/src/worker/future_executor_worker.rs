@@ -1,67 +1,188 @@
 use super::base_worker::BaseWorker;
+use std::io;
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
-use std::{sync::atomic::Ordering, time::Duration};
+use std::{sync::atomic::Ordering, time::{Duration, Instant}};
+
+use crossbeam::channel::TryRecvError;
 
 use crate::channel::types::{ReceiverType, ShutdownSender};
+use crate::core::executor_types::{ShutdownStateArc, WorkerPoolArc};
+use crate::core::shutdown::ShutdownMode;
 use crate::core::types::{MetricsData, StopFlag};
-use crate::future_executors::future_executor_with_timeout::CustomFutureExecutorTimeout;
-use crate::future_executors::future_types::receive_future_no_output;
+use crate::future_executors::future_status::FutureStatus;
+use crate::future_executors::local_run_queue::LocalRunQueue;
+use crate::error_handler::error_handler::fail_gracefully;
+use crate::lifecycle::task_state::TaskState;
+use crate::result_backend::stored_result::StoredResult;
 
 pub struct FutureExecutorBuilder {
     rx_clone: ReceiverType,
     metrics_clone: MetricsData,
     stop_flag: StopFlag,
     shutdown_arc_sender: Arc<ShutdownSender>,
+    max_tasks: usize, // How many futures this worker runs concurrently
+    shutdown_state: ShutdownStateArc,
+    pool: WorkerPoolArc, // Wait times for the autoscaler, decides if this worker can stop when idle
 }
 
 impl FutureExecutorBuilder {
-    pub fn new(rx_clone: ReceiverType, metrics_clone: MetricsData, stop_flag: StopFlag, shutdown_arc_sender: Arc<ShutdownSender>) -> FutureExecutorBuilder {
+    const STOP_FLAG_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+    pub fn new(rx_clone: ReceiverType, metrics_clone: MetricsData, stop_flag: StopFlag, shutdown_arc_sender: Arc<ShutdownSender>, max_tasks: usize, shutdown_state: ShutdownStateArc, pool: WorkerPoolArc) -> FutureExecutorBuilder {
         FutureExecutorBuilder {
             rx_clone,
             metrics_clone,
             stop_flag,
-            shutdown_arc_sender
+            shutdown_arc_sender,
+            max_tasks,
+            shutdown_state,
+            pool,
+        }
+    }
+
+    // Final state of the task + its result, a failed attempt that is retried is not stored (the tracker keeps it Retrying)
+    // Returns false when the state was not accepted (the attempt is retried)
+    fn finish_task(&self, status: &FutureStatus) -> bool {
+        status.span.finished(status);
+        if !self.pool.get_tracker().transition(status.task_id, status.state) {
+            return false;
+        }
+
+        if let Some(result_backend) = self.pool.get_result_backend()
+            && let Err(error) = result_backend.store(StoredResult::from_status(status, self.pool.get_result_ttl()))
+        {
+            fail_gracefully(error, "Task result was not stored");
+        }
+        true
+    }
+
+    // Final outcome only, Cancelled has no hook
+    fn call_finish_hooks(&self, status: &FutureStatus) {
+        let middleware = self.pool.get_middleware();
+
+        match status.state {
+            TaskState::Succeeded => middleware.on_success(&status.context),
+            TaskState::Failed => middleware.on_failure(&status.context, status.error.as_deref().unwrap_or_default()),
+            TaskState::TimedOut => middleware.on_timeout(&status.context),
+            _ => {}
+        }
+    }
+
+    fn record_statuses(&self, statuses: Vec<FutureStatus>) {
+        for status in statuses {
+            self.shutdown_state.task_finished();
+            if self.finish_task(&status) {
+                self.call_finish_hooks(&status);
+            }
+
+            if status.panicked {
+                self.metrics_clone.lock().unwrap().increment_tasks_panicked();
+            }
+
+            if status.is_failed() {
+                self.metrics_clone.lock().unwrap().increment_tasks_failed();
+            } else {
+                self.metrics_clone.lock().unwrap().increment_total_execution_time(status.execution_time.as_secs() as u32);
+            }
         }
     }
 }
 
 impl BaseWorker for FutureExecutorBuilder {
-    fn spawn_thread(self, timeout: Duration) -> JoinHandle<()> {
-        let handle = thread::spawn(move || {
+    fn spawn_thread(self, timeout: Duration) -> io::Result<JoinHandle<()>> {
+        thread::Builder::new().name(String::from("executor-worker")).spawn(move || {
             // Here i can check if worker Thread is started using Prints
             // let rx = rx_clone.lock().unwrap(); // When i leave this here locks entire receiver for the lifetime of this worker thread others are blocked and this makes my workers work sequentially
-            
+            let mut run_queue = LocalRunQueue::new(self.max_tasks);
+            let mut channel_disconnected = false;
+            let mut idle_since: Option<Instant> = None;
+            let mut retired = false;
+
             while !self.stop_flag.load(Ordering::Relaxed) {
-                match self.rx_clone.recv_timeout(Duration::from_millis(100)) {
-                    Ok(task) => {
-                        println!("\n🛠️  Task is running on thread: {:?}\n",std::thread::current().id());
-                        self.metrics_clone.lock().unwrap().increment_task_count();
+                let shutdown_mode = self.shutdown_state.get_mode();
 
-                        let mut future_exec = CustomFutureExecutorTimeout::new(receive_future_no_output(task));
-                        let status = future_exec.poll_future(timeout); 
+                if shutdown_mode == Some(ShutdownMode::Abort) {
+                    let aborted = run_queue.abort_all();
+                    self.shutdown_state.running_tasks_cancelled(aborted.len());
 
-                        if status.failed {
-                            self.metrics_clone.lock().unwrap().increment_tasks_failed();
-                        } else {
-                            self.metrics_clone.lock().unwrap().increment_total_execution_time(status.execution_time);
-                        }
+                    for status in aborted {
+                        self.finish_task(&status);
                     }
-                    Err(crossbeam::channel::RecvTimeoutError::Timeout) => {
-                        // Timeout hit, check stop flag
-                        continue;
+                    break;
+                }
+
+                // Drain takes the queued tasks till the channel is closed, the other modes don't take new tasks
+                let accepts_new_tasks = matches!(shutdown_mode, None | Some(ShutdownMode::Drain));
+                // Paused => running tasks are finished, queued tasks wait in the channel till resume()
+                let takes_new_tasks = accepts_new_tasks && !self.pool.is_paused();
+
+                // Take new tasks from the channel while this worker has free capacity, the rest stay for the other workers
+                while takes_new_tasks && !channel_disconnected && run_queue.has_capacity() {
+                    match self.rx_clone.try_recv() {
+                        Ok(task) => {
+                            let queued = task.queued_at.map(|queued_at| queued_at.elapsed());
+                            if let Some(queued) = queued {
+                                self.pool.job_waited(queued);
+                            }
+                            task.span.started(queued);
+                            idle_since = None;
+                            self.metrics_clone.lock().unwrap().increment_task_count();
+
+                            self.shutdown_state.task_started();
+                            self.pool.get_tracker().transition(task.id, TaskState::Running);
+
+                            let middleware = self.pool.get_middleware();
+                            let task = match middleware.is_empty() {
+                                true => task,
+                                false => {
+                                    let context = task.get_context();
+                                    middleware.on_start(&context);
+                                    task.map_future(|future| middleware.wrap(&context, future))
+                                }
+                            };
+                            let task_timeout = task.timeout.unwrap_or(timeout);
+                            run_queue.spawn(task, task_timeout);
+                        }
+                        Err(TryRecvError::Empty) => break,
+                        Err(TryRecvError::Disconnected) => {
+                            // println!("💀 Channel disconnected");
+                            channel_disconnected = true;
+                        }
                     }
+                }
 
-                    Err(crossbeam::channel::RecvTimeoutError::Disconnected) => {
-                        // println!("💀 Channel disconnected");
+                let finished = run_queue.poll_ready();
+                self.record_statuses(finished);
+
+                let timed_out = run_queue.cancel_timed_out();
+                self.record_statuses(timed_out);
+
+                if (channel_disconnected || !accepts_new_tasks) && run_queue.is_empty() {
+                    break;
+                }
+
+                // Worker over min_workers stops after worker_keep_alive without tasks (not while paused, the queue can be full)
+                if run_queue.is_empty() && shutdown_mode.is_none() && takes_new_tasks {
+                    let idle = idle_since.get_or_insert_with(Instant::now).elapsed();
+
+                    if idle >= self.pool.get_keep_alive() && self.pool.try_retire(idle) {
+                        retired = true;
                         break;
                     }
+                } else {
+                    idle_since = None;
                 }
 
+                // Park till a task is woken, a new task arrives or a deadline is reached
+                let accepts_tasks = takes_new_tasks && !channel_disconnected && run_queue.has_capacity();
+                run_queue.wait(accepts_tasks.then_some(&self.rx_clone), Self::STOP_FLAG_CHECK_INTERVAL);
+            }
+            if !retired {
+                self.pool.worker_stopped(); // Retired worker is already removed from the pool
             }
             let _ = self.shutdown_arc_sender.as_ref().as_ref().unwrap().send(()); // SENDS A SIGNAL WHEN THE THREAD IS CLOSED
-        });
-        handle
+        })
     }
 }
 
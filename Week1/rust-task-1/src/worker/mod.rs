@@ -1,2 +1,3 @@
 pub mod base_worker;
-pub mod future_executor_worker;
\ No newline at end of file
+pub mod future_executor_worker;
+pub mod worker_pool;
\ No newline at end of file
@@ -0,0 +1,267 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::channel::types::ReceiverType;
+use crate::core::executor_types::{ConfigParamsArc, ShutdownSenderArc, ShutdownStateArc};
+use crate::core::types::{MetricsData, ScalingHistoryData, StopFlag, WorkerHandles};
+use crate::error_handler::error_handler::{fail, ExecutorError};
+use crate::lifecycle::{task_tracker::TaskTracker, types::TaskTrackerArc};
+use crate::result_backend::types::ResultBackendArc;
+use crate::middleware::{middleware_chain::MiddlewareChain, types::MiddlewareChainArc};
+use crate::performance_monitoring::{scaling_event::{ScalingEvent, ScalingReason}, scaling_history::ScalingHistory};
+
+use super::base_worker::BaseWorker;
+use super::future_executor_worker::FutureExecutorBuilder;
+
+/*
+    Worker threads of one executor, between min_workers and max_workers.
+    - min_workers are started with the executor and never stop before the shutdown
+    - Autoscaler thread (only when max_workers > min_workers) starts one worker per check while
+      the queue depth or the wait time of the jobs passes the threshold from ExecutorConfig
+    - Worker over min_workers stops by itself after worker_keep_alive without tasks
+    Every change of the pool size is counted in the metrics and kept in the ScalingHistory as ScalingEvent.
+*/
+pub struct WorkerPool {
+    config: ConfigParamsArc,
+    receiver: ReceiverType,
+    metrics: MetricsData,
+    stop_flag: StopFlag,
+    shutdown_ack_tx: ShutdownSenderArc,
+    shutdown_state: ShutdownStateArc,
+    worker_handles: WorkerHandles,
+    live_workers: AtomicUsize,
+    longest_wait_ms: AtomicU64, // Longest wait of a job since the last autoscaler check
+    closed: AtomicBool, // No new workers after wait_all() or shutdown()
+    paused: AtomicBool, // Workers don't take jobs from the channel
+    autoscaler: Mutex<Option<JoinHandle<()>>>,
+    tracker: TaskTrackerArc, // Workers move their tasks to Running and to the final state
+    result_backend: Mutex<Option<ResultBackendArc>>, // Workers store the outcome of finished tasks, set by Proxy::set_result_backend()
+    middleware: MiddlewareChainArc, // Workers call the hooks around every task, the chain of the executor
+    scaling_history: ScalingHistoryData, // Last changes of the pool size, read by Proxy::get_scaling_events()
+}
+
+impl WorkerPool {
+    const SCALE_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+    pub fn new(config: ConfigParamsArc, receiver: ReceiverType, metrics: MetricsData, stop_flag: StopFlag, shutdown_ack_tx: ShutdownSenderArc, shutdown_state: ShutdownStateArc, worker_handles: WorkerHandles) -> WorkerPool {
+        WorkerPool {
+            config,
+            receiver,
+            metrics,
+            stop_flag,
+            shutdown_ack_tx,
+            shutdown_state,
+            worker_handles,
+            live_workers: AtomicUsize::new(0),
+            longest_wait_ms: AtomicU64::new(0),
+            closed: AtomicBool::new(false),
+            paused: AtomicBool::new(false),
+            autoscaler: Mutex::new(None),
+            tracker: Arc::new(TaskTracker::new()),
+            result_backend: Mutex::new(None),
+            middleware: Arc::new(MiddlewareChain::new()),
+            scaling_history: Arc::new(Mutex::new(ScalingHistory::new())),
+        }
+    }
+
+    // Tracker of the executor, without it the states are kept only in the pool
+    pub fn with_tracker(mut self, tracker: TaskTrackerArc) -> WorkerPool {
+        self.tracker = tracker;
+        self
+    }
+
+    pub fn with_middleware(mut self, middleware: MiddlewareChainArc) -> WorkerPool {
+        self.middleware = middleware;
+        self
+    }
+
+    // History of the executor, without it the events are kept only in the pool
+    pub fn with_scaling_history(mut self, scaling_history: ScalingHistoryData) -> WorkerPool {
+        self.scaling_history = scaling_history;
+        self
+    }
+
+    // Starts min_workers and the autoscaler
+    pub fn start(self: &Arc<Self>) -> Result<(), ExecutorError> {
+        let min_workers = self.config.get_min_workers();
+        println!("\n===> Workers allowed: {} (3/4 of total CPU cores by default), up to {} under load\n", min_workers, self.config.get_max_workers());
+
+        for _ in 0..min_workers {
+            self.spawn_worker()?;
+        }
+        self.metrics.lock().unwrap().set_pool_size(min_workers);
+
+        if self.config.is_autoscaling() {
+            let pool = self.clone();
+            let handle = thread::Builder::new()
+                .name(String::from("executor-autoscaler"))
+                .spawn(move || pool.run_autoscaler())
+                .map_err(|source| ExecutorError::Io { operation: "start autoscaler", source })?;
+
+            *self.autoscaler.lock().unwrap() = Some(handle);
+        }
+        Ok(())
+    }
+
+    fn spawn_worker(self: &Arc<Self>) -> Result<(), ExecutorError> {
+        // Handles are locked while the worker is started, so close() + join can't miss a new worker
+        let mut handles = self.worker_handles
+            .lock()
+            .unwrap_or_else(|_| fail(ExecutorError::Fail, String::from("Failed when tried to add spawn handle to worker_handles!")));
+
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(ExecutorError::ChannelConnectionIsNotEstablished);
+        }
+
+        let future_executor = FutureExecutorBuilder::new(self.receiver.clone(), self.metrics.clone(), self.stop_flag.clone(), self.shutdown_ack_tx.clone(), self.config.get_max_tasks_per_worker(), self.shutdown_state.clone(), self.clone());
+
+        self.live_workers.fetch_add(1, Ordering::SeqCst);
+        match future_executor.spawn_thread(self.config.get_task_timeout()) {
+            Ok(handle) => {
+                handles.push(handle);
+                Ok(())
+            }
+            Err(source) => {
+                self.live_workers.fetch_sub(1, Ordering::SeqCst);
+                Err(ExecutorError::Io { operation: "spawn worker", source })
+            }
+        }
+    }
+
+    fn run_autoscaler(self: Arc<Self>) {
+        // Stops with the pool, or when all workers stopped because the channel was closed
+        while !self.is_closed() && self.get_live_workers() > 0 {
+            thread::sleep(WorkerPool::SCALE_CHECK_INTERVAL);
+
+            if self.is_paused() {
+                continue; // Queue grows because of the pause, not because of the load
+            }
+
+            let Some(reason) = self.scale_up_reason() else { continue };
+            if self.get_live_workers() >= self.config.get_max_workers() {
+                continue;
+            }
+
+            self.join_stopped_workers();
+            if self.spawn_worker().is_ok() {
+                self.record_scaling_event(ScalingEvent::new(self.get_live_workers(), reason));
+            }
+        }
+    }
+
+    fn scale_up_reason(&self) -> Option<ScalingReason> {
+        let queued = self.receiver.len();
+        let longest_wait = Duration::from_millis(self.longest_wait_ms.swap(0, Ordering::SeqCst));
+
+        if queued >= self.config.get_scale_up_queue_depth() {
+            Some(ScalingReason::QueueDepth(queued))
+        } else if longest_wait >= self.config.get_scale_up_wait_time() {
+            Some(ScalingReason::WaitTime(longest_wait))
+        } else {
+            None
+        }
+    }
+
+    // Workers that stopped when idle => their handles are not kept till the shutdown
+    fn join_stopped_workers(&self) {
+        let mut handles = self.worker_handles.lock().unwrap();
+        let (stopped, running): (Vec<_>, Vec<_>) = handles.drain(..).partition(|handle| handle.is_finished());
+        *handles = running;
+
+        for handle in stopped {
+            let _ = handle.join();
+        }
+    }
+
+    // Called by a worker when it receives a job
+    pub fn job_waited(&self, wait: Duration) {
+        self.longest_wait_ms.fetch_max(wait.as_millis() as u64, Ordering::SeqCst);
+    }
+
+    // Called by an idle worker, true when the worker can stop (pool stays at min_workers)
+    pub fn try_retire(&self, idle: Duration) -> bool {
+        let min_workers = self.config.get_min_workers();
+        let retired = self.live_workers
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |live| (live > min_workers).then(|| live - 1))
+            .is_ok();
+
+        if retired {
+            self.record_scaling_event(ScalingEvent::new(self.get_live_workers(), ScalingReason::Idle(idle)));
+        }
+        retired
+    }
+
+    fn record_scaling_event(&self, event: ScalingEvent) {
+        self.metrics.lock().unwrap().record_scaling_event(event);
+        self.scaling_history.lock().unwrap().record(event);
+    }
+
+    // Called by a worker that stops because of the shutdown or a closed channel
+    pub fn worker_stopped(&self) {
+        self.live_workers.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    pub fn get_tracker(&self) -> &TaskTrackerArc {
+        &self.tracker
+    }
+
+    pub fn get_middleware(&self) -> &MiddlewareChainArc {
+        &self.middleware
+    }
+
+    pub fn set_result_backend(&self, result_backend: ResultBackendArc) {
+        *self.result_backend.lock().unwrap() = Some(result_backend);
+    }
+
+    pub fn get_result_backend(&self) -> Option<ResultBackendArc> {
+        self.result_backend.lock().unwrap().clone()
+    }
+
+    pub fn get_result_ttl(&self) -> Duration {
+        self.config.get_result_ttl()
+    }
+
+    pub fn get_keep_alive(&self) -> Duration {
+        self.config.get_worker_keep_alive()
+    }
+
+    pub fn get_live_workers(&self) -> usize {
+        self.live_workers.load(Ordering::SeqCst)
+    }
+
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    // No new workers from now on, waits for the autoscaler to stop
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+
+        if let Some(handle) = self.autoscaler.lock().unwrap().take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl std::fmt::Debug for WorkerPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkerPool")
+            .field("live_workers", &self.get_live_workers())
+            .field("closed", &self.is_closed())
+            .finish()
+    }
+}
@@ -2,10 +2,15 @@
 
 #[cfg(test)]
 mod integration_tests {
-    use std::time::Duration;
+    use std::process::{Command, Stdio};
+    use std::sync::{atomic::{AtomicUsize, Ordering}, Arc, Mutex};
+    use std::time::{Duration, Instant};
+    use std::{env, fs, thread};
 
     use futures_timer::Delay;
-    use rust_task_1::{core::proxy::Proxy, priority::priority::Priority};
+    use rust_task_1::{core::{proxy::Proxy, types::FallibleTask}, durability::write_ahead_log::WriteAheadLog, error_handler::error_handler::{ExecutorError, TaskError}, priority::priority::Priority};
+    use rust_task_1::{registry::task_registry::TaskRegistry, task};
+    use serde::{Deserialize, Serialize};
 
     pub async fn send_email() {
         println!("Sending an email....");
@@ -15,27 +20,173 @@ mod integration_tests {
     
     #[test]
     fn test_proxy_full_workflow() {
-        let mut executor = Proxy::new();
-        executor.task(send_email(), Priority::None);
-        executor.task(send_email(), Priority::None);
-        executor.task(send_email(), Priority::None);
-        executor.task(send_email(), Priority::None);
-        executor.task(send_email(), Priority::None);
-        executor.task(send_email(), Priority::Low);
-
-        executor.await_completion();
+        let mut executor = Proxy::new().unwrap();
+        executor.task(send_email(), Priority::None).unwrap();
+        executor.task(send_email(), Priority::None).unwrap();
+        executor.task(send_email(), Priority::None).unwrap();
+        executor.task(send_email(), Priority::None).unwrap();
+        executor.task(send_email(), Priority::None).unwrap();
+        executor.task(send_email(), Priority::Low).unwrap();
+
+        executor.await_completion().unwrap();
         executor.metrics();
-        executor.force_shutdown();
+        executor.force_shutdown().unwrap();
 
-        let mut executor = Proxy::new();
-        executor.task(send_email(), Priority::None);
-        executor.task(send_email(), Priority::None);
-        executor.task(send_email(), Priority::Low);
+        let mut executor = Proxy::new().unwrap();
+        executor.task(send_email(), Priority::None).unwrap();
+        executor.task(send_email(), Priority::None).unwrap();
+        executor.task(send_email(), Priority::Low).unwrap();
 
-        executor.await_completion();
+        executor.await_completion().unwrap();
         executor.metrics();
-        executor.force_shutdown();
+        executor.force_shutdown().unwrap();
         assert!(true);
     }
 
-}
+    const WAL_CHILD_ENV: &str = "RUST_TASK_1_WAL_CHILD_PATH";
+
+    // Every "sleep" task waits longer than the test, so none of them is done when the child is killed
+    fn wal_factory(started: Arc<AtomicUsize>, sleep: Duration) -> impl Fn(&str, &serde_json::Value) -> Result<FallibleTask, ExecutorError> + Send + Sync {
+        move |name: &str, _args: &serde_json::Value| -> Result<FallibleTask, ExecutorError> {
+            if name != "sleep" {
+                return Err(ExecutorError::UnknownTaskName(name.to_string()));
+            }
+            let started = started.clone();
+            Ok(Box::pin(async move {
+                started.fetch_add(1, Ordering::SeqCst);
+                Delay::new(sleep).await;
+                Ok(())
+            }))
+        }
+    }
+
+    // Child process of test_wal_should_survive_killed_process, does nothing when it is run by cargo test
+    #[test]
+    #[ignore]
+    fn wal_crash_child() {
+        let Ok(path) = env::var(WAL_CHILD_ENV) else { return };
+        let mut executor = Proxy::new().unwrap();
+        executor.enable_durability(path, wal_factory(Arc::new(AtomicUsize::new(0)), Duration::from_secs(60))).unwrap();
+
+        for n in 0..10_000 {
+            executor.submit_durable("sleep", n, Priority::None).unwrap();
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_wal_should_survive_killed_process() {
+        let dir = env::temp_dir().join(format!("rust-task-1-wal-crash-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("tasks.wal");
+        let logged_tasks = || fs::read_to_string(&path).map(|log| log.lines().count()).unwrap_or(0);
+
+        let mut child = Command::new(env::current_exe().unwrap())
+            .args(["integration_tests::wal_crash_child", "--exact", "--ignored", "--nocapture"])
+            .env(WAL_CHILD_ENV, &path)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(30);
+        while logged_tasks() < 20 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        child.kill().unwrap(); // Killed in the middle of the submissions
+        child.wait().unwrap();
+        assert!(logged_tasks() >= 20);
+
+        let started = Arc::new(AtomicUsize::new(0));
+        let mut executor = Proxy::new().unwrap();
+        let replayed = executor.enable_durability(&path, wal_factory(started.clone(), Duration::from_millis(10))).unwrap();
+        executor.await_completion().unwrap();
+
+        assert!(replayed >= 20);
+        assert_eq!(started.load(Ordering::SeqCst), replayed);
+        assert!(WriteAheadLog::open(&path).unwrap().get_pending().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct WelcomeArgs {
+        to: String,
+    }
+
+    static WELCOME_ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+    static ADDED: AtomicUsize = AtomicUsize::new(0);
+
+    /// First attempt fails, the retry succeeds
+    #[task(name = "send_welcome_email", priority = High, retries = 2, timeout = "2s", rate_limit = "2/s")]
+    async fn send_welcome_email(args: WelcomeArgs) -> Result<(), String> {
+        if WELCOME_ATTEMPTS.fetch_add(1, Ordering::SeqCst) == 0 {
+            return Err(format!("mail server is down, {} is not welcomed yet", args.to));
+        }
+        Ok(())
+    }
+
+    #[task]
+    async fn add(a: usize, b: usize) {
+        ADDED.fetch_add(a + b, Ordering::SeqCst);
+    }
+
+    #[task(timeout = "50ms")]
+    async fn sleepy() {
+        Delay::new(Duration::from_secs(5)).await;
+    }
+
+    #[test]
+    fn test_task_macro_should_register_and_delay_typed_tasks() {
+        let config = send_welcome_email::config();
+        assert_eq!(send_welcome_email::NAME, "send_welcome_email");
+        assert_eq!(config.priority, Priority::High);
+        assert_eq!(config.retries, 2);
+        assert_eq!(config.timeout, Some(Duration::from_secs(2)));
+        assert_eq!(config.rate_limit.unwrap().get_count(), 2);
+        assert_eq!(add::NAME, "add");
+
+        let mut executor = Proxy::new().unwrap();
+        let not_registered = executor.send_task("add", (1, 2)).err().unwrap();
+        assert!(matches!(not_registered.root_cause(), ExecutorError::UnknownTaskName(_)));
+
+        let mut registry = TaskRegistry::new();
+        send_welcome_email.register(&mut registry);
+        add.register(&mut registry);
+        sleepy.register(&mut registry);
+        executor.set_registry(registry);
+
+        let welcome = send_welcome_email.delay(&mut executor, WelcomeArgs { to: String::from("satoshi@example.com") }).unwrap();
+        let added = add.delay(&mut executor, 2, 3).unwrap();
+        let timed_out = sleepy.delay(&mut executor).unwrap();
+
+        assert_eq!(welcome.join(), Ok(()));
+        assert_eq!(WELCOME_ATTEMPTS.load(Ordering::SeqCst), 2);
+        assert_eq!(added.join(), Ok(()));
+        assert_eq!(ADDED.load(Ordering::SeqCst), 5);
+        assert_eq!(timed_out.join(), Err(TaskError::Timeout));
+    }
+
+    #[test]
+    fn test_task_rate_limit_should_delay_the_starts_over_the_limit() {
+        static STARTS: Mutex<Vec<Instant>> = Mutex::new(Vec::new());
+
+        #[task(rate_limit = "2/s")]
+        async fn rate_limited_start() -> Result<(), String> {
+            STARTS.lock().unwrap().push(Instant::now());
+            Ok(())
+        }
+
+        let mut registry = TaskRegistry::new();
+        rate_limited_start.register(&mut registry);
+        let mut executor = Proxy::new().unwrap();
+        executor.set_registry(registry);
+
+        let handles: Vec<_> = (0..3).map(|_| rate_limited_start.delay(&mut executor).unwrap()).collect();
+        assert!(handles.into_iter().all(|handle| handle.join().is_ok()));
+
+        let mut starts = STARTS.lock().unwrap().clone();
+        starts.sort();
+        assert_eq!(starts.len(), 3);
+        assert!(starts[1] - starts[0] < Duration::from_millis(900));
+        assert!(starts[2] - starts[0] >= Duration::from_millis(950)); // Third start waits for the first one to leave the window
+    }}